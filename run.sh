@@ -6,9 +6,9 @@ set -e # Exit immediately if a command exits with a non-zero status.
 echo "Compiling C runtime..."
 gcc -c src/runtime/runtime.c -o runtime.o
 
-# Run the Rust program and capture its output (assembly) to a file
+# Compile the program (by default samples/example1.lisp) to assembly
 echo "Running Rust program to generate assembly..."
-cargo run >output.s
+cargo run -- emit-asm "${1:-samples/example1.lisp}" --target aarch64-apple-darwin -o output.s
 
 # Check if the assembly file is not empty
 if [ ! -s output.s ]; then
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    ast::{float_literal, BinaryOp, Expr, TernaryOp, UnaryOp},
+    compile::{Error, NUM_MAX, NUM_MIN},
+    utils::gensym,
+};
+
+/// An operand that takes no computation to get at
+#[derive(Clone, Debug, PartialEq)]
+pub enum Atom {
+    Num(i64),
+    Float(f64),
+    Bool(bool),
+    Unit,
+    Var(String),
+}
+
+/// One step of computation, on atoms only
+#[derive(Clone, Debug)]
+pub enum Op {
+    Atom(Atom),
+    UnOp(UnaryOp, Atom),
+    BinOp(BinaryOp, Atom, Atom),
+    TriOp(TernaryOp, Atom, Atom, Atom),
+}
+
+impl Op {
+    /// The atoms the step reads, in order
+    pub fn operands(&self) -> Vec<&Atom> {
+        match self {
+            Op::Atom(a) | Op::UnOp(_, a) => vec![a],
+            Op::BinOp(_, a1, a2) => vec![a1, a2],
+            Op::TriOp(_, a1, a2, a3) => vec![a1, a2, a3],
+        }
+    }
+}
+
+/// What a variable is bound to: a step, or a choice between two programs
+#[derive(Clone, Debug)]
+pub enum Value {
+    Op(Op),
+    If(Atom, Box<Anf>, Box<Anf>),
+}
+
+/// A program in A-normal form: a sequence of bindings, in the order they are evaluated, ending
+/// in the value of the whole. Every variable is bound once, under a name of its own: those of
+/// the source keep theirs as a prefix, and temporaries are called `tmp`.
+#[derive(Clone, Debug)]
+pub enum Anf {
+    Let(String, Value, Box<Anf>),
+    Value(Value),
+}
+
+/// Lowers a program to A-normal form, resolving its variables
+pub fn lower(expr: &Expr) -> Result<Anf, Error> {
+    lower_tail(expr, &HashMap::new())
+}
+
+fn lower_tail(expr: &Expr, env: &HashMap<String, String>) -> Result<Anf, Error> {
+    let mut bindings = Vec::new();
+    let value = lower_value(expr, env, &mut bindings)?;
+    Ok(bindings
+        .into_iter()
+        .rev()
+        .fold(Anf::Value(value), |body, (var, value)| {
+            Anf::Let(var, value, Box::new(body))
+        }))
+}
+
+/// The value of `expr`, after the bindings it pushes onto `bindings`
+fn lower_value(
+    expr: &Expr,
+    env: &HashMap<String, String>,
+    bindings: &mut Vec<(String, Value)>,
+) -> Result<Value, Error> {
+    Ok(match expr {
+        Expr::Num(x) if !(NUM_MIN..=NUM_MAX).contains(x) => {
+            return Err(Error::NumberOutOfRange(*x))
+        }
+        Expr::Num(x) => Value::Op(Op::Atom(Atom::Num(*x))),
+        Expr::Float(x) => Value::Op(Op::Atom(Atom::Float(*x))),
+        Expr::Bool(b) => Value::Op(Op::Atom(Atom::Bool(*b))),
+        Expr::Unit => Value::Op(Op::Atom(Atom::Unit)),
+        Expr::Id(s) => match env.get(s) {
+            Some(var) => Value::Op(Op::Atom(Atom::Var(var.clone()))),
+            None => return Err(Error::Unbound(s.clone())),
+        },
+        Expr::UnOp(op, e) => Value::Op(Op::UnOp(op.clone(), lower_atom(e, env, bindings)?)),
+        Expr::BinOp(op, e1, e2) => {
+            let a1 = lower_atom(e1, env, bindings)?;
+            Value::Op(Op::BinOp(op.clone(), a1, lower_atom(e2, env, bindings)?))
+        }
+        Expr::TriOp(op, e1, e2, e3) => {
+            let a1 = lower_atom(e1, env, bindings)?;
+            let a2 = lower_atom(e2, env, bindings)?;
+            Value::Op(Op::TriOp(
+                op.clone(),
+                a1,
+                a2,
+                lower_atom(e3, env, bindings)?,
+            ))
+        }
+        Expr::If(cond, then_branch, else_branch) => Value::If(
+            lower_atom(cond, env, bindings)?,
+            Box::new(lower_tail(then_branch, env)?),
+            Box::new(lower_tail(else_branch, env)?),
+        ),
+        Expr::Let(let_bindings, body) => {
+            let mut env = env.clone();
+            for (name, e) in let_bindings {
+                let value = lower_value(e, &env, bindings)?;
+                let var = gensym(name);
+                bindings.push((var.clone(), value));
+                env.insert(name.clone(), var);
+            }
+            lower_value(body, &env, bindings)?
+        }
+        // Only the last value is kept, but the others are still computed
+        Expr::Do(exprs) => match exprs.split_last() {
+            Some((last, rest)) => {
+                for e in rest {
+                    let value = lower_value(e, env, bindings)?;
+                    bindings.push((gensym("tmp"), value));
+                }
+                lower_value(last, env, bindings)?
+            }
+            None => Value::Op(Op::Atom(Atom::Unit)),
+        },
+        Expr::FuncDef(..) => return Err(Error::Unsupported("function definition".to_string())),
+        Expr::FuncCall(..) => return Err(Error::Unsupported("function call".to_string())),
+    })
+}
+
+/// An atom for the value of `expr`, binding it to a temporary unless it already is one
+fn lower_atom(
+    expr: &Expr,
+    env: &HashMap<String, String>,
+    bindings: &mut Vec<(String, Value)>,
+) -> Result<Atom, Error> {
+    match lower_value(expr, env, bindings)? {
+        Value::Op(Op::Atom(atom)) => Ok(atom),
+        value => {
+            let var = gensym("tmp");
+            bindings.push((var.clone(), value));
+            Ok(Atom::Var(var))
+        }
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Atom::Num(n) => write!(f, "{}", n),
+            Atom::Float(x) => write!(f, "{}", float_literal(*x)),
+            Atom::Bool(b) => write!(f, "{}", b),
+            Atom::Unit => write!(f, "()"),
+            Atom::Var(var) => write!(f, "{}", var),
+        }
+    }
+}
+
+fn indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    write!(f, "{}", "  ".repeat(depth))
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Atom(a) => write!(f, "{}", a),
+            Op::UnOp(op, a) => write!(f, "{}({})", op, a),
+            Op::BinOp(op @ (BinaryOp::Pair | BinaryOp::Vector | BinaryOp::VectorGet), a1, a2) => {
+                write!(f, "{}({}, {})", op, a1, a2)
+            }
+            Op::BinOp(op, a1, a2) => write!(f, "{} {} {}", a1, op, a2),
+            Op::TriOp(op, a1, a2, a3) => write!(f, "{}({}, {}, {})", op, a1, a2, a3),
+        }
+    }
+}
+
+fn fmt_value(f: &mut fmt::Formatter<'_>, value: &Value, depth: usize) -> fmt::Result {
+    match value {
+        Value::Op(op) => write!(f, "{}", op),
+        Value::If(cond, then_branch, else_branch) => {
+            writeln!(f, "if {} then", cond)?;
+            fmt_anf(f, then_branch, depth + 1)?;
+            indent(f, depth)?;
+            writeln!(f, "else")?;
+            fmt_anf(f, else_branch, depth + 1)?;
+            indent(f, depth)?;
+            write!(f, "end")
+        }
+    }
+}
+
+/// One binding per line, with the branches of an `if` indented under it
+fn fmt_anf(f: &mut fmt::Formatter<'_>, anf: &Anf, depth: usize) -> fmt::Result {
+    let mut anf = anf;
+    while let Anf::Let(var, value, body) = anf {
+        indent(f, depth)?;
+        write!(f, "let {} = ", var)?;
+        fmt_value(f, value, depth)?;
+        writeln!(f)?;
+        anf = body;
+    }
+    if let Anf::Value(value) = anf {
+        indent(f, depth)?;
+        fmt_value(f, value, depth)?;
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_value(f, self, 0)
+    }
+}
+
+impl fmt::Display for Anf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_anf(f, self, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The printed program, with the generated names numbered from 0 in order of appearance so
+    /// that they do not depend on what else has called `gensym`
+    fn lowered(source: &str) -> String {
+        let printed = lower(&crate::parse(source).unwrap()).unwrap().to_string();
+        let mut names: HashMap<String, String> = HashMap::new();
+        let mut out = String::new();
+        let mut word = String::new();
+        for c in printed.chars().chain(std::iter::once('\n')) {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                word.push(c);
+                continue;
+            }
+            match word.rsplit_once('_') {
+                Some((prefix, n)) if !n.is_empty() && n.chars().all(|d| d.is_ascii_digit()) => {
+                    let fresh = format!("{}_{}", prefix, names.len());
+                    out.push_str(names.entry(word.clone()).or_insert(fresh));
+                }
+                _ => out.push_str(&word),
+            }
+            word.clear();
+            out.push(c);
+        }
+        out.trim_end().to_string()
+    }
+
+    #[test]
+    fn let_bindings_in_order() {
+        assert_eq!(
+            lowered("let x = add1(1), y = x + 2 in pair(x, y)"),
+            "\
+let x_0 = add1(1)
+let y_1 = x_0 + 2
+pair(x_0, y_1)"
+        );
+        // A later binding of the same name shadows the earlier one under a name of its own
+        assert_eq!(
+            lowered("let x = 1 in let x = add1(x) in x"),
+            "\
+let x_0 = 1
+let x_1 = add1(x_0)
+x_1"
+        );
+    }
+
+    /// Discarded values are still computed, in order, before the last one
+    #[test]
+    fn blocks_in_order() {
+        assert_eq!(
+            lowered("let v = vector(2, 0) in { vectorSet(v, 0, 1); vectorSet(v, 1, 2); vectorGet(v, 0) }"),
+            "\
+let v_0 = vector(2, 0)
+let tmp_1 = vectorSet(v_0, 0, 1)
+let tmp_2 = vectorSet(v_0, 1, 2)
+vectorGet(v_0, 0)"
+        );
+        assert_eq!(lowered("{ }"), "()");
+    }
+
+    /// Operands are evaluated left to right, each bound to a temporary unless it is an atom
+    #[test]
+    fn operands_left_to_right() {
+        assert_eq!(
+            lowered("add1(1) + sub1(2) < 3"),
+            "\
+let tmp_0 = add1(1)
+let tmp_1 = sub1(2)
+let tmp_2 = tmp_0 + tmp_1
+tmp_2 < 3"
+        );
+    }
+
+    /// An `if` used as an operand is bound to a temporary, with each branch lowered on its own
+    #[test]
+    fn nested_if_as_an_operand() {
+        assert_eq!(
+            lowered("pair(if (1 < 2) add1(1) else 0, 5)"),
+            "\
+let tmp_0 = 1 < 2
+let tmp_1 = if tmp_0 then
+  add1(1)
+else
+  0
+end
+pair(tmp_1, 5)"
+        );
+        assert_eq!(
+            lowered("if (if (true) false else true) 1 else { 2; 3 }"),
+            "\
+let tmp_0 = if true then
+  false
+else
+  true
+end
+if tmp_0 then
+  1
+else
+  let tmp_1 = 2
+  3
+end"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |source: &str| lower(&crate::parse(source).unwrap()).unwrap_err();
+        assert_eq!(
+            error("{ def f(x) = x; 1 }"),
+            Error::Unsupported("function definition".to_string())
+        );
+        assert_eq!(
+            error("let f = 1 in f(2)"),
+            Error::Unsupported("function call".to_string())
+        );
+        assert_eq!(error("add1(y)"), Error::Unbound("y".to_string()));
+        // A binding is not visible in its own right-hand side
+        assert_eq!(error("let x = x in x"), Error::Unbound("x".to_string()));
+        assert_eq!(
+            lower(&Expr::Num(NUM_MAX + 1)).unwrap_err(),
+            Error::NumberOutOfRange(NUM_MAX + 1)
+        );
+    }
+}
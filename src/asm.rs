@@ -1,10 +1,10 @@
-use std::{fmt, ops};
+use std::fmt;
 
 pub fn test() {
     println!("From another File");
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Register {
     X0,
     X1,
@@ -23,20 +23,105 @@ pub enum Register {
     X14,
     X15,
     X16,
+    X19,
+    X20,
+    X21,
+    X22,
+    X23,
+    X24,
+    X25,
+    X26,
+    X27,
+    X28,
     Sp,
     Lr,
     Fp,
+    /// A register of the code generator's output, which `regalloc` maps to a machine register
+    /// or a stack slot before anything is emitted
+    Virtual(usize),
 }
 
-#[derive(Clone, Debug)]
+/// How the AArch64 procedure call standard treats a register
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegisterClass {
+    /// X0 to X7, which carry arguments and results
+    Argument,
+    /// X8 to X16, which any call may overwrite
+    CallerSaved,
+    /// X19 to X28, which a function must give back as it found them
+    CalleeSaved,
+    /// sp, lr and fp
+    Special,
+    Virtual,
+}
+
+impl Register {
+    pub fn class(&self) -> RegisterClass {
+        match self {
+            Register::X0
+            | Register::X1
+            | Register::X2
+            | Register::X3
+            | Register::X4
+            | Register::X5
+            | Register::X6
+            | Register::X7 => RegisterClass::Argument,
+            Register::X8
+            | Register::X9
+            | Register::X10
+            | Register::X11
+            | Register::X12
+            | Register::X13
+            | Register::X14
+            | Register::X15
+            | Register::X16 => RegisterClass::CallerSaved,
+            Register::X19
+            | Register::X20
+            | Register::X21
+            | Register::X22
+            | Register::X23
+            | Register::X24
+            | Register::X25
+            | Register::X26
+            | Register::X27
+            | Register::X28 => RegisterClass::CalleeSaved,
+            Register::Sp | Register::Lr | Register::Fp => RegisterClass::Special,
+            Register::Virtual(_) => RegisterClass::Virtual,
+        }
+    }
+}
+
+/// The registers the allocator hands out, cheapest first: the caller-saved ones cost nothing
+/// to use, the callee-saved ones a save and restore around the function
+pub const ALLOCATABLE: &[Register] = &[
+    Register::X9,
+    Register::X10,
+    Register::X11,
+    Register::X12,
+    Register::X13,
+    Register::X14,
+    Register::X15,
+    Register::X19,
+    Register::X20,
+    Register::X21,
+    Register::X22,
+    Register::X23,
+    Register::X24,
+    Register::X25,
+    Register::X26,
+    Register::X27,
+    Register::X28,
+];
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Operand {
     Reg(Register),
     Imm(i64),
     MemOffset(Box<Operand>, Box<Operand>),
-    RegOffset(Register, i64)
+    RegOffset(Register, i64),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Directive {
     Global(String),
     Extern(String),
@@ -49,6 +134,9 @@ pub enum Directive {
     Mov(Operand, Operand),
     Add(Operand, Operand),
     Sub(Operand, Operand),
+    /// Add and subtract, setting the flags
+    Adds(Operand, Operand),
+    Subs(Operand, Operand),
     Mul(Operand, Operand),
     Sdiv(Operand, Operand),
     Lsl(Operand, Operand),
@@ -66,6 +154,10 @@ pub enum Directive {
     Bge(String),
     Bgt(String),
     Ble(String),
+    /// Branch if lower or same, comparing unsigned
+    Bls(String),
+    /// Branch if the last flag-setting instruction overflowed
+    Bvs(String),
     Br(Operand),
     Str(Operand, Operand),
     Ldr(Operand, Operand),
@@ -94,79 +186,18 @@ impl fmt::Display for Operand {
 }
 
 pub fn string_of_register(reg: &Register) -> String {
-    match reg {
-        Register::X0 => String::from("X0"),
-        Register::X1 => String::from("X1"),
-        Register::X2 => String::from("X2"),
-        Register::X3 => String::from("X3"),
-        Register::X4 => String::from("X4"),
-        Register::X5 => String::from("X5"),
-        Register::X6 => String::from("X6"),
-        Register::X7 => String::from("X7"),
-        Register::X8 => String::from("X8"),
-        Register::X9 => String::from("X9"),
-        Register::X10 => String::from("X10"),
-        Register::X11 => String::from("X11"),
-        Register::X12 => String::from("X12"),
-        Register::X13 => String::from("X13"),
-        Register::X14 => String::from("X14"),
-        Register::X15 => String::from("X15"),
-        Register::X16 => String::from("X16"),
-        Register::Sp => String::from("sp"),
-        Register::Lr => String::from("lr"),
-        Register::Fp => String::from("fp"),
-    }
-}
-
-impl ops::Add<i32> for Register {
-    type Output = Register;
-
-    fn add(self, rhs: i32) -> Self::Output {
-        match rhs {
-            1 => Self::X1,
-            2 => Self::X2,
-            3 => Self::X3,
-            4 => Self::X4,
-            5 => Self::X5,
-            6 => Self::X6,
-            7 => Self::X7,
-            8 => Self::X8,
-            9 => Self::X9,
-            10 => Self::X10,
-            11 => Self::X11,
-            12 => Self::X12,
-            13 => Self::X13,
-            14 => Self::X14,
-            15 => Self::X15,
-            16 => Self::X16,
-            _ => Self::X0,
-        }
-    }
+    reg.to_string()
 }
 
 impl fmt::Display for Register {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Register::X0 => write!(f, "X0"),
-            Register::X1 => write!(f, "X1"),
-            Register::X2 => write!(f, "X2"),
-            Register::X3 => write!(f, "X3"),
-            Register::X4 => write!(f, "X4"),
-            Register::X5 => write!(f, "X5"),
-            Register::X6 => write!(f, "X7"),
-            Register::X7 => write!(f, "X7"),
-            Register::X8 => write!(f, "X8"),
-            Register::X9 => write!(f, "X9"),
-            Register::X10 => write!(f, "X10"),
-            Register::X11 => write!(f, "X11"),
-            Register::X12 => write!(f, "X12"),
-            Register::X13 => write!(f, "X13"),
-            Register::X14 => write!(f, "X14"),
-            Register::X15 => write!(f, "X15"),
-            Register::X16 => write!(f, "X16"),
             Register::Sp => write!(f, "sp"),
             Register::Lr => write!(f, "lr"),
             Register::Fp => write!(f, "fp"),
+            Register::Virtual(n) => write!(f, "v{}", n),
+            // The machine registers print as their names, X0 to X28
+            r => write!(f, "{:?}", r),
         }
     }
 }
@@ -178,13 +209,160 @@ pub fn is_register(o: Operand) -> bool {
     }
 }
 
-pub fn label_name(label: &str) -> String {
-    format!("_{}", label)
+fn operand_registers(o: &Operand) -> Vec<Register> {
+    match o {
+        Operand::Reg(r) | Operand::RegOffset(r, _) => vec![r.clone()],
+        Operand::Imm(_) => vec![],
+        Operand::MemOffset(offset, base) => {
+            [operand_registers(offset), operand_registers(base)].concat()
+        }
+    }
+}
+
+fn map_operand(o: &Operand, f: &mut impl FnMut(&Register) -> Register) -> Operand {
+    match o {
+        Operand::Reg(r) => Operand::Reg(f(r)),
+        Operand::Imm(i) => Operand::Imm(*i),
+        Operand::MemOffset(offset, base) => Operand::MemOffset(
+            Box::new(map_operand(offset, f)),
+            Box::new(map_operand(base, f)),
+        ),
+        Operand::RegOffset(r, offset) => Operand::RegOffset(f(r), *offset),
+    }
+}
+
+impl Directive {
+    /// The registers the instruction reads. A call reads its arguments and a return the result.
+    pub fn uses(&self) -> Vec<Register> {
+        match self {
+            Directive::Mov(_, src) | Directive::Ldr(_, src) | Directive::Br(src) => {
+                operand_registers(src)
+            }
+            Directive::Add(dest, src)
+            | Directive::Sub(dest, src)
+            | Directive::Adds(dest, src)
+            | Directive::Subs(dest, src)
+            | Directive::Mul(dest, src)
+            | Directive::Sdiv(dest, src)
+            | Directive::Lsl(dest, src)
+            | Directive::Lsr(dest, src)
+            | Directive::Asr(dest, src)
+            | Directive::Cmp(dest, src)
+            | Directive::And(dest, src)
+            | Directive::Orr(dest, src)
+            | Directive::Str(dest, src) => {
+                [operand_registers(dest), operand_registers(src)].concat()
+            }
+            Directive::Stp(r1, r2, mem) => [
+                operand_registers(r1),
+                operand_registers(r2),
+                operand_registers(mem),
+            ]
+            .concat(),
+            Directive::Ldp(_, _, mem) => operand_registers(mem),
+            Directive::Bl(_) => vec![Register::X0, Register::X1, Register::X2, Register::X3],
+            Directive::Ret => vec![Register::X0],
+            _ => vec![],
+        }
+    }
+
+    /// The registers the instruction writes; a call leaves its result in X0
+    pub fn defs(&self) -> Vec<Register> {
+        match self {
+            Directive::Mov(dest, _)
+            | Directive::Add(dest, _)
+            | Directive::Sub(dest, _)
+            | Directive::Adds(dest, _)
+            | Directive::Subs(dest, _)
+            | Directive::Mul(dest, _)
+            | Directive::Sdiv(dest, _)
+            | Directive::Lsl(dest, _)
+            | Directive::Lsr(dest, _)
+            | Directive::Asr(dest, _)
+            | Directive::And(dest, _)
+            | Directive::Orr(dest, _)
+            | Directive::Cset(dest, _)
+            | Directive::Adr(dest, _)
+            | Directive::Ldr(dest, _) => operand_registers(dest),
+            Directive::Ldp(r1, r2, _) => [operand_registers(r1), operand_registers(r2)].concat(),
+            Directive::Bl(_) => vec![Register::X0],
+            _ => vec![],
+        }
+    }
+
+    /// The same directive with each register replaced by `f` of it
+    pub fn map_registers(&self, f: &mut impl FnMut(&Register) -> Register) -> Directive {
+        let mut m = |o: &Operand| map_operand(o, f);
+        match self {
+            Directive::Mov(a, b) => Directive::Mov(m(a), m(b)),
+            Directive::Add(a, b) => Directive::Add(m(a), m(b)),
+            Directive::Sub(a, b) => Directive::Sub(m(a), m(b)),
+            Directive::Adds(a, b) => Directive::Adds(m(a), m(b)),
+            Directive::Subs(a, b) => Directive::Subs(m(a), m(b)),
+            Directive::Mul(a, b) => Directive::Mul(m(a), m(b)),
+            Directive::Sdiv(a, b) => Directive::Sdiv(m(a), m(b)),
+            Directive::Lsl(a, b) => Directive::Lsl(m(a), m(b)),
+            Directive::Lsr(a, b) => Directive::Lsr(m(a), m(b)),
+            Directive::Asr(a, b) => Directive::Asr(m(a), m(b)),
+            Directive::Cmp(a, b) => Directive::Cmp(m(a), m(b)),
+            Directive::And(a, b) => Directive::And(m(a), m(b)),
+            Directive::Orr(a, b) => Directive::Orr(m(a), m(b)),
+            Directive::Str(a, b) => Directive::Str(m(a), m(b)),
+            Directive::Ldr(a, b) => Directive::Ldr(m(a), m(b)),
+            Directive::Cset(a, cond) => Directive::Cset(m(a), cond.clone()),
+            Directive::Adr(a, label) => Directive::Adr(m(a), label.clone()),
+            Directive::Br(a) => Directive::Br(m(a)),
+            Directive::Stp(a, b, c) => Directive::Stp(m(a), m(b), m(c)),
+            Directive::Ldp(a, b, c) => Directive::Ldp(m(a), m(b), m(c)),
+            other => other.clone(),
+        }
+    }
+}
+
+/// The platform whose assembler and linker conventions the output follows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    MacOs,
+    Linux,
+}
+
+impl Target {
+    pub fn host() -> Target {
+        if cfg!(target_os = "macos") {
+            Target::MacOs
+        } else {
+            Target::Linux
+        }
+    }
+
+    pub fn from_triple(triple: &str) -> Result<Target, String> {
+        match triple {
+            "aarch64-apple-darwin" | "macos" => Ok(Target::MacOs),
+            "aarch64-linux-gnu" | "aarch64-unknown-linux-gnu" | "linux" => Ok(Target::Linux),
+            other => Err(format!(
+                "unknown target {} (expected aarch64-apple-darwin or aarch64-linux-gnu)",
+                other
+            )),
+        }
+    }
+
+    /// Mach-O symbols carry a leading underscore, ELF symbols do not
+    pub fn symbol_prefix(self) -> &'static str {
+        match self {
+            Target::MacOs => "_",
+            Target::Linux => "",
+        }
+    }
 }
 
 pub fn string_of_directive(directive: &Directive) -> String {
+    string_of_directive_for(directive, Target::MacOs)
+}
+
+pub fn string_of_directive_for(directive: &Directive, target: Target) -> String {
+    let label_name = |label: &str| format!("{}{}", target.symbol_prefix(), label);
     match directive {
-        Directive::Global(l) => format!(".global _{}", l),
+        Directive::Global(l) => format!(".global {}", label_name(l)),
         Directive::Extern(l) => format!(".extern {}", label_name(l)),
         Directive::Section(l) => format!(".section {}", l),
         Directive::Label(l) => format!("{}:", label_name(l)),
@@ -195,6 +373,8 @@ pub fn string_of_directive(directive: &Directive) -> String {
         Directive::Mov(dest, src) => format!("\tmov {}, {}", dest, src),
         Directive::Add(dest, src) => format!("\tadd {}, {}, {}", dest, dest, src),
         Directive::Sub(dest, src) => format!("\tsub {}, {}, {}", dest, dest, src),
+        Directive::Adds(dest, src) => format!("\tadds {}, {}, {}", dest, dest, src),
+        Directive::Subs(dest, src) => format!("\tsubs {}, {}, {}", dest, dest, src),
         Directive::Mul(dest, src) => format!("\tmul {}, {}, {}", dest, dest, src),
         Directive::Sdiv(dest, src) => format!("\tsdiv {}, {}, {}", dest, dest, src),
         Directive::Lsl(dest, src) => format!("\tlsl {}, {}, {}", dest, dest, src),
@@ -212,6 +392,8 @@ pub fn string_of_directive(directive: &Directive) -> String {
         Directive::Bge(dest) => format!("\tbge {}", label_name(dest)),
         Directive::Bgt(dest) => format!("\tbgt {}", label_name(dest)),
         Directive::Ble(dest) => format!("\tble {}", label_name(dest)),
+        Directive::Bls(dest) => format!("\tbls {}", label_name(dest)),
+        Directive::Bvs(dest) => format!("\tbvs {}", label_name(dest)),
         Directive::Br(dest) => format!("\tbr {}", dest),
         Directive::Str(dest, src) => format!("\tstr {}, {}", src, dest),
         Directive::Ldr(dest, src) => format!("\tldr {}, {}", dest, src),
@@ -1,7 +1,11 @@
 use std::fs::{self, File};
 use std::io::Write;
+use std::path::Path;
 use std::process::Command;
 
+use crate::asm::Directive;
+use crate::{elf, encode};
+
 pub fn assemble(name: &str, instructions: &[String]) -> Result<(), String> {
     let asm_file = format!("{}.s", name);
     let object_file = format!("{}.o", name);
@@ -45,3 +49,52 @@ pub fn assemble(name: &str, instructions: &[String]) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Encodes the directives in-process and writes an ELF object, so no external assembler is needed
+pub fn assemble_object(object_file: &Path, directives: &[Directive]) -> Result<(), String> {
+    let object = encode::encode(directives).map_err(|e| format!("Failed to encode: {}", e))?;
+
+    File::create(object_file)
+        .and_then(|mut file| file.write_all(&elf::write_elf(&object)))
+        .map_err(|e| format!("Failed to write object file: {}", e))
+}
+
+/// Assembles a `.s` file with the system compiler driver, for targets without an in-process writer
+pub fn assemble_with_cc(asm_file: &Path, object_file: &Path) -> Result<(), String> {
+    let output = Command::new("cc")
+        .arg("-c")
+        .arg(asm_file)
+        .arg("-o")
+        .arg(object_file)
+        .output()
+        .map_err(|e| format!("Failed to execute cc: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "cc failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Links an object produced by `assemble_object` against the C runtime
+pub fn link(object_file: &Path, runtime: &Path, binary_file: &Path) -> Result<(), String> {
+    let output = Command::new("cc")
+        .arg(object_file)
+        .arg(runtime)
+        .arg("-o")
+        .arg(binary_file)
+        .output()
+        .map_err(|e| format!("Failed to execute cc: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "cc failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
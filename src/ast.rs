@@ -1,6 +1,9 @@
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum Expr {
     Num(i64),
+    Float(f64),
     Id(String),
     Bool(bool),
     Unit,
@@ -10,8 +13,8 @@ pub enum Expr {
     If(Box<Expr>, Box<Expr>, Box<Expr>),
     Let(Vec<(String, Box<Expr>)>, Box<Expr>),
     Do(Vec<Box<Expr>>),
-    FuncDef(String, Vec<String>, Box<Expr>), 
-    FuncCall(Box<Expr>, Vec<Box<Expr>>),      
+    FuncDef(String, Vec<String>, Box<Expr>),
+    FuncCall(Box<Expr>, Vec<Box<Expr>>),
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +30,9 @@ pub enum UnaryOp {
     IsList,
     IsVector,
     VectorLength,
+    Floor,
+    Round,
+    ToFloat,
 }
 
 #[derive(Debug, Clone)]
@@ -43,4 +49,214 @@ pub enum BinaryOp {
 #[derive(Debug, Clone)]
 pub enum TernaryOp {
     VectorSet,
-}
\ No newline at end of file
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UnaryOp::Add1 => "add1",
+            UnaryOp::Sub1 => "sub1",
+            UnaryOp::IsZero => "isZero",
+            UnaryOp::IsNum => "isNum",
+            UnaryOp::Not => "!",
+            UnaryOp::IsPair => "isPair",
+            UnaryOp::Left => "left",
+            UnaryOp::Right => "right",
+            UnaryOp::IsList => "isList",
+            UnaryOp::IsVector => "isVector",
+            UnaryOp::VectorLength => "vectorLength",
+            UnaryOp::Floor => "floor",
+            UnaryOp::Round => "round",
+            UnaryOp::ToFloat => "toFloat",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Eq => "==",
+            BinaryOp::Lt => "<",
+            BinaryOp::Pair => "pair",
+            BinaryOp::Vector => "vector",
+            BinaryOp::VectorGet => "vectorGet",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for TernaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TernaryOp::VectorSet => write!(f, "vectorSet"),
+        }
+    }
+}
+
+/// Prints a float the way `print_value` in runtime.c does: the fewest digits that read back as
+/// the same number, with a `.` or an exponent so that they read back as a float
+pub fn float_literal(x: f64) -> String {
+    if x.is_nan() {
+        return "nan".to_string();
+    }
+    if x.is_infinite() {
+        return if x < 0.0 { "-inf" } else { "inf" }.to_string();
+    }
+    // Like runtime.c, the first precision that reads back, as in `-1.25e-7`; seventeen digits
+    // always do
+    let scientific = (0..17)
+        .map(|precision| format!("{:.*e}", precision, x))
+        .find(|s| s.parse() == Ok(x))
+        .unwrap();
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    if !(-5..16).contains(&exponent) {
+        return scientific;
+    }
+    let digits = mantissa.replace('.', "");
+    if exponent < 0 {
+        let zeros = "0".repeat((-exponent - 1) as usize);
+        return format!("{}0.{}{}", sign, zeros, digits);
+    }
+    let point = exponent as usize + 1;
+    if digits.len() > point {
+        format!("{}{}.{}", sign, &digits[..point], &digits[point..])
+    } else {
+        format!("{}{}{}.0", sign, digits, "0".repeat(point - digits.len()))
+    }
+}
+
+/// How tightly an expression binds, following the grammar's Expr/BinaryExpr/Term/Factor levels
+pub fn level(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Let(..) | Expr::If(..) | Expr::FuncDef(..) => 0,
+        Expr::BinOp(BinaryOp::Eq | BinaryOp::Lt, _, _) => 1,
+        Expr::BinOp(BinaryOp::Add | BinaryOp::Sub, _, _) => 2,
+        _ => 3,
+    }
+}
+
+fn fmt_at(f: &mut fmt::Formatter<'_>, expr: &Expr, required: u8) -> fmt::Result {
+    if level(expr) < required {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
+fn fmt_list(f: &mut fmt::Formatter<'_>, exprs: &[&Expr], sep: &str) -> fmt::Result {
+    for (i, e) in exprs.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", sep)?;
+        }
+        write!(f, "{}", e)?;
+    }
+    Ok(())
+}
+
+/// Prints source that `ExprParser` parses back to the same tree
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Float(x) => write!(f, "{}", float_literal(*x)),
+            Expr::Id(s) => write!(f, "{}", s),
+            Expr::Bool(b) => write!(f, "{}", b),
+            Expr::Unit => write!(f, "()"),
+            Expr::UnOp(op, e) => write!(f, "{}({})", op, e),
+            Expr::BinOp(op @ (BinaryOp::Pair | BinaryOp::Vector | BinaryOp::VectorGet), e1, e2) => {
+                write!(f, "{}({}, {})", op, e1, e2)
+            }
+            Expr::BinOp(op, e1, e2) => {
+                let l = level(self);
+                fmt_at(f, e1, l)?;
+                write!(f, " {} ", op)?;
+                fmt_at(f, e2, l + 1)
+            }
+            Expr::TriOp(op, e1, e2, e3) => write!(f, "{}({}, {}, {})", op, e1, e2, e3),
+            Expr::If(cond, then_branch, else_branch) => {
+                write!(f, "if ({}) {} else {}", cond, then_branch, else_branch)
+            }
+            Expr::Let(bindings, body) => {
+                write!(f, "let ")?;
+                for (i, (var, e)) in bindings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} = {}", var, e)?;
+                }
+                write!(f, " in {}", body)
+            }
+            Expr::Do(exprs) if exprs.is_empty() => write!(f, "{{}}"),
+            Expr::Do(exprs) => {
+                write!(f, "{{ ")?;
+                fmt_list(
+                    f,
+                    &exprs.iter().map(|e| e.as_ref()).collect::<Vec<_>>(),
+                    "; ",
+                )?;
+                write!(f, " }}")
+            }
+            Expr::FuncDef(name, params, body) => {
+                write!(f, "def {}({}) = {}", name, params.join(", "), body)
+            }
+            Expr::FuncCall(callee, args) => {
+                fmt_at(f, callee, 3)?;
+                write!(f, "(")?;
+                fmt_list(
+                    f,
+                    &args.iter().map(|e| e.as_ref()).collect::<Vec<_>>(),
+                    ", ",
+                )?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A printed float reads back as the same float, both with `parse` and with the program
+    /// parser
+    #[test]
+    fn float_literals_round_trip() {
+        let values = [
+            0.0,
+            1.5,
+            -2.5,
+            3.0,
+            0.1 + 0.2,
+            1e20,
+            -1.25e-7,
+            1e15,
+            1e16,
+            1e-5,
+            1e-6,
+            2.305843009213694e18,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+        ];
+        for x in values {
+            let printed = float_literal(x);
+            assert_eq!(printed.parse::<f64>(), Ok(x), "{}", printed);
+            match crate::parse(&printed) {
+                Ok(Expr::Float(y)) => assert_eq!(y, x, "{}", printed),
+                other => panic!("{} parsed as {:?}", printed, other.ok()),
+            }
+        }
+        assert_eq!(float_literal(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(float_literal(3.0), "3.0");
+        assert_eq!(float_literal(1e20), "1e20");
+        assert_eq!(float_literal(-1.25e-7), "-1.25e-7");
+    }
+}
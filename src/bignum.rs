@@ -0,0 +1,190 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// An arbitrary-precision integer: a sign and a magnitude in 64-bit limbs, least significant
+/// first, with no high zero limbs. This is the layout runtime.c keeps on the heap, after a
+/// header word holding the limb count, negated for negative numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u64>,
+}
+
+impl BigInt {
+    pub fn from_parts(negative: bool, mut limbs: Vec<u64>) -> BigInt {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigInt {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    pub fn from_i64(n: i64) -> BigInt {
+        BigInt::from_parts(n < 0, vec![n.unsigned_abs()])
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [n] if self.negative => 0i64.checked_sub_unsigned(*n),
+            [n] => i64::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    /// The integer a whole, finite double holds
+    pub fn from_f64(x: f64) -> BigInt {
+        let bits = x.to_bits();
+        if bits << 1 == 0 {
+            return BigInt::from_i64(0);
+        }
+        let mut mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let mut exponent = ((bits >> 52) & 0x7ff) as i64 - 1075;
+        if exponent < 0 {
+            mantissa >>= -exponent;
+            exponent = 0;
+        }
+        let (word, shift) = (exponent as usize / 64, exponent % 64);
+        let mut limbs = vec![0; word + 2];
+        limbs[word] = mantissa << shift;
+        if shift != 0 {
+            limbs[word + 1] = mantissa >> (64 - shift);
+        }
+        BigInt::from_parts(bits >> 63 == 1, limbs)
+    }
+
+    /// The nearest double, accumulated from the most significant limb as runtime.c does
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0.0, |d, &limb| d * 18446744073709551616.0 + limb as f64);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitudes(&self.limbs, &other.limbs));
+        }
+        // Opposite signs: the larger magnitude decides the sign
+        match compare_magnitudes(&self.limbs, &other.limbs) {
+            Ordering::Less => {
+                BigInt::from_parts(other.negative, sub_magnitudes(&other.limbs, &self.limbs))
+            }
+            _ => BigInt::from_parts(self.negative, sub_magnitudes(&self.limbs, &other.limbs)),
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+}
+
+fn add_magnitudes(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = false;
+    for i in 0..a.len().max(b.len()) {
+        let (s, c1) = a
+            .get(i)
+            .copied()
+            .unwrap_or(0)
+            .overflowing_add(b.get(i).copied().unwrap_or(0));
+        let (s, c2) = s.overflowing_add(carry as u64);
+        sum.push(s);
+        carry = c1 || c2;
+    }
+    if carry {
+        sum.push(1);
+    }
+    sum
+}
+
+/// `a - b` for `a >= b`
+fn sub_magnitudes(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for (i, &limb) in a.iter().enumerate() {
+        let (d, b1) = limb.overflowing_sub(b.get(i).copied().unwrap_or(0));
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        difference.push(d);
+        borrow = b1 || b2;
+    }
+    difference
+}
+
+fn compare_magnitudes(a: &[u64], b: &[u64]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.limbs, &other.limbs),
+            (true, true) => compare_magnitudes(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Decimal, like `print_value` in runtime.c
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+        let mut magnitude = self.limbs.clone();
+        let mut chunks = Vec::new();
+        // Divide by 10^19 until nothing is left, collecting the remainders
+        while !magnitude.is_empty() {
+            let mut remainder = 0u128;
+            for limb in magnitude.iter_mut().rev() {
+                let current = (remainder << 64) | *limb as u128;
+                *limb = (current / CHUNK) as u64;
+                remainder = current % CHUNK;
+            }
+            chunks.push(remainder as u64);
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{:019}", chunk)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use std::io;
+use std::process;
+
+/// Language server for the infix syntax, speaking JSON-RPC over stdin and stdout
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    match asm::lsp::serve(&mut stdin.lock(), &mut stdout.lock()) {
+        Ok(code) => process::exit(code),
+        Err(e) => {
+            eprintln!("asm-lsp: {}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::{
+    anf::{Anf, Atom, Op, Value},
+    utils::gensym,
+};
+
+/// How a block hands on control, to blocks named by their index
+#[derive(Clone, Debug)]
+pub enum Terminator {
+    Jump(usize),
+    /// To the first block unless the atom is false, else to the second
+    Branch(Atom, usize, usize),
+    Return(Atom),
+}
+
+/// Straight-line code: assignments, in order, then a terminator
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub label: String,
+    pub body: Vec<(String, Op)>,
+    pub terminator: Terminator,
+}
+
+/// A program as basic blocks, entered at the first. Unlike in `Anf`, a variable an `if` binds
+/// is assigned at the end of each branch, before both jump to the block that follows.
+#[derive(Clone, Debug)]
+pub struct Cfg {
+    pub blocks: Vec<Block>,
+}
+
+/// The variables live on entry to and on exit from each block
+pub struct Liveness {
+    pub live_in: Vec<BTreeSet<String>>,
+    pub live_out: Vec<BTreeSet<String>>,
+}
+
+/// Blocks under construction, whose terminators are set once their code is complete
+struct Builder {
+    blocks: Vec<(String, Vec<(String, Op)>)>,
+    terminators: Vec<Option<Terminator>>,
+}
+
+impl Builder {
+    fn block(&mut self, prefix: &str) -> usize {
+        self.blocks.push((gensym(prefix), Vec::new()));
+        self.terminators.push(None);
+        self.blocks.len() - 1
+    }
+
+    fn terminate(&mut self, block: usize, terminator: Terminator) {
+        self.terminators[block] = Some(terminator);
+    }
+
+    /// Adds the code of `anf` to `current`, assigning its value to `dest`, and returns the
+    /// block it ends in
+    fn build(&mut self, anf: Anf, mut current: usize, dest: &str) -> usize {
+        let mut anf = anf;
+        loop {
+            let (var, value, body) = match anf {
+                Anf::Let(var, value, body) => (var, value, Some(*body)),
+                Anf::Value(value) => (dest.to_string(), value, None),
+            };
+            match value {
+                Value::Op(op) => self.blocks[current].1.push((var, op)),
+                Value::If(cond, then_branch, else_branch) => {
+                    let then_block = self.block("then");
+                    let else_block = self.block("else");
+                    let join = self.block("join");
+                    self.terminate(current, Terminator::Branch(cond, then_block, else_block));
+                    let end = self.build(*then_branch, then_block, &var);
+                    self.terminate(end, Terminator::Jump(join));
+                    let end = self.build(*else_branch, else_block, &var);
+                    self.terminate(end, Terminator::Jump(join));
+                    current = join;
+                }
+            }
+            match body {
+                Some(body) => anf = body,
+                None => return current,
+            }
+        }
+    }
+}
+
+/// Builds the graph of a lowered program
+pub fn build(anf: Anf) -> Cfg {
+    let mut builder = Builder {
+        blocks: Vec::new(),
+        terminators: Vec::new(),
+    };
+    let entry = builder.block("entry");
+    let result = gensym("result");
+    let end = builder.build(anf, entry, &result);
+    builder.terminate(end, Terminator::Return(Atom::Var(result)));
+    Cfg {
+        blocks: builder
+            .blocks
+            .into_iter()
+            .zip(builder.terminators)
+            .map(|((label, body), terminator)| Block {
+                label,
+                body,
+                terminator: terminator.unwrap(),
+            })
+            .collect(),
+    }
+}
+
+fn variables<'a>(atoms: impl IntoIterator<Item = &'a Atom>) -> Vec<String> {
+    atoms
+        .into_iter()
+        .filter_map(|a| match a {
+            Atom::Var(var) => Some(var.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<usize> {
+        match self {
+            Terminator::Jump(target) => vec![*target],
+            Terminator::Branch(_, then_block, else_block) => vec![*then_block, *else_block],
+            Terminator::Return(_) => vec![],
+        }
+    }
+
+    fn uses(&self) -> Vec<String> {
+        match self {
+            Terminator::Jump(_) => vec![],
+            Terminator::Branch(a, ..) | Terminator::Return(a) => variables([a]),
+        }
+    }
+}
+
+impl Cfg {
+    pub fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![Vec::new(); self.blocks.len()];
+        for (i, block) in self.blocks.iter().enumerate() {
+            for s in block.terminator.successors() {
+                predecessors[s].push(i);
+            }
+        }
+        predecessors
+    }
+
+    /// The order to lay the blocks out in: reverse postorder, which puts every block after
+    /// those that dominate it, with the `then` of a branch right after it and the block after
+    /// the `else` where the branches join
+    pub fn layout(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.blocks.len()];
+        let mut postorder = Vec::new();
+        // Blocks with the successors yet to visit, last first
+        let mut stack = vec![(0, self.blocks[0].terminator.successors())];
+        visited[0] = true;
+        while let Some((block, successors)) = stack.last_mut() {
+            match successors.pop() {
+                Some(s) if !visited[s] => {
+                    visited[s] = true;
+                    stack.push((s, self.blocks[s].terminator.successors()));
+                }
+                Some(_) => {}
+                None => {
+                    postorder.push(*block);
+                    stack.pop();
+                }
+            }
+        }
+        postorder.reverse();
+        postorder
+    }
+
+    pub fn liveness(&self) -> Liveness {
+        let n = self.blocks.len();
+        let mut live_in = vec![BTreeSet::new(); n];
+        let mut live_out = vec![BTreeSet::new(); n];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in (0..n).rev() {
+                let block = &self.blocks[i];
+                let out: BTreeSet<String> = block
+                    .terminator
+                    .successors()
+                    .into_iter()
+                    .flat_map(|s| live_in[s].iter().cloned())
+                    .collect();
+                let mut live = out.clone();
+                live.extend(block.terminator.uses());
+                for (var, op) in block.body.iter().rev() {
+                    live.remove(var);
+                    live.extend(variables(op.operands()));
+                }
+                if live != live_in[i] {
+                    live_in[i] = live;
+                    changed = true;
+                }
+                live_out[i] = out;
+            }
+        }
+        Liveness { live_in, live_out }
+    }
+
+    /// The blocks every path from the entry to each block goes through, the block included
+    pub fn dominators(&self) -> Vec<BTreeSet<usize>> {
+        let n = self.blocks.len();
+        let predecessors = self.predecessors();
+        let mut dominators = vec![(0..n).collect::<BTreeSet<_>>(); n];
+        dominators[0] = BTreeSet::from([0]);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 1..n {
+                let mut common = predecessors[i]
+                    .iter()
+                    .map(|&p| dominators[p].clone())
+                    .reduce(|a, b| a.intersection(&b).copied().collect())
+                    .unwrap_or_default();
+                common.insert(i);
+                if common != dominators[i] {
+                    dominators[i] = common;
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// The closest block other than itself that dominates each block, which the entry lacks
+    pub fn immediate_dominators(&self) -> Vec<Option<usize>> {
+        let dominators = self.dominators();
+        dominators
+            .iter()
+            .enumerate()
+            .map(|(i, doms)| {
+                doms.iter()
+                    .copied()
+                    .filter(|&d| d != i)
+                    .max_by_key(|&d| dominators[d].len())
+            })
+            .collect()
+    }
+
+    fn terminator_text(&self, terminator: &Terminator) -> String {
+        let label = |b: &usize| &self.blocks[*b].label;
+        match terminator {
+            Terminator::Jump(target) => format!("jump {}", label(target)),
+            Terminator::Branch(cond, then_block, else_block) => format!(
+                "branch {} {} {}",
+                cond,
+                label(then_block),
+                label(else_block)
+            ),
+            Terminator::Return(a) => format!("return {}", a),
+        }
+    }
+
+    /// The graph in Graphviz's dot language: a box per block listing the variables live into
+    /// it and its code, its edges, and dashed edges from each block's immediate dominator
+    pub fn to_dot(&self) -> String {
+        let liveness = self.liveness();
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot = String::from("digraph cfg {\n  node [shape=box, fontname=monospace];\n");
+        for (i, block) in self.blocks.iter().enumerate() {
+            let live: Vec<&str> = liveness.live_in[i].iter().map(|v| v.as_str()).collect();
+            let mut lines = vec![
+                format!("{}:", block.label),
+                format!("  ; live: {}", live.join(" ")),
+            ];
+            lines.extend(
+                block
+                    .body
+                    .iter()
+                    .map(|(var, op)| format!("  {} = {}", var, op)),
+            );
+            lines.push(format!("  {}", self.terminator_text(&block.terminator)));
+            let text: String = lines.iter().map(|l| format!("{}\\l", escape(l))).collect();
+            dot.push_str(&format!("  b{} [label=\"{}\"];\n", i, text));
+        }
+        for (i, block) in self.blocks.iter().enumerate() {
+            match &block.terminator {
+                Terminator::Branch(_, then_block, else_block) => {
+                    dot.push_str(&format!("  b{} -> b{} [label=\"true\"];\n", i, then_block));
+                    dot.push_str(&format!("  b{} -> b{} [label=\"false\"];\n", i, else_block));
+                }
+                terminator => {
+                    for s in terminator.successors() {
+                        dot.push_str(&format!("  b{} -> b{};\n", i, s));
+                    }
+                }
+            }
+        }
+        for (i, idom) in self.immediate_dominators().iter().enumerate() {
+            if let Some(d) = idom {
+                dot.push_str(&format!(
+                    "  b{} -> b{} [style=dashed, color=gray, constraint=false];\n",
+                    d, i
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Each block under its label, with its assignments and terminator indented
+impl fmt::Display for Cfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for block in &self.blocks {
+            writeln!(f, "{}:", block.label)?;
+            for (var, op) in &block.body {
+                writeln!(f, "  {} = {}", var, op)?;
+            }
+            writeln!(f, "  {}", self.terminator_text(&block.terminator))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anf;
+
+    /// Entry, then, else and join: a diamond
+    fn diamond() -> Cfg {
+        let expr = crate::parse("let x = 1, c = x < 2 in if (c) add1(x) else sub1(x)").unwrap();
+        build(anf::lower(&expr).unwrap())
+    }
+
+    /// The variable a block assigns `i`th
+    fn assigned(cfg: &Cfg, block: usize, i: usize) -> String {
+        cfg.blocks[block].body[i].0.clone()
+    }
+
+    fn set(vars: &[&String]) -> BTreeSet<String> {
+        vars.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn diamond_shape() {
+        let cfg = diamond();
+        assert_eq!(cfg.blocks.len(), 4);
+        let c = assigned(&cfg, 0, 1);
+        assert!(
+            matches!(&cfg.blocks[0].terminator, Terminator::Branch(Atom::Var(v), 1, 2) if *v == c)
+        );
+        assert!(matches!(cfg.blocks[1].terminator, Terminator::Jump(3)));
+        assert!(matches!(cfg.blocks[2].terminator, Terminator::Jump(3)));
+        assert!(matches!(cfg.blocks[3].terminator, Terminator::Return(_)));
+        assert_eq!(cfg.predecessors(), [vec![], vec![0], vec![0], vec![1, 2]]);
+        assert_eq!(cfg.layout(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn diamond_liveness() {
+        let cfg = diamond();
+        let x = assigned(&cfg, 0, 0);
+        // Each branch assigns the result, which the join returns
+        let result = assigned(&cfg, 1, 0);
+        assert_eq!(assigned(&cfg, 2, 0), result);
+        let liveness = cfg.liveness();
+        assert_eq!(liveness.live_in[0], set(&[]));
+        assert_eq!(liveness.live_out[0], set(&[&x]));
+        assert_eq!(liveness.live_in[1], set(&[&x]));
+        assert_eq!(liveness.live_in[2], set(&[&x]));
+        assert_eq!(liveness.live_out[1], set(&[&result]));
+        assert_eq!(liveness.live_out[2], set(&[&result]));
+        assert_eq!(liveness.live_in[3], set(&[&result]));
+        assert_eq!(liveness.live_out[3], set(&[]));
+    }
+
+    #[test]
+    fn diamond_dominators() {
+        let cfg = diamond();
+        let dominators = cfg.dominators();
+        assert_eq!(dominators[0], BTreeSet::from([0]));
+        assert_eq!(dominators[1], BTreeSet::from([0, 1]));
+        assert_eq!(dominators[2], BTreeSet::from([0, 2]));
+        // Neither branch dominates the join
+        assert_eq!(dominators[3], BTreeSet::from([0, 3]));
+        assert_eq!(
+            cfg.immediate_dominators(),
+            [None, Some(0), Some(0), Some(0)]
+        );
+    }
+
+    #[test]
+    fn nested_dominators() {
+        // Blocks: entry, outer then, else and join, then inner then, else and join
+        let expr = crate::parse("if (true) if (false) 1 else 2 else 3").unwrap();
+        let cfg = build(anf::lower(&expr).unwrap());
+        assert_eq!(cfg.predecessors()[3], [2, 6]);
+        assert_eq!(cfg.dominators()[6], BTreeSet::from([0, 1, 6]));
+        assert_eq!(
+            cfg.immediate_dominators(),
+            [None, Some(0), Some(0), Some(0), Some(1), Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn dot_output() {
+        let cfg = diamond();
+        let x = assigned(&cfg, 0, 0);
+        let dot = cfg.to_dot();
+        assert!(dot.starts_with("digraph cfg {\n  node [shape=box, fontname=monospace];\n"));
+        assert!(dot.ends_with("}\n"));
+        let lines: Vec<&str> = dot.lines().collect();
+        for i in 0..4 {
+            let node = format!("  b{} [label=\"{}:\\l  ; live: ", i, cfg.blocks[i].label);
+            assert!(lines.iter().any(|l| l.starts_with(&node)), "{}", node);
+        }
+        let then_node = lines.iter().find(|l| l.starts_with("  b1 [")).unwrap();
+        assert!(then_node.contains(&format!("; live: {}\\l", x)));
+        assert!(then_node.ends_with(&format!("jump {}\\l\"];", cfg.blocks[3].label)));
+        for edge in [
+            "  b0 -> b1 [label=\"true\"];",
+            "  b0 -> b2 [label=\"false\"];",
+            "  b1 -> b3;",
+            "  b2 -> b3;",
+            "  b0 -> b1 [style=dashed, color=gray, constraint=false];",
+            "  b0 -> b2 [style=dashed, color=gray, constraint=false];",
+            "  b0 -> b3 [style=dashed, color=gray, constraint=false];",
+        ] {
+            assert!(lines.contains(&edge), "{}", edge);
+        }
+        // Four nodes, four edges and three dominator edges between the braces
+        assert_eq!(lines.len(), 2 + 4 + 4 + 3 + 1);
+    }
+}
@@ -0,0 +1,237 @@
+use std::path::{Path, PathBuf};
+
+use asm::asm::Target;
+
+pub const USAGE: &str = "\
+Usage: asm <command> [options] <file>
+
+Commands:
+  check <file>       parse and compile, reporting errors only
+  emit-ast <file>    print the parsed expression
+  emit-asm <file>    print the generated assembly
+  build <file>       write an executable, or what --emit asks for
+  run <file>         run in the built-in simulator (--interp to interpret instead)
+  test [files]       run name|source|expected corpora (default samples/*.in)
+  repl               read expressions from stdin, keeping definitions
+  difftest [files]   compare compiled code against the interpreter
+  fuzz <target>      throw random inputs at the parser or the compiler
+  fmt [files]        rewrite files in the canonical layout (--check, --width <n>)
+
+Options:
+  -o <path>          output file (emit-* default to stdout, build to the input's stem)
+  --target <triple>  aarch64-apple-darwin or aarch64-linux-gnu (default: the host)
+  -O<level>          optimization level 0 or 1 (-O, -O2 and -O3 all mean -O1)
+  --emit <kind>      ast, anf, cfg-dot, asm, obj or exe
+  --runtime <path>   the C runtime linked into executables
+  --interp           run: evaluate the AST instead of simulating the compiled code
+  --overflow-checks  stop with an error when arithmetic overflows instead of using bignums
+  --no-peephole      leave the generated instructions as they are
+  --error-format <f> human (default) or json, one object per line
+
+Built executables size their heap from --heap-size <bytes>[k|m|g] or LISP_HEAP_SIZE
+(default 1m per semispace).";
+
+/// What a compiling command writes out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Emit {
+    Ast,
+    /// The program lowered to A-normal form
+    Anf,
+    /// The control-flow graph of the program, for Graphviz
+    CfgDot,
+    Asm,
+    Obj,
+    Exe,
+}
+
+impl Emit {
+    fn from_name(name: &str) -> Result<Emit, String> {
+        match name {
+            "ast" => Ok(Emit::Ast),
+            "anf" => Ok(Emit::Anf),
+            "cfg-dot" => Ok(Emit::CfgDot),
+            "asm" => Ok(Emit::Asm),
+            "obj" => Ok(Emit::Obj),
+            "exe" => Ok(Emit::Exe),
+            other => Err(format!(
+                "unknown --emit kind {} (expected ast, anf, cfg-dot, asm, obj or exe)",
+                other
+            )),
+        }
+    }
+
+    /// The extension `build` gives the output when no `-o` is given
+    pub fn extension(self) -> &'static str {
+        match self {
+            Emit::Ast => "ast",
+            Emit::Anf => "anf",
+            Emit::CfgDot => "dot",
+            Emit::Asm => "s",
+            Emit::Obj => "o",
+            Emit::Exe => "",
+        }
+    }
+}
+
+/// How problems in the source are written to stderr
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl ErrorFormat {
+    fn from_name(name: &str) -> Result<ErrorFormat, String> {
+        match name {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            other => Err(format!(
+                "unknown --error-format {} (expected human or json)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub target: Target,
+    pub opt_level: u8,
+    pub emit: Option<Emit>,
+    pub runtime: PathBuf,
+    pub interp: bool,
+    pub overflow_checks: bool,
+    pub peephole: bool,
+    pub error_format: ErrorFormat,
+}
+
+/// There is only one level of optimization, so the higher levels other compilers take are
+/// accepted as aliases of it
+fn opt_level(level: &str) -> Result<u8, String> {
+    match level {
+        "0" => Ok(0),
+        "" | "1" | "2" | "3" => Ok(1),
+        other => Err(format!("unknown optimization level -O{}", other)),
+    }
+}
+
+/// Parses the options that follow a compiling command; errors here are usage errors
+pub fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut target = Target::host();
+    let mut level = 0;
+    let mut emit = None;
+    let mut runtime = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/runtime/runtime.c");
+    let mut interp = false;
+    let mut overflow_checks = false;
+    let mut peephole = true;
+    let mut error_format = ErrorFormat::Human;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} expects a value", flag))
+        };
+        match arg.as_str() {
+            "-o" => output = Some(PathBuf::from(value("-o")?)),
+            "--target" => target = Target::from_triple(&value("--target")?)?,
+            "--emit" => emit = Some(Emit::from_name(&value("--emit")?)?),
+            "--runtime" => runtime = PathBuf::from(value("--runtime")?),
+            "--interp" => interp = true,
+            "--overflow-checks" => overflow_checks = true,
+            "--no-peephole" => peephole = false,
+            "--error-format" => error_format = ErrorFormat::from_name(&value("--error-format")?)?,
+            _ if arg.starts_with("--target=") => target = Target::from_triple(&arg[9..])?,
+            _ if arg.starts_with("--emit=") => emit = Some(Emit::from_name(&arg[7..])?),
+            _ if arg.starts_with("--error-format=") => {
+                error_format = ErrorFormat::from_name(&arg[15..])?
+            }
+            _ if arg.starts_with("-O") => level = opt_level(&arg[2..])?,
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option {}", arg))
+            }
+            file if input.is_none() => input = Some(PathBuf::from(file)),
+            file => return Err(format!("unexpected argument {}", file)),
+        }
+    }
+
+    Ok(Options {
+        input: input.ok_or("no input file")?,
+        output,
+        target,
+        opt_level: level,
+        emit,
+        runtime,
+        interp,
+        overflow_checks,
+        peephole,
+        error_format,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        parse_options(&args)
+    }
+
+    #[test]
+    fn optimization_levels() {
+        assert_eq!(parse(&["a.lisp"]).unwrap().opt_level, 0);
+        assert_eq!(parse(&["-O0", "a.lisp"]).unwrap().opt_level, 0);
+        for flag in ["-O", "-O1", "-O2", "-O3"] {
+            assert_eq!(parse(&[flag, "a.lisp"]).unwrap().opt_level, 1, "{}", flag);
+        }
+        assert_eq!(
+            parse(&["-O4", "a.lisp"]).unwrap_err(),
+            "unknown optimization level -O4"
+        );
+        assert_eq!(
+            parse(&["-Os", "a.lisp"]).unwrap_err(),
+            "unknown optimization level -Os"
+        );
+    }
+
+    #[test]
+    fn options_and_arguments() {
+        let options = parse(&[
+            "a.lisp",
+            "-o",
+            "out",
+            "--emit=obj",
+            "--target",
+            "aarch64-linux-gnu",
+            "--no-peephole",
+            "--error-format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(options.input, PathBuf::from("a.lisp"));
+        assert_eq!(options.output, Some(PathBuf::from("out")));
+        assert_eq!(options.emit, Some(Emit::Obj));
+        assert_eq!(options.target, Target::Linux);
+        assert!(!options.peephole);
+        assert_eq!(options.error_format, ErrorFormat::Json);
+        assert_eq!(parse(&["-"]).unwrap().input, PathBuf::from("-"));
+
+        assert_eq!(
+            parse(&["--frobnicate", "a.lisp"]).unwrap_err(),
+            "unknown option --frobnicate"
+        );
+        assert_eq!(
+            parse(&["a.lisp", "b.lisp"]).unwrap_err(),
+            "unexpected argument b.lisp"
+        );
+        assert_eq!(parse(&["a.lisp", "-o"]).unwrap_err(), "-o expects a value");
+        assert_eq!(parse(&[]).unwrap_err(), "no input file");
+        assert!(parse(&["--emit", "elf", "a.lisp"]).is_err());
+    }
+}
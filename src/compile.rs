@@ -1,20 +1,26 @@
 use std::collections::HashMap;
-
-use nom::combinator::map;
+use std::fmt;
 
 use crate::{
+    anf::{self, Anf, Atom, Op},
     asm::{
         Directive::{self, *},
         Operand::{self, *},
-        Register::*,
+        Register::{self, *},
     },
     ast::{BinaryOp, Expr, UnaryOp},
-    utils::gensym,
+    cfg::{self, Cfg, Terminator},
+    dce, fold, peephole, regalloc,
+    utils::{fresh_register, gensym},
+    Options,
 };
 
 pub const NUM_SHIFT: i64 = 2;
 pub const NUM_MASK: i64 = 0b11;
 pub const NUM_TAG: i64 = 0b00;
+/// The numbers a tagged word can hold
+pub const NUM_MIN: i64 = i64::MIN >> NUM_SHIFT;
+pub const NUM_MAX: i64 = i64::MAX >> NUM_SHIFT;
 
 pub const BOOL_SHIFT: i64 = 7;
 pub const BOOL_MASK: i64 = 0b1111111;
@@ -22,13 +28,70 @@ pub const BOOL_TAG: i64 = 0b0011111;
 
 pub const HEAP_MASK: i64 = 0b111;
 
+/// Floats are boxed: the tag marks a pointer to the 8 bytes of a double
+pub const FLOAT_TAG: i64 = 0b001;
+
 pub const PAIR_TAG: i64 = 0b010;
 
+/// Numbers outside the fixnum range live on the heap in the layout of `bignum::BigInt`
+pub const BIG_TAG: i64 = 0b011;
+
 pub const NIL_MASK: i64 = 0b11111111;
 pub const NIL_TAG: i64 = 0b11111111;
 
 pub const VEC_MASK: i64 = 0b111;
 pub const VEC_TAG: i64 = 0b101;
+
+/// The C runtime's slow paths for arithmetic and comparisons on anything but two fixnums, its
+/// conversions between integers and floats, and the collector the compiled code calls when the
+/// heap is full, with the number of values each takes
+pub const RUNTIME_FUNCTIONS: &[(&str, usize)] = &[
+    ("lisp_add", 2),
+    ("lisp_sub", 2),
+    ("lisp_add1", 1),
+    ("lisp_sub1", 1),
+    ("lisp_equal", 2),
+    ("lisp_less", 2),
+    ("lisp_is_zero", 1),
+    ("lisp_floor", 1),
+    ("lisp_round", 1),
+    ("lisp_to_float", 1),
+    ("lisp_collect", 1),
+];
+
+/// `lisp_entry` gets the heap pointer and the end of the heap, which it keeps in the two slots
+/// at the top of its frame, above the values of the program. The runtime finds them there
+/// when it allocates, and moves both when it collects. Slots are addressed from fp, which
+/// points at the saved fp and lr just above them; below the heap slots, the register allocator
+/// saves values across calls and spills what does not fit in registers.
+const HEAP_POINTER_SLOT: i64 = -8;
+const HEAP_LIMIT_SLOT: i64 = -16;
+const FIRST_SLOT: i64 = -24;
+
+/// Where checked arithmetic jumps when a result does not fit in a tagged word
+const OVERFLOW_LABEL: &str = "lisp_overflow";
+/// Where a function goes when its frame would reach past the runtime's stack limit
+const STACK_OVERFLOW_LABEL: &str = "lisp_stack_overflow";
+
+/// A program the code generator cannot translate
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Unbound(String),
+    Unsupported(String),
+    NumberOutOfRange(i64),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unbound(name) => write!(f, "unbound identifier {}", name),
+            Error::Unsupported(what) => write!(f, "{} is not supported by the compiler yet", what),
+            Error::NumberOutOfRange(n) => write!(f, "{} does not fit in a tagged number", n),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
 pub fn operand_of_num(x: i64) -> Operand {
     Imm(((x) << NUM_SHIFT) | NUM_TAG)
 }
@@ -38,147 +101,572 @@ pub fn operand_of_bool(b: bool) -> Operand {
     Operand::Imm((if b { 1i64 } else { 0i64 } << BOOL_SHIFT) | BOOL_TAG)
 }
 
-pub fn zf_to_bool() -> Vec<Directive> {
+pub fn zf_to_bool(dest: &Register) -> Vec<Directive> {
     vec![
-        Mov(Reg(X0), Imm(0)),
-        Cset(Reg(X0), "eq".to_string()),
-        Lsl(Reg(X0), Imm(BOOL_SHIFT)),
-        Orr(Reg(X0), Imm(BOOL_TAG)),
+        Mov(Reg(dest.clone()), Imm(0)),
+        Cset(Reg(dest.clone()), "eq".to_string()),
+        Lsl(Reg(dest.clone()), Imm(BOOL_SHIFT)),
+        Orr(Reg(dest.clone()), Imm(BOOL_TAG)),
     ]
 }
 
-pub fn setl_bool() -> Vec<Directive> {
+pub fn setl_bool(dest: &Register) -> Vec<Directive> {
     vec![
-        Mov(Reg(X0), Imm(0)),
-        Cset(Reg(X0), "lt".to_string()),
-        Lsl(Reg(X0), Imm(BOOL_SHIFT)),
-        Orr(Reg(X0), Imm(BOOL_TAG)),
+        Mov(Reg(dest.clone()), Imm(0)),
+        Cset(Reg(dest.clone()), "lt".to_string()),
+        Lsl(Reg(dest.clone()), Imm(BOOL_SHIFT)),
+        Orr(Reg(dest.clone()), Imm(BOOL_TAG)),
     ]
 }
 
 pub fn stack_address(index: i64) -> Operand {
-    MemOffset(Box::new(Imm(index)), Box::new(Reg(Sp)))
+    MemOffset(Box::new(Imm(index)), Box::new(Reg(Fp)))
+}
+
+/// Calls a function of the C runtime with its arguments in X0 and X1, leaving the result in
+/// X0. The register allocator saves the values live across the call in slots, whose extent
+/// `runtime_roots` passes along.
+fn call_runtime(name: &str, arguments: Vec<Operand>) -> Vec<Directive> {
+    let mut call: Vec<Directive> = arguments
+        .into_iter()
+        .zip([X0, X1])
+        .map(|(argument, register)| Mov(Reg(register), argument))
+        .collect();
+    call.push(Bl(name.to_string()));
+    call
+}
+
+/// Tells a runtime function, in the registers after its arguments, where the slots above
+/// `stack_index` are, from the lowest one up to the heap limit, so that the collector can find
+/// and update the heap pointers among them
+fn runtime_roots(name: &str, stack_index: i64) -> Vec<Directive> {
+    let arguments = RUNTIME_FUNCTIONS
+        .iter()
+        .find(|(f, _)| *f == name)
+        .map_or(1, |(_, n)| *n);
+    let (bottom, top) = match arguments {
+        1 => (X1, X2),
+        _ => (X2, X3),
+    };
+    vec![
+        Mov(Reg(bottom.clone()), Reg(Fp)),
+        Sub(Reg(bottom), Imm(-stack_index - 8)),
+        Mov(Reg(top.clone()), Reg(Fp)),
+        Sub(Reg(top), Imm(-HEAP_LIMIT_SLOT)),
+    ]
+}
+
+/// Runs `fast`, which leaves its result in `dest`, when the operands are fixnums. Bignums,
+/// operands of the wrong type and whatever `fast` branches to its label for go to `runtime`
+/// instead.
+fn with_slow_path(
+    operands: &[Register],
+    fast: impl FnOnce(&str) -> Vec<Directive>,
+    runtime: &str,
+    dest: &Register,
+) -> Vec<Directive> {
+    let slow = gensym("slow");
+    let done = gensym("done");
+    let tags = fresh_register();
+    let mut check = vec![Mov(Reg(tags.clone()), Reg(operands[0].clone()))];
+    if let Some(second) = operands.get(1) {
+        check.push(Orr(Reg(tags.clone()), Reg(second.clone())));
+    }
+    [
+        check,
+        vec![
+            And(Reg(tags.clone()), Imm(NUM_MASK)),
+            Cmp(Reg(tags), Imm(NUM_TAG)),
+            Bne(slow.clone()),
+        ],
+        fast(&slow),
+        vec![B(done.clone()), Label(slow)],
+        call_runtime(runtime, operands.iter().cloned().map(Reg).collect()),
+        vec![Mov(Reg(dest.clone()), Reg(X0)), Label(done)],
+    ]
+    .concat()
+}
+
+/// `op` on fixnums, leaving the result in `dest`. Tagged numbers keep their value in the upper
+/// bits, so a 64-bit overflow is exactly a tagged overflow: it either stops the program or, by
+/// default, takes the slow path that promotes to a bignum.
+fn fixnum_arithmetic(
+    options: &Options,
+    op: fn(Operand, Operand) -> Directive,
+    (first, second): (&Register, Operand),
+    dest: &Register,
+    slow: &str,
+) -> Vec<Directive> {
+    let overflow = if options.overflow_checks {
+        OVERFLOW_LABEL
+    } else {
+        slow
+    };
+    let result = fresh_register();
+    vec![
+        Mov(Reg(result.clone()), Reg(first.clone())),
+        op(Reg(result.clone()), second),
+        Bvs(overflow.to_string()),
+        Mov(Reg(dest.clone()), Reg(result)),
+    ]
 }
 
-fn compile_binary_primitive(stack_index: i64, expr: BinaryOp) -> Vec<Directive> {
-    match expr {
-        BinaryOp::Add => vec![
-            Ldr(Reg(X1), stack_address(stack_index)),
-            Add(Reg(X0), Reg(X1)),
+/// Bumps the heap pointer past an object of `words` words, leaving its address in `address`.
+/// When the heap is too full for it the runtime collects, or stops if that frees too little.
+fn allocate(words: i64, address: &Register) -> Vec<Directive> {
+    let done = gensym("allocated");
+    let limit = fresh_register();
+    let bump = vec![
+        Ldr(Reg(address.clone()), stack_address(HEAP_POINTER_SLOT)),
+        Add(Reg(address.clone()), Imm(words * 8)),
+    ];
+    [
+        bump.clone(),
+        vec![
+            Ldr(Reg(limit.clone()), stack_address(HEAP_LIMIT_SLOT)),
+            Cmp(Reg(address.clone()), Reg(limit)),
+            Bls(done.clone()),
         ],
-        BinaryOp::Sub => vec![
-            Mov(Reg(X1), Reg(X0)),
-            Ldr(Reg(X0), stack_address(stack_index)),
-            Sub(Reg(X0), Reg(X1)),
+        call_runtime("lisp_collect", vec![Imm(words)]),
+        bump,
+        vec![
+            Label(done),
+            Str(stack_address(HEAP_POINTER_SLOT), Reg(address.clone())),
+            Sub(Reg(address.clone()), Imm(words * 8)),
         ],
-        BinaryOp::Eq => [
+    ]
+    .concat()
+}
+
+fn compile_binary_primitive(
+    options: &Options,
+    op: BinaryOp,
+    (first, second): (&Register, &Register),
+    dest: &Register,
+) -> Result<Vec<Directive>, Error> {
+    let generic = |fast: &dyn Fn(&str) -> Vec<Directive>, runtime| {
+        with_slow_path(&[first.clone(), second.clone()], fast, runtime, dest)
+    };
+    Ok(match op {
+        BinaryOp::Add => generic(
+            &|slow| fixnum_arithmetic(options, Adds, (first, Reg(second.clone())), dest, slow),
+            "lisp_add",
+        ),
+        BinaryOp::Sub => generic(
+            &|slow| fixnum_arithmetic(options, Subs, (first, Reg(second.clone())), dest, slow),
+            "lisp_sub",
+        ),
+        BinaryOp::Eq => generic(
+            &|_| {
+                [
+                    vec![Cmp(Reg(first.clone()), Reg(second.clone()))],
+                    zf_to_bool(dest),
+                ]
+                .concat()
+            },
+            "lisp_equal",
+        ),
+        BinaryOp::Lt => generic(
+            &|_| {
+                [
+                    vec![Cmp(Reg(first.clone()), Reg(second.clone()))],
+                    setl_bool(dest),
+                ]
+                .concat()
+            },
+            "lisp_less",
+        ),
+        // Both halves stay live across the allocation, where a collection can move them
+        BinaryOp::Pair => {
+            let address = fresh_register();
+            [
+                allocate(2, &address),
+                vec![
+                    Str(RegOffset(address.clone(), 0), Reg(first.clone())),
+                    Str(RegOffset(address.clone(), 8), Reg(second.clone())),
+                    Mov(Reg(dest.clone()), Reg(address)),
+                    Orr(Reg(dest.clone()), Imm(PAIR_TAG)),
+                ],
+            ]
+            .concat()
+        }
+        op => return Err(Error::Unsupported(format!("`{}`", op))),
+    })
+}
+
+fn compile_unary_primitive(
+    options: &Options,
+    op: UnaryOp,
+    operand: &Register,
+    dest: &Register,
+) -> Result<Vec<Directive>, Error> {
+    let value = || Reg(operand.clone());
+    let result = || Reg(dest.clone());
+    Ok(match op {
+        UnaryOp::Add1 => with_slow_path(
+            std::slice::from_ref(operand),
+            |slow| fixnum_arithmetic(options, Adds, (operand, operand_of_num(1)), dest, slow),
+            "lisp_add1",
+            dest,
+        ),
+        UnaryOp::Not => [vec![Cmp(value(), operand_of_bool(false))], zf_to_bool(dest)].concat(),
+        UnaryOp::Sub1 => with_slow_path(
+            std::slice::from_ref(operand),
+            |slow| fixnum_arithmetic(options, Subs, (operand, operand_of_num(1)), dest, slow),
+            "lisp_sub1",
+            dest,
+        ),
+        // A fixnum, a bignum or a float
+        UnaryOp::IsNum => {
+            let (tag, big) = (fresh_register(), fresh_register());
             vec![
-                Ldr(Reg(X1), stack_address(stack_index)),
-                Cmp(Reg(X1), Reg(X0)),
-            ],
-            zf_to_bool(),
+                Mov(Reg(tag.clone()), value()),
+                And(Reg(tag.clone()), Imm(HEAP_MASK)),
+                Cmp(Reg(tag.clone()), Imm(BIG_TAG)),
+                Cset(Reg(big.clone()), "eq".to_string()),
+                Cmp(Reg(tag.clone()), Imm(FLOAT_TAG)),
+                Cset(Reg(tag.clone()), "eq".to_string()),
+                Orr(Reg(tag.clone()), Reg(big)),
+                Mov(result(), value()),
+                And(result(), Imm(NUM_MASK)),
+                Cmp(result(), Imm(NUM_TAG)),
+                Cset(result(), "eq".to_string()),
+                Orr(result(), Reg(tag)),
+                Lsl(result(), Imm(BOOL_SHIFT)),
+                Orr(result(), Imm(BOOL_TAG)),
+            ]
+        }
+        UnaryOp::IsZero => with_slow_path(
+            std::slice::from_ref(operand),
+            |_| [vec![Cmp(value(), operand_of_num(0))], zf_to_bool(dest)].concat(),
+            "lisp_is_zero",
+            dest,
+        ),
+        // Integers are already whole
+        UnaryOp::Floor => with_slow_path(
+            std::slice::from_ref(operand),
+            |_| vec![Mov(result(), value())],
+            "lisp_floor",
+            dest,
+        ),
+        UnaryOp::Round => with_slow_path(
+            std::slice::from_ref(operand),
+            |_| vec![Mov(result(), value())],
+            "lisp_round",
+            dest,
+        ),
+        UnaryOp::ToFloat => [
+            call_runtime("lisp_to_float", vec![value()]),
+            vec![Mov(result(), Reg(X0))],
         ]
         .concat(),
-        BinaryOp::Lt => [
+        op => return Err(Error::Unsupported(format!("`{}`", op))),
+    })
+}
+
+/// The virtual register of a variable
+fn variable(variables: &mut HashMap<String, Register>, var: &str) -> Register {
+    variables
+        .entry(var.to_string())
+        .or_insert_with(fresh_register)
+        .clone()
+}
+
+/// Code that leaves the value of `atom` in `dest`
+fn compile_atom(
+    variables: &mut HashMap<String, Register>,
+    atom: &Atom,
+    dest: &Register,
+) -> Vec<Directive> {
+    let result = || Reg(dest.clone());
+    match atom {
+        Atom::Unit => vec![Mov(result(), Imm(NIL_TAG))],
+        Atom::Num(x) => vec![Mov(result(), operand_of_num(*x))],
+        // The box of a literal sits in the code, where nothing can change it
+        Atom::Float(x) => {
+            let boxed = gensym("float");
+            let after = gensym("after_float");
             vec![
-                Ldr(Reg(X1), stack_address(stack_index)),
-                Cmp(Reg(X1), Reg(X0)),
-            ],
-            setl_bool(),
-        ]
-        .concat(),
-        _ => vec![],
+                Adr(result(), boxed.clone()),
+                Orr(result(), Imm(FLOAT_TAG)),
+                B(after.clone()),
+                Align(3),
+                Label(boxed),
+                DqInt(x.to_bits() as i64),
+                Label(after),
+            ]
+        }
+        Atom::Bool(b) => vec![Mov(result(), operand_of_bool(*b))],
+        Atom::Var(var) => vec![Mov(result(), Reg(variable(variables, var)))],
     }
 }
 
-fn compile_unary_primitive(expr: UnaryOp) -> Vec<Directive> {
-    match expr {
-        UnaryOp::Add1 => vec![Add(Reg(X0), operand_of_num(1))],
-        UnaryOp::Not => [vec![Cmp(Reg(X0), operand_of_bool(false))], zf_to_bool()].concat(),
-        UnaryOp::Sub1 => vec![Sub(Reg(X0), operand_of_num(1))],
-        UnaryOp::IsNum => [
-            vec![And(Reg(X0), Imm(NUM_MASK)), Cmp(Reg(X0), Imm(NUM_TAG))],
-            zf_to_bool(),
-        ]
-        .concat(),
-        UnaryOp::IsZero => [vec![Cmp(Reg(X0), operand_of_num(0))], zf_to_bool()].concat(),
-        _ => vec![],
+/// The register holding `atom`: a variable's own, or a temporary loaded with a literal
+fn atom_register(
+    variables: &mut HashMap<String, Register>,
+    atom: &Atom,
+) -> (Vec<Directive>, Register) {
+    match atom {
+        Atom::Var(var) => (vec![], variable(variables, var)),
+        literal => {
+            let register = fresh_register();
+            (compile_atom(variables, literal, &register), register)
+        }
     }
 }
-pub fn compile_expr(symtab: &HashMap<String, i64>, stack_index: i64, expr: Expr) -> Vec<Directive> {
-    match expr {
-        Expr::Unit => vec![Mov(Reg(X0), Imm(NIL_TAG))],
-        Expr::Num(x) => vec![Mov(Reg(X0), operand_of_num(x))],
-        Expr::Bool(b) => vec![Mov(Reg(X0), operand_of_bool(b))],
-        Expr::UnOp(p1, expr) => [
-            compile_expr(symtab, stack_index, *expr),
-            compile_unary_primitive(p1),
-        ]
-        .concat(),
-        Expr::BinOp(f, arg1, arg2) => [
-            compile_expr(symtab, stack_index, *arg1),
-            vec![Str(stack_address(stack_index), Reg(X0))],
-            compile_expr(symtab, stack_index - 8, *arg2),
-            compile_binary_primitive(stack_index, f),
-        ]
-        .concat(),
-        Expr::If(test_expr, then_expr, else_expr) => {
-            let then_label = gensym("then");
-            let else_label = gensym("else");
-            let continue_label = gensym("continue");
+
+fn compile_op(
+    options: &Options,
+    variables: &mut HashMap<String, Register>,
+    op: &Op,
+    dest: &Register,
+) -> Result<Vec<Directive>, Error> {
+    Ok(match op {
+        Op::Atom(atom) => compile_atom(variables, atom, dest),
+        Op::UnOp(op, atom) => {
+            let (load, operand) = atom_register(variables, atom);
             [
-                compile_expr(symtab, stack_index, *test_expr),
-                vec![
-                    Cmp(Reg(X0), operand_of_bool(false)),
-                    Beq(else_label.clone()),
-                    Label(then_label.clone()),
-                ],
-                compile_expr(symtab, stack_index, *then_expr),
-                vec![B(continue_label.clone()), Label(else_label)],
-                compile_expr(symtab, stack_index, *else_expr),
-                vec![Label(continue_label)],
+                load,
+                compile_unary_primitive(options, op.clone(), &operand, dest)?,
+            ]
+            .concat()
+        }
+        Op::BinOp(op, atom1, atom2) => {
+            let (load1, first) = atom_register(variables, atom1);
+            let (load2, second) = atom_register(variables, atom2);
+            [
+                load1,
+                load2,
+                compile_binary_primitive(options, op.clone(), (&first, &second), dest)?,
             ]
             .concat()
         }
-        Expr::Id(s) if symtab.contains_key(&s) => {
-            vec![Ldr(Reg(X0), stack_address(*symtab.get(&s).unwrap()))]
+        Op::TriOp(op, ..) => return Err(Error::Unsupported(format!("`{}`", op))),
+    })
+}
+
+/// Lays out the blocks of `cfg`, leaving the value it returns in X0. Each variable lives in a
+/// virtual register of its own; jumps to the block laid out next are left out.
+pub fn compile_cfg(options: &Options, cfg: &Cfg) -> Result<Vec<Directive>, Error> {
+    let mut variables = HashMap::new();
+    let label = |b: &usize| cfg.blocks[*b].label.clone();
+    let exit = gensym("return");
+    let order = cfg.layout();
+    let mut code = Vec::new();
+    for (position, &b) in order.iter().enumerate() {
+        let block = &cfg.blocks[b];
+        let next = order.get(position + 1);
+        code.push(Label(block.label.clone()));
+        for (var, op) in &block.body {
+            let dest = variable(&mut variables, var);
+            code.extend(compile_op(options, &mut variables, op, &dest)?);
         }
-        Expr::Let(bindings, body) => {
-            let mut compiled = Vec::new();
-            let mut new_symtab = symtab.clone();
-
-            for (i, (var, exp)) in bindings.iter().enumerate() {
-                let mut exp_code =
-                    compile_expr(&new_symtab, stack_index - (8 * i as i64), *exp.clone());
-                compiled.append(&mut exp_code);
-                compiled.push(Str(stack_address(stack_index - (8 * i as i64)), Reg(X0)));
-                new_symtab.insert(var.clone(), stack_index - (8 * i as i64));
+        match &block.terminator {
+            Terminator::Jump(target) if Some(target) == next => {}
+            Terminator::Jump(target) => code.push(B(label(target))),
+            Terminator::Branch(cond, then_block, else_block) => {
+                let (load, test) = atom_register(&mut variables, cond);
+                code.extend(load);
+                code.push(Cmp(Reg(test), operand_of_bool(false)));
+                if Some(else_block) == next {
+                    code.push(Bne(label(then_block)));
+                } else {
+                    code.push(Beq(label(else_block)));
+                    if Some(then_block) != next {
+                        code.push(B(label(then_block)));
+                    }
+                }
+            }
+            Terminator::Return(a) => {
+                code.extend(compile_atom(&mut variables, a, &X0));
+                if next.is_some() {
+                    code.push(B(exit.clone()));
+                }
             }
+        }
+    }
+    code.push(Label(exit));
+    Ok(code)
+}
 
-            let body_code = compile_expr(
-                &new_symtab,
-                stack_index - (8 * bindings.len() as i64),
-                *body,
-            );
-            compiled.extend(body_code);
+/// Lowers a program to A-normal form, optimized as far as `options.opt_level` asks
+pub fn lower(expr: &Expr, options: &Options) -> Result<Anf, Error> {
+    if options.opt_level == 0 {
+        return anf::lower(expr);
+    }
+    let expr = dce::eliminate(expr.clone(), options);
+    Ok(fold::fold(anf::lower(&expr)?))
+}
 
-            compiled
-        }
-        Expr::Do(exps) => exps
-            .into_iter()
-            .flat_map(|e| compile_expr(symtab, stack_index, *e))
+pub fn compile(expr: Expr) -> Result<Vec<Directive>, Error> {
+    compile_with(expr, &Options::default())
+}
+
+/// Reports `message` through the runtime, which never returns
+fn error_handler(label: &str, message: &str) -> Vec<Directive> {
+    let message_label = format!("{}_message", label);
+    vec![
+        Label(label.to_string()),
+        Adr(Reg(X0), message_label.clone()),
+        Bl("lisp_error".to_string()),
+        Label(message_label),
+        DqString(message.to_string()),
+        // Whatever code follows the message must start on an instruction boundary
+        Align(2),
+    ]
+}
+
+/// The lowest slot below fp that `body` uses
+fn deepest_slot(body: &[Directive]) -> i64 {
+    body.iter()
+        .filter_map(|d| match d {
+            Str(MemOffset(offset, base), _) | Ldr(_, MemOffset(offset, base)) => {
+                match (&**offset, &**base) {
+                    (Imm(index), Reg(Fp)) => Some(*index),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .min()
+        .unwrap_or(0)
+        .min(HEAP_LIMIT_SLOT)
+}
+
+/// Pushes a frame record, so fp chains back through the callers, and reserves `frame` bytes
+/// below it. The runtime passes the lowest address the stack may reach in X2; a frame that
+/// would go past it stops the program instead of overwriting whatever lies below the stack.
+fn prologue(frame: i64) -> Vec<Directive> {
+    vec![
+        Sub(Reg(Sp), Imm(16)),
+        Stp(Reg(Fp), Reg(Lr), stack_top()),
+        Mov(Reg(Fp), Reg(Sp)),
+        Mov(Reg(X9), Reg(Sp)),
+        Sub(Reg(X9), Imm(frame)),
+        Cmp(Reg(X9), Reg(X2)),
+        Blt(STACK_OVERFLOW_LABEL.to_string()),
+        Mov(Reg(Sp), Reg(X9)),
+    ]
+}
+
+fn epilogue() -> Vec<Directive> {
+    vec![
+        Mov(Reg(Sp), Reg(Fp)),
+        Ldp(Reg(Fp), Reg(Lr), stack_top()),
+        Add(Reg(Sp), Imm(16)),
+        Ret,
+    ]
+}
+
+fn stack_top() -> Operand {
+    MemOffset(Box::new(Imm(0)), Box::new(Reg(Sp)))
+}
+
+pub fn compile_with(expr: Expr, options: &Options) -> Result<Vec<Directive>, Error> {
+    let start = [
+        vec![
+            Global("lisp_entry".to_string()),
+            Extern("lisp_error".to_string()),
+        ],
+        RUNTIME_FUNCTIONS
+            .iter()
+            .map(|(f, _)| Extern(f.to_string()))
             .collect(),
-        _ => vec![],
-    }
+    ]
+    .concat();
+    let cfg = cfg::build(lower(&expr, options)?);
+    let body = compile_cfg(options, &cfg)?;
+    let allocation = regalloc::allocate(&body, FIRST_SLOT, runtime_roots);
+
+    // The callee-saved registers the body uses go in slots below all of its own
+    let code = if options.peephole {
+        peephole::optimize(&allocation.code)
+    } else {
+        allocation.code
+    };
+    let deepest = deepest_slot(&code);
+    let slots = allocation
+        .callee_saved
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (deepest - 8 * (i as i64 + 1), r));
+    let (save, restore): (Vec<_>, Vec<_>) = slots
+        .map(|(slot, r)| {
+            (
+                Str(stack_address(slot), Reg(r.clone())),
+                Ldr(Reg(r.clone()), stack_address(slot)),
+            )
+        })
+        .unzip();
+    let frame = -(deepest - 8 * save.len() as i64);
+
+    let entry = [
+        vec![Label("lisp_entry".to_string())],
+        prologue((frame + 15) / 16 * 16),
+        vec![
+            Str(stack_address(HEAP_POINTER_SLOT), Reg(X0)),
+            Str(stack_address(HEAP_LIMIT_SLOT), Reg(X1)),
+        ],
+        save,
+    ]
+    .concat();
+    let overflow = if options.overflow_checks {
+        error_handler(OVERFLOW_LABEL, "overflow")
+    } else {
+        vec![]
+    };
+    Ok([
+        start,
+        entry,
+        code,
+        restore,
+        epilogue(),
+        error_handler(STACK_OVERFLOW_LABEL, "stack overflow"),
+        overflow,
+    ]
+    .concat())
 }
 
-pub fn compile(expr: Expr) -> Vec<Directive> {
-    let start = vec![
-        Global("lisp_entry".to_string()),
-        Extern("lisp_error".to_string()),
-        Label("lisp_entry".to_string()),
-    ];
-    let body = compile_expr(&HashMap::new(), -8, expr);
-    let ret = vec![Ret];
-    [start, body, ret].concat()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::encode;
+
+    /// The error messages live among the instructions, which must still all be word aligned
+    #[test]
+    fn instructions_stay_aligned_after_messages() {
+        let expr = crate::parse("let x = add1(2305843009213693951) in pair(x, x - 1)").unwrap();
+        for overflow_checks in [false, true] {
+            let options = Options {
+                overflow_checks,
+                ..Options::default()
+            };
+            let object = encode(&compile_with(expr.clone(), &options).unwrap()).unwrap();
+            let text = &object.sections[0];
+            assert!(text.relocs.len() > 1);
+            for reloc in &text.relocs {
+                assert_eq!(
+                    reloc.offset % 4,
+                    0,
+                    "{} at {:#x}",
+                    reloc.symbol,
+                    reloc.offset
+                );
+            }
+            for (offset, code) in &text.mapping {
+                assert!(!code || offset % 4 == 0, "code at {:#x}", offset);
+            }
+            for symbol in &object.symbols {
+                if symbol.section == Some(0) && !symbol.name.ends_with("_message") {
+                    assert_eq!(
+                        symbol.value % 4,
+                        0,
+                        "{} at {:#x}",
+                        symbol.name,
+                        symbol.value
+                    );
+                }
+            }
+        }
+    }
 }
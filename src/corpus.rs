@@ -0,0 +1,195 @@
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::ast::Expr;
+use crate::compile::compile;
+use crate::eval;
+use crate::sim::{self, Output};
+
+/// One `name|source|expected,` record
+#[derive(Clone, Debug)]
+pub struct Case {
+    pub name: String,
+    pub source: String,
+    pub expected: String,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        actual: String,
+    },
+    Timeout,
+    /// The source is not a program, as with a corpus written for another syntax
+    Unparseable,
+}
+
+/// Reads up to the first `delimiter` that no `\\` escapes, returning the text before it,
+/// unescaped, and what follows it, if there is such a delimiter
+fn field(text: &str, delimiter: char) -> (String, Option<&str>) {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next().map(|(_, c)| c)),
+            c if c == delimiter => return (value, Some(&text[i + 1..])),
+            c => value.push(c),
+        }
+    }
+    (value, None)
+}
+
+/// Splits a corpus file into cases. The source runs up to the second `|` and may span lines;
+/// the expected value runs up to the next `,`, which the last case may leave out. A `\\`
+/// makes the character after it, such as a `|` or `,`, part of the field.
+pub fn parse_corpus(contents: &str) -> Result<Vec<Case>, String> {
+    let mut cases = Vec::new();
+    let mut rest = contents;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(cases);
+        }
+        let (name, after_name) = match field(rest, '|') {
+            (name, Some(after)) => (name, after),
+            _ => return Err(format!("missing '|' after case name near {:?}", rest)),
+        };
+        let (source, after_source) = match field(after_name, '|') {
+            (source, Some(after)) => (source, after),
+            _ => return Err(format!("missing '|' after source of case {}", name.trim())),
+        };
+        let (expected, after_expected) = field(after_source, ',');
+        cases.push(Case {
+            name: name.trim().to_string(),
+            source: source.trim().to_string(),
+            expected: expected.trim().to_string(),
+        });
+        rest = after_expected.unwrap_or("");
+    }
+}
+
+/// Compiles and runs a program, or interprets it, returning what it printed
+fn execute(expr: Expr, interp: bool) -> Result<Output, String> {
+    if interp {
+        eval::run(&expr)
+    } else {
+        sim::simulate(&compile(expr).map_err(|e| e.to_string())?)
+    }
+}
+
+/// An expected value of `error` accepts any failure; `Stuck[...]` must match the runtime error
+fn matches(expected: &str, result: &Result<Output, String>) -> bool {
+    match result {
+        Ok(output) if output.exit_code == 0 => output.stdout.trim_end() == expected,
+        Ok(output) => expected == "error" || output.stdout.trim_end() == expected,
+        Err(_) => expected == "error",
+    }
+}
+
+fn describe(result: &Result<Output, String>) -> String {
+    match result {
+        Ok(output) if output.exit_code == 0 => output.stdout.trim_end().to_string(),
+        Ok(output) => format!("{} (exit {})", output.stdout.trim_end(), output.exit_code),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+/// Runs one case on its own thread so a runaway program is cut off after `timeout`
+pub fn run_case(case: &Case, timeout: Duration, interp: bool) -> Outcome {
+    let expr = match crate::parse(&case.source) {
+        Ok(expr) => expr,
+        Err(_) => return Outcome::Unparseable,
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(execute(expr, interp));
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) if matches(&case.expected, &result) => Outcome::Pass,
+        Ok(result) => Outcome::Fail {
+            actual: describe(&result),
+        },
+        Err(RecvTimeoutError::Timeout) => Outcome::Timeout,
+        Err(RecvTimeoutError::Disconnected) => Outcome::Fail {
+            actual: "panicked".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(cases: &[Case]) -> Vec<(&str, &str, &str)> {
+        cases
+            .iter()
+            .map(|c| (c.name.as_str(), c.source.as_str(), c.expected.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn records() {
+        let cases = parse_corpus("add|2 + 3|5,\nmultiline|\nlet x = 1 in\n  x + 1|\n2,\n").unwrap();
+        assert_eq!(
+            fields(&cases),
+            [
+                ("add", "2 + 3", "5"),
+                ("multiline", "let x = 1 in\n  x + 1", "2")
+            ]
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        let cases =
+            parse_corpus(r"a\|b|pair(1\, 2)|(pair 1 2),back\\slash|1|Stuck[x\, y],").unwrap();
+        assert_eq!(
+            fields(&cases),
+            [
+                ("a|b", "pair(1, 2)", "(pair 1 2)"),
+                (r"back\slash", "1", "Stuck[x, y]")
+            ]
+        );
+    }
+
+    #[test]
+    fn expected_errors() {
+        let cases = parse_corpus("bad|add1(true)|error,\nstuck|true + 1|Stuck[+],").unwrap();
+        assert_eq!(
+            fields(&cases),
+            [
+                ("bad", "add1(true)", "error"),
+                ("stuck", "true + 1", "Stuck[+]")
+            ]
+        );
+        let timeout = Duration::from_secs(5);
+        for case in &cases {
+            for interp in [false, true] {
+                assert!(matches!(run_case(case, timeout, interp), Outcome::Pass));
+            }
+        }
+    }
+
+    #[test]
+    fn missing_trailing_comma() {
+        let cases = parse_corpus("one|1|1,\ntwo|2|2\n").unwrap();
+        assert_eq!(fields(&cases), [("one", "1", "1"), ("two", "2", "2")]);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(parse_corpus("no separators").is_err());
+        assert!(parse_corpus("name|source only").is_err());
+    }
+
+    #[test]
+    fn unparseable_source() {
+        let case = &parse_corpus("sexp|(+ 2 3)|5,").unwrap()[0];
+        assert!(matches!(
+            run_case(case, Duration::from_secs(5), false),
+            Outcome::Unparseable
+        ));
+    }
+}
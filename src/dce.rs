@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::{BinaryOp, Expr, UnaryOp},
+    compile::{NUM_MAX, NUM_MIN},
+    Options,
+};
+
+/// What little is known about the value of an expression before it runs
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Int,
+    Float,
+    Bool,
+    Unit,
+    Pair,
+    Any,
+}
+
+impl Kind {
+    fn numeric(self) -> bool {
+        matches!(self, Kind::Int | Kind::Float)
+    }
+}
+
+/// Removes the code whose value is never used and whose running could not be told apart from
+/// skipping it: expressions discarded by a `{ ...; ... }`, unused `let` bindings, and the
+/// branch an `if` on a literal never takes.
+///
+/// An expression is pure when it is sure to produce a value without an error. Anything that
+/// could fail, from `true + 1` to an overflow under `--overflow-checks`, is kept, as is anything
+/// the compiler would reject, such as an unbound name.
+pub fn eliminate(expr: Expr, options: &Options) -> Expr {
+    let analysis = Analysis {
+        overflow_checks: options.overflow_checks,
+    };
+    analysis.eliminate(expr, &HashMap::new())
+}
+
+struct Analysis {
+    overflow_checks: bool,
+}
+
+impl Analysis {
+    fn eliminate(&self, expr: Expr, env: &HashMap<String, Kind>) -> Expr {
+        match expr {
+            Expr::UnOp(op, e) => Expr::UnOp(op, Box::new(self.eliminate(*e, env))),
+            Expr::BinOp(op, e1, e2) => Expr::BinOp(
+                op,
+                Box::new(self.eliminate(*e1, env)),
+                Box::new(self.eliminate(*e2, env)),
+            ),
+            Expr::TriOp(op, e1, e2, e3) => Expr::TriOp(
+                op,
+                Box::new(self.eliminate(*e1, env)),
+                Box::new(self.eliminate(*e2, env)),
+                Box::new(self.eliminate(*e3, env)),
+            ),
+            Expr::If(cond, then_branch, else_branch) => match self.eliminate(*cond, env) {
+                Expr::Bool(false) => self.eliminate(*else_branch, env),
+                cond @ (Expr::Num(_) | Expr::Float(_) | Expr::Bool(true) | Expr::Unit)
+                    if self.pure(&cond, env) =>
+                {
+                    self.eliminate(*then_branch, env)
+                }
+                cond => Expr::If(
+                    Box::new(cond),
+                    Box::new(self.eliminate(*then_branch, env)),
+                    Box::new(self.eliminate(*else_branch, env)),
+                ),
+            },
+            Expr::Let(bindings, body) => {
+                let mut env = env.clone();
+                let mut analyzed = Vec::new();
+                for (name, e) in bindings {
+                    let e = self.eliminate(*e, &env);
+                    let pure = self.pure(&e, &env);
+                    env.insert(name.clone(), self.kind(&e, &env));
+                    analyzed.push((name, e, pure));
+                }
+                let body = self.eliminate(*body, &env);
+                // From the last binding back, so that dropping one can leave earlier ones unused
+                let mut kept: Vec<(String, Box<Expr>)> = Vec::new();
+                for (name, e, pure) in analyzed.into_iter().rev() {
+                    if !pure || used(&name, &kept, &body) {
+                        kept.insert(0, (name, Box::new(e)));
+                    }
+                }
+                if kept.is_empty() {
+                    body
+                } else {
+                    Expr::Let(kept, Box::new(body))
+                }
+            }
+            Expr::Do(exprs) if exprs.is_empty() => Expr::Do(exprs),
+            Expr::Do(exprs) => {
+                let last = exprs.len() - 1;
+                let mut kept: Vec<Box<Expr>> = exprs
+                    .into_iter()
+                    .map(|e| self.eliminate(*e, env))
+                    .enumerate()
+                    .filter(|(i, e)| *i == last || !self.pure(e, env))
+                    .map(|(_, e)| Box::new(e))
+                    .collect();
+                if kept.len() == 1 {
+                    *kept.pop().unwrap()
+                } else {
+                    Expr::Do(kept)
+                }
+            }
+            Expr::FuncDef(name, params, body) => {
+                let mut env = env.clone();
+                env.extend(params.iter().map(|p| (p.clone(), Kind::Any)));
+                Expr::FuncDef(name, params, Box::new(self.eliminate(*body, &env)))
+            }
+            Expr::FuncCall(callee, args) => Expr::FuncCall(
+                Box::new(self.eliminate(*callee, env)),
+                args.into_iter()
+                    .map(|a| Box::new(self.eliminate(*a, env)))
+                    .collect(),
+            ),
+            expr => expr,
+        }
+    }
+
+    fn kind(&self, expr: &Expr, env: &HashMap<String, Kind>) -> Kind {
+        match expr {
+            Expr::Num(_) => Kind::Int,
+            Expr::Float(_) => Kind::Float,
+            Expr::Bool(_) => Kind::Bool,
+            Expr::Unit => Kind::Unit,
+            Expr::Id(s) => env.get(s).copied().unwrap_or(Kind::Any),
+            Expr::UnOp(op, e) => match (op, self.kind(e, env)) {
+                (UnaryOp::Add1 | UnaryOp::Sub1, kind) if kind.numeric() => kind,
+                (UnaryOp::Floor | UnaryOp::Round, kind) if kind.numeric() => Kind::Int,
+                (UnaryOp::ToFloat, _) => Kind::Float,
+                (UnaryOp::IsZero | UnaryOp::IsNum | UnaryOp::Not, _) => Kind::Bool,
+                _ => Kind::Any,
+            },
+            Expr::BinOp(op, e1, e2) => match (op, self.kind(e1, env), self.kind(e2, env)) {
+                (BinaryOp::Add | BinaryOp::Sub, Kind::Int, Kind::Int) => Kind::Int,
+                (BinaryOp::Add | BinaryOp::Sub, k1, k2) if k1.numeric() && k2.numeric() => {
+                    Kind::Float
+                }
+                (BinaryOp::Eq | BinaryOp::Lt, _, _) => Kind::Bool,
+                (BinaryOp::Pair, _, _) => Kind::Pair,
+                _ => Kind::Any,
+            },
+            Expr::If(_, then_branch, else_branch) => {
+                match (self.kind(then_branch, env), self.kind(else_branch, env)) {
+                    (k1, k2) if k1 == k2 => k1,
+                    _ => Kind::Any,
+                }
+            }
+            Expr::Let(bindings, body) => {
+                let mut env = env.clone();
+                for (name, e) in bindings {
+                    let kind = self.kind(e, &env);
+                    env.insert(name.clone(), kind);
+                }
+                self.kind(body, &env)
+            }
+            Expr::Do(exprs) => match exprs.last() {
+                Some(e) => self.kind(e, env),
+                None => Kind::Any,
+            },
+            _ => Kind::Any,
+        }
+    }
+
+    /// Whether `expr` surely evaluates to a value, with no error and no effect
+    fn pure(&self, expr: &Expr, env: &HashMap<String, Kind>) -> bool {
+        // Integer arithmetic only fails when overflows are errors; floats never overflow
+        let arithmetic = |kinds: &[Kind]| {
+            kinds.iter().all(|k| k.numeric())
+                && (!self.overflow_checks || kinds.contains(&Kind::Float))
+        };
+        match expr {
+            Expr::Num(x) => (NUM_MIN..=NUM_MAX).contains(x),
+            Expr::Float(_) | Expr::Bool(_) | Expr::Unit => true,
+            Expr::Id(s) => env.contains_key(s),
+            Expr::UnOp(op, e) => {
+                let kind = self.kind(e, env);
+                self.pure(e, env)
+                    && match op {
+                        UnaryOp::IsNum | UnaryOp::Not => true,
+                        UnaryOp::IsZero | UnaryOp::ToFloat => kind.numeric(),
+                        UnaryOp::Add1 | UnaryOp::Sub1 => arithmetic(&[kind]),
+                        // Infinities and NaN have no whole value
+                        UnaryOp::Floor | UnaryOp::Round => kind == Kind::Int,
+                        _ => false,
+                    }
+            }
+            Expr::BinOp(op, e1, e2) => {
+                let kinds = [self.kind(e1, env), self.kind(e2, env)];
+                self.pure(e1, env)
+                    && self.pure(e2, env)
+                    && match op {
+                        BinaryOp::Eq | BinaryOp::Pair => true,
+                        BinaryOp::Lt => kinds.iter().all(|k| k.numeric()),
+                        BinaryOp::Add | BinaryOp::Sub => arithmetic(&kinds),
+                        _ => false,
+                    }
+            }
+            Expr::If(cond, then_branch, else_branch) => {
+                self.pure(cond, env) && self.pure(then_branch, env) && self.pure(else_branch, env)
+            }
+            Expr::Let(bindings, body) => {
+                let mut env = env.clone();
+                for (name, e) in bindings {
+                    if !self.pure(e, &env) {
+                        return false;
+                    }
+                    let kind = self.kind(e, &env);
+                    env.insert(name.clone(), kind);
+                }
+                self.pure(body, &env)
+            }
+            Expr::Do(exprs) => !exprs.is_empty() && exprs.iter().all(|e| self.pure(e, env)),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `name`, bound just before `bindings`, is read by them or by `body`
+fn used(name: &str, bindings: &[(String, Box<Expr>)], body: &Expr) -> bool {
+    for (var, e) in bindings {
+        if occurs_free(name, e) {
+            return true;
+        }
+        if var == name {
+            return false;
+        }
+    }
+    occurs_free(name, body)
+}
+
+fn occurs_free(name: &str, expr: &Expr) -> bool {
+    match expr {
+        Expr::Id(s) => s == name,
+        Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Unit => false,
+        Expr::UnOp(_, e) => occurs_free(name, e),
+        Expr::BinOp(_, e1, e2) => occurs_free(name, e1) || occurs_free(name, e2),
+        Expr::TriOp(_, e1, e2, e3) => [e1, e2, e3].iter().any(|e| occurs_free(name, e)),
+        Expr::If(cond, then_branch, else_branch) => [cond, then_branch, else_branch]
+            .iter()
+            .any(|e| occurs_free(name, e)),
+        Expr::Let(bindings, body) => used(name, bindings, body),
+        Expr::Do(exprs) => exprs.iter().any(|e| occurs_free(name, e)),
+        Expr::FuncDef(_, params, body) => {
+            !params.iter().any(|p| p == name) && occurs_free(name, body)
+        }
+        Expr::FuncCall(callee, args) => {
+            occurs_free(name, callee) || args.iter().any(|a| occurs_free(name, a))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eliminated_with(source: &str, options: &Options) -> String {
+        eliminate(crate::parse(source).unwrap(), options).to_string()
+    }
+
+    fn eliminated(source: &str) -> String {
+        eliminated_with(source, &Options::default())
+    }
+
+    #[test]
+    fn removes_discarded_pure_expressions() {
+        assert_eq!(eliminated("let x = 5 in { x + 1; x }"), "let x = 5 in x");
+        assert_eq!(eliminated("{ 1 < 2; isNum(true); 3 }"), "3");
+    }
+
+    #[test]
+    fn removes_unused_pure_bindings() {
+        assert_eq!(eliminated("let x = 5, y = x + 1 in x"), "let x = 5 in x");
+        // Dropping `z` leaves `y` unused in turn
+        assert_eq!(eliminated("let y = 1, z = y + 1 in 7"), "7");
+    }
+
+    #[test]
+    fn keeps_what_may_fail() {
+        for source in [
+            "{ left(5); 1 }",
+            "{ add1(true); 1 }",
+            "let x = add1(true) in 1",
+            "{ unbound; 1 }",
+            "{ 1 + pair(1, 2); 1 }",
+        ] {
+            assert_eq!(
+                eliminated(source),
+                crate::parse(source).unwrap().to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_arithmetic_that_may_overflow() {
+        let checked = Options {
+            overflow_checks: true,
+            ..Options::default()
+        };
+        let source = "let x = 5 in { x + 1; x }";
+        assert_eq!(
+            eliminated_with(source, &checked),
+            crate::parse(source).unwrap().to_string()
+        );
+        // Float arithmetic never overflows
+        assert_eq!(
+            eliminated_with("let x = 0.5 in { x + 1; x }", &checked),
+            "let x = 0.5 in x"
+        );
+    }
+
+    #[test]
+    fn takes_the_branch_of_a_literal_condition() {
+        assert_eq!(eliminated("if (true) 1 else add1(true)"), "1");
+        assert_eq!(eliminated("if (false) add1(true) else 2"), "2");
+        assert_eq!(eliminated("if (1 < 2) 1 else 2"), "if (1 < 2) 1 else 2");
+    }
+}
@@ -0,0 +1,605 @@
+use std::fmt;
+use std::ops::Range;
+
+use lalrpop_util::ParseError;
+
+use crate::compile;
+use crate::json::Json;
+use crate::lexer::{Lexer, LexicalError, Tok};
+use crate::resolve::{self, Kind, Resolution};
+
+pub const INVALID_TOKEN: &str = "E0001";
+pub const UNEXPECTED_EOF: &str = "E0002";
+pub const UNEXPECTED_TOKEN: &str = "E0003";
+pub const TRAILING_INPUT: &str = "E0004";
+pub const INVALID_LITERAL: &str = "E0005";
+pub const UNTERMINATED_COMMENT: &str = "E0006";
+pub const UNBOUND: &str = "E0101";
+pub const UNSUPPORTED: &str = "E0102";
+pub const UNUSED: &str = "W0001";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A span of the source with a message; the primary label says where the problem is,
+/// secondary labels point at related places
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub span: Range<usize>,
+    pub message: String,
+    pub primary: bool,
+}
+
+/// A problem found in the source, located by byte offsets into it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+/// Everything wrong with one source text
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "{}[{}]: {}", self.severity, code, self.message),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(|d| d.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// 0-based line and character column of a byte offset
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count())
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+
+impl Severity {
+    fn color(self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;32m",
+        }
+    }
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: Option<&'static str>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity,
+            code,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Error, Some(code), message)
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Warning, Some(code), message)
+    }
+
+    pub fn with_label(mut self, span: Range<usize>, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Range<usize>, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn primary_span(&self) -> Option<Range<usize>> {
+        self.labels
+            .iter()
+            .find(|l| l.primary)
+            .map(|l| l.span.clone())
+    }
+
+    /// Renders the diagnostic with the labelled lines of `source` and carets under each span
+    pub fn render(&self, file: &str, source: &str, color: bool) -> String {
+        let paint = |style: &str, text: &str| {
+            if color {
+                format!("{}{}{}", style, text, RESET)
+            } else {
+                text.to_string()
+            }
+        };
+        let severity = match self.code {
+            Some(code) => format!("{}[{}]", self.severity, code),
+            None => self.severity.to_string(),
+        };
+        let mut out = format!(
+            "{}{}\n",
+            paint(self.severity.color(), &severity),
+            paint(BOLD, &format!(": {}", self.message))
+        );
+
+        let lines: Vec<&str> = source.split('\n').collect();
+        let mut labels: Vec<(usize, usize, usize, &Label)> = self
+            .labels
+            .iter()
+            .map(|label| {
+                let (line, start) = line_column(source, label.span.start);
+                let (end_line, end) = line_column(source, label.span.end);
+                let end = if end_line == line {
+                    end
+                } else {
+                    lines[line].chars().count()
+                };
+                (line, start, end.max(start + 1), label)
+            })
+            .collect();
+        labels.sort_by_key(|(line, start, _, label)| (*line, !label.primary, *start));
+        let width = labels
+            .iter()
+            .map(|(line, ..)| (line + 1).to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = |number: &str| paint(BLUE, &format!("{:>width$} |", number, width = width));
+
+        if let Some((line, start, _, _)) = labels.iter().find(|(.., label)| label.primary) {
+            out.push_str(&format!(
+                "{}{} {}:{}:{}\n",
+                " ".repeat(width),
+                paint(BLUE, "-->"),
+                file,
+                line + 1,
+                start + 1
+            ));
+        }
+        if !labels.is_empty() {
+            out.push_str(&format!("{}\n", gutter("")));
+        }
+        let mut previous_line = None;
+        for (line, start, end, label) in &labels {
+            let text = lines[*line];
+            if previous_line != Some(*line) {
+                out.push_str(&format!("{} {}\n", gutter(&(line + 1).to_string()), text));
+                previous_line = Some(*line);
+            }
+            // Keep tabs so the carets line up under tab-indented source
+            let indent: String = text
+                .chars()
+                .chain(std::iter::repeat(' '))
+                .take(*start)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let (mark, style) = if label.primary {
+                ('^', self.severity.color())
+            } else {
+                ('-', BLUE)
+            };
+            let marks: String = mark.to_string().repeat(end - start);
+            let message = if label.message.is_empty() {
+                marks
+            } else {
+                format!("{} {}", marks, label.message)
+            };
+            out.push_str(&format!(
+                "{} {}{}\n",
+                gutter(""),
+                indent,
+                paint(style, &message)
+            ));
+        }
+        if !labels.is_empty() && !self.notes.is_empty() {
+            out.push_str(&format!("{}\n", gutter("")));
+        }
+        for note in &self.notes {
+            out.push_str(&format!(
+                "{} {} note: {}\n",
+                " ".repeat(width),
+                paint(BLUE, "="),
+                note
+            ));
+        }
+        out
+    }
+
+    pub fn to_json(&self, file: &str, source: &str) -> Json {
+        let labels = self
+            .labels
+            .iter()
+            .map(|label| {
+                let (line, column) = line_column(source, label.span.start);
+                let (end_line, end_column) = line_column(source, label.span.end);
+                Json::object(vec![
+                    ("primary", label.primary.into()),
+                    ("message", label.message.as_str().into()),
+                    ("start", label.span.start.into()),
+                    ("end", label.span.end.into()),
+                    ("line", (line + 1).into()),
+                    ("column", (column + 1).into()),
+                    ("end_line", (end_line + 1).into()),
+                    ("end_column", (end_column + 1).into()),
+                ])
+            })
+            .collect();
+        Json::object(vec![
+            ("severity", self.severity.to_string().into()),
+            ("code", self.code.map_or(Json::Null, Json::from)),
+            ("message", self.message.as_str().into()),
+            ("file", file.into()),
+            ("labels", Json::Array(labels)),
+            (
+                "notes",
+                Json::Array(self.notes.iter().map(|n| n.as_str().into()).collect()),
+            ),
+            ("rendered", self.render(file, source, false).into()),
+        ])
+    }
+}
+
+impl Diagnostics {
+    pub fn has_errors(&self) -> bool {
+        self.0.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn render(&self, file: &str, source: &str, color: bool) -> String {
+        let rendered: Vec<String> = self
+            .0
+            .iter()
+            .map(|d| d.render(file, source, color))
+            .collect();
+        rendered.join("\n")
+    }
+}
+
+/// The grammar names terminals by their spelling; say what the literal ones mean instead
+fn describe_expected(expected: &[String]) -> Option<String> {
+    let names: Vec<String> = expected
+        .iter()
+        .map(|e| match e.as_str() {
+            r#""number""# => "a number".to_string(),
+            r#""float""# => "a float".to_string(),
+            r#""identifier""# => "an identifier".to_string(),
+            other => format!("`{}`", other.trim_matches('"')),
+        })
+        .collect();
+    match names.as_slice() {
+        [] => None,
+        [one] => Some(format!("expected {}", one)),
+        [rest @ .., last] => Some(format!("expected one of {} or {}", rest.join(", "), last)),
+    }
+}
+
+/// The innermost bracket still open at the end of `source`
+fn unclosed_bracket(source: &str) -> Option<(usize, Tok<'_>)> {
+    let mut open = Vec::new();
+    for (start, tok, _) in Lexer::new(source).map_while(Result::ok) {
+        match tok {
+            Tok::LParen | Tok::LBrace => open.push((start, tok)),
+            Tok::RParen | Tok::RBrace => {
+                open.pop();
+            }
+            _ => (),
+        }
+    }
+    open.pop()
+}
+
+pub fn parse_error(source: &str, error: ParseError<usize, Tok<'_>, LexicalError>) -> Diagnostic {
+    let with_expected =
+        |diagnostic: Diagnostic, expected: &[String]| match describe_expected(expected) {
+            Some(note) => diagnostic.with_note(note),
+            None => diagnostic,
+        };
+    match error {
+        ParseError::InvalidToken { location } => {
+            let c = source[location..].chars().next().unwrap_or(' ');
+            Diagnostic::error(INVALID_TOKEN, format!("unexpected character `{}`", c))
+                .with_label(location..location + c.len_utf8(), "not part of any token")
+        }
+        ParseError::User {
+            error: error @ LexicalError::InvalidCharacter { .. },
+        } => Diagnostic::error(INVALID_TOKEN, error.to_string())
+            .with_label(error.span(), "not part of any token"),
+        ParseError::User {
+            error: error @ LexicalError::UnterminatedComment { .. },
+        } => Diagnostic::error(UNTERMINATED_COMMENT, error.to_string())
+            .with_label(error.span(), "the comment starts here")
+            .with_note("block comments nest, so every `/*` needs its own `*/`"),
+        ParseError::User {
+            error: error @ LexicalError::MalformedNumber { .. },
+        } => Diagnostic::error(INVALID_LITERAL, error.to_string())
+            .with_label(error.span(), "not a valid number")
+            .with_note(
+                "numbers are decimal, like `12` or `1.5e3`, or hexadecimal after `0x` or binary \
+                 after `0b`",
+            ),
+        ParseError::User {
+            error: error @ LexicalError::NumberOutOfRange { .. },
+        } => Diagnostic::error(INVALID_LITERAL, error.to_string())
+            .with_label(error.span(), "does not fit in a tagged number")
+            .with_note(format!(
+                "numbers range from {} to {}",
+                compile::NUM_MIN,
+                compile::NUM_MAX
+            )),
+        ParseError::User {
+            error: error @ LexicalError::FloatOutOfRange { .. },
+        } => Diagnostic::error(INVALID_LITERAL, error.to_string())
+            .with_label(error.span(), "too large for a double")
+            .with_note(format!("floats range up to {:e} in magnitude", f64::MAX)),
+        ParseError::UnrecognizedEof { location, expected } => {
+            let diagnostic = Diagnostic::error(UNEXPECTED_EOF, "unexpected end of input")
+                .with_label(location..location, "the program ends here");
+            let diagnostic = match unclosed_bracket(source) {
+                Some((at, c)) => {
+                    diagnostic.with_secondary(at..at + 1, format!("`{}` is never closed", c))
+                }
+                None => diagnostic,
+            };
+            with_expected(diagnostic, &expected)
+        }
+        ParseError::UnrecognizedToken {
+            token: (start, token, end),
+            expected,
+        } => with_expected(
+            Diagnostic::error(UNEXPECTED_TOKEN, format!("unexpected `{}`", token))
+                .with_label(start..end, "unexpected token"),
+            &expected,
+        ),
+        ParseError::ExtraToken {
+            token: (start, token, end),
+        } => Diagnostic::error(
+            TRAILING_INPUT,
+            format!("unexpected `{}` after the end of the program", token),
+        )
+        .with_label(start..end, "the program should end before this"),
+    }
+}
+
+/// Unbound names are errors, names that are never used are warnings
+pub fn name_diagnostics(resolution: &Resolution) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for reference in resolution.unbound() {
+        let diagnostic =
+            Diagnostic::error(UNBOUND, format!("unbound identifier `{}`", reference.name))
+                .with_label(reference.span.clone(), "not found in this scope");
+        // A function body cannot see the variables around its definition
+        let diagnostic = match resolution
+            .definitions
+            .iter()
+            .find(|d| d.name == reference.name)
+        {
+            Some(d) => diagnostic.with_secondary(
+                d.span.clone(),
+                format!("`{}` is defined here, but is not in scope", d.name),
+            ),
+            None => diagnostic,
+        };
+        diagnostics.push(diagnostic);
+    }
+    for definition in resolution.unused() {
+        let kind = match definition.kind {
+            Kind::Parameter => "parameter",
+            _ => "variable",
+        };
+        diagnostics.push(
+            Diagnostic::warning(UNUSED, format!("unused {} `{}`", kind, definition.name))
+                .with_label(definition.span.clone(), "never used"),
+        );
+    }
+    diagnostics
+}
+
+/// Where the construct the compiler rejected first appears. The tree has no positions, so this
+/// searches the tokens for the one that introduces it.
+fn locate_unsupported(source: &str, what: &str) -> Option<Range<usize>> {
+    let tokens: Vec<(usize, Tok, usize)> = Lexer::new(source).map_while(Result::ok).collect();
+    let op = what.trim_matches('`');
+    tokens
+        .iter()
+        .enumerate()
+        .find(|(i, (_, tok, _))| match (what, tok) {
+            ("function definition", Tok::Def) => true,
+            ("function call", Tok::Id(_)) => {
+                matches!(tokens.get(i + 1), Some((_, Tok::LParen, _)))
+            }
+            _ => tok.to_string() == op,
+        })
+        .map(|(_, (start, _, end))| *start..*end)
+}
+
+pub fn compile_error(source: &str, error: &compile::Error) -> Diagnostic {
+    match error {
+        compile::Error::Unbound(name) => {
+            let diagnostic = Diagnostic::error(UNBOUND, error.to_string());
+            match resolve::identifiers(source)
+                .into_iter()
+                .find(|(_, w)| w == name)
+            {
+                Some((span, _)) => diagnostic.with_label(span, "not found in this scope"),
+                None => diagnostic,
+            }
+        }
+        compile::Error::NumberOutOfRange(_) => {
+            Diagnostic::error(INVALID_LITERAL, error.to_string())
+        }
+        compile::Error::Unsupported(what) => {
+            let diagnostic = Diagnostic::error(UNSUPPORTED, error.to_string())
+                .with_note("`asm run --interp` can evaluate it with the interpreter");
+            match locate_unsupported(source, what) {
+                Some(span) => diagnostic.with_label(span, "cannot be compiled yet"),
+                None => diagnostic,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_at_end_of_file() {
+        let source = "pair(1,\n  add1(2)";
+        let diagnostics = crate::parse(source).unwrap_err();
+        assert_eq!(
+            diagnostics.render("main.lisp", source, false),
+            "\
+error[E0002]: unexpected end of input
+ --> main.lisp:2:10
+  |
+1 | pair(1,
+  |     - `(` is never closed
+2 |   add1(2)
+  |          ^ the program ends here
+  |
+  = note: expected `)`
+"
+        );
+        assert_eq!(
+            diagnostics.0[0].to_json("main.lisp", source).to_string(),
+            concat!(
+                r#"{"severity":"error","code":"E0002","message":"unexpected end of input","#,
+                r#""file":"main.lisp","labels":["#,
+                r#"{"primary":true,"message":"the program ends here","start":17,"end":17,"#,
+                r#""line":2,"column":10,"end_line":2,"end_column":10},"#,
+                r#"{"primary":false,"message":"`(` is never closed","start":4,"end":5,"#,
+                r#""line":1,"column":5,"end_line":1,"end_column":6}],"#,
+                r#""notes":["expected `)`"],"#,
+                r#""rendered":"error[E0002]: unexpected end of input\n --> main.lisp:2:10\n  |\n"#,
+                r#"1 | pair(1,\n  |     - `(` is never closed\n2 |   add1(2)\n"#,
+                r#"  |          ^ the program ends here\n  |\n  = note: expected `)`\n"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn end_of_file_after_a_newline() {
+        let source = "let x = 1 in\n";
+        let rendered = crate::parse(source)
+            .unwrap_err()
+            .render("main.lisp", source, false);
+        assert!(rendered.starts_with(
+            "\
+error[E0002]: unexpected end of input
+ --> main.lisp:1:13
+  |
+1 | let x = 1 in
+  |             ^ the program ends here
+"
+        ));
+    }
+
+    #[test]
+    fn resolve_errors() {
+        let source = "{\n  let x = 1 in\n    add1(y)\n}\n";
+        let (_, diagnostics) = crate::analyze(source);
+        assert_eq!(
+            diagnostics.render("main.lisp", source, false),
+            "\
+error[E0101]: unbound identifier `y`
+ --> main.lisp:3:10
+  |
+3 |     add1(y)
+  |          ^ not found in this scope
+
+warning[W0001]: unused variable `x`
+ --> main.lisp:2:7
+  |
+2 |   let x = 1 in
+  |       ^ never used
+"
+        );
+        assert_eq!(
+            diagnostics.0[0].to_json("main.lisp", source).to_string(),
+            concat!(
+                r#"{"severity":"error","code":"E0101","message":"unbound identifier `y`","#,
+                r#""file":"main.lisp","labels":["#,
+                r#"{"primary":true,"message":"not found in this scope","start":26,"end":27,"#,
+                r#""line":3,"column":10,"end_line":3,"end_column":11}],"notes":[],"#,
+                r#""rendered":"error[E0101]: unbound identifier `y`\n --> main.lisp:3:10\n  |\n"#,
+                r#"3 |     add1(y)\n  |          ^ not found in this scope\n"}"#
+            )
+        );
+    }
+
+    /// A span over several lines is marked to the end of its first line
+    #[test]
+    fn multi_line_span() {
+        let source = "let x = pair(1,\n  2) in x";
+        let diagnostic = Diagnostic::error(UNSUPPORTED, "pairs are not supported")
+            .with_label(8..20, "this pair")
+            .with_note("try a vector");
+        assert_eq!(
+            diagnostic.render("main.lisp", source, false),
+            "\
+error[E0102]: pairs are not supported
+ --> main.lisp:1:9
+  |
+1 | let x = pair(1,
+  |         ^^^^^^^ this pair
+  |
+  = note: try a vector
+"
+        );
+        let json = diagnostic.to_json("main.lisp", source);
+        let label = &json.get("labels").unwrap().as_array().unwrap()[0];
+        let field = |name| label.get(name).unwrap().as_usize().unwrap();
+        assert_eq!(
+            (
+                field("line"),
+                field("column"),
+                field("end_line"),
+                field("end_column")
+            ),
+            (1, 9, 2, 5)
+        );
+    }
+}
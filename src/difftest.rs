@@ -0,0 +1,326 @@
+use crate::asm::Directive;
+use crate::ast::Expr;
+use crate::compile::{compile, Error};
+use crate::eval;
+use crate::sim::{self, Output};
+
+/// A program whose compiled and interpreted runs disagree
+#[derive(Debug)]
+pub struct Divergence {
+    pub program: Expr,
+    pub compiled: Result<Output, String>,
+    pub interpreted: Result<Output, String>,
+}
+
+fn describe(result: &Result<Output, String>) -> String {
+    match result {
+        Ok(output) => format!("{:?} (exit {})", output.stdout, output.exit_code),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+impl Divergence {
+    pub fn report(&self) -> String {
+        format!(
+            "program:     {}\ncompiled:    {}\ninterpreted: {}",
+            self.program,
+            describe(&self.compiled),
+            describe(&self.interpreted)
+        )
+    }
+}
+
+/// What running a program both ways showed
+#[derive(Debug)]
+pub enum Comparison {
+    Agree,
+    /// The compiler does not handle something in the program, so there is nothing to compare
+    Unsupported(String),
+    Diverge(Divergence),
+}
+
+/// The compiler as `compare` runs it, which tests swap for a broken one
+type Compiler = dyn Fn(Expr) -> Result<Vec<Directive>, Error>;
+
+/// Runs `expr` through the compiler and simulator and through the interpreter
+pub fn compare(expr: &Expr) -> Comparison {
+    compare_with(&compile, expr)
+}
+
+fn compare_with(compiler: &Compiler, expr: &Expr) -> Comparison {
+    let compiled = match compiler(expr.clone()) {
+        Err(Error::Unsupported(what)) => return Comparison::Unsupported(what),
+        result => result
+            .map_err(|e| e.to_string())
+            .and_then(|directives| sim::simulate(&directives)),
+    };
+    let interpreted = eval::run(expr);
+    if compiled == interpreted {
+        Comparison::Agree
+    } else {
+        Comparison::Diverge(Divergence {
+            program: expr.clone(),
+            compiled,
+            interpreted,
+        })
+    }
+}
+
+/// Whether every variable and function `expr` refers to is bound inside it
+fn is_closed(scope: &[String], expr: &Expr) -> bool {
+    let extended = |names: &[String]| [scope, names].concat();
+    match expr {
+        Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Unit => true,
+        Expr::Id(name) => scope.contains(name),
+        Expr::UnOp(_, e) => is_closed(scope, e),
+        Expr::BinOp(_, e1, e2) => is_closed(scope, e1) && is_closed(scope, e2),
+        Expr::TriOp(_, e1, e2, e3) | Expr::If(e1, e2, e3) => {
+            is_closed(scope, e1) && is_closed(scope, e2) && is_closed(scope, e3)
+        }
+        Expr::Let(bindings, body) => {
+            let mut scope = scope.to_vec();
+            for (var, e) in bindings {
+                if !is_closed(&scope, e) {
+                    return false;
+                }
+                scope.push(var.clone());
+            }
+            is_closed(&scope, body)
+        }
+        Expr::Do(exps) => {
+            let defined: Vec<String> = exps
+                .iter()
+                .filter_map(|e| match e.as_ref() {
+                    Expr::FuncDef(name, _, _) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect();
+            let scope = extended(&defined);
+            exps.iter().all(|e| is_closed(&scope, e))
+        }
+        Expr::FuncDef(name, params, body) => is_closed(
+            &extended(&[vec![name.clone()], params.clone()].concat()),
+            body,
+        ),
+        Expr::FuncCall(f, args) => is_closed(scope, f) && args.iter().all(|e| is_closed(scope, e)),
+    }
+}
+
+/// Candidate programs that are one step simpler than `expr`
+fn shrink(expr: &Expr) -> Vec<Expr> {
+    let b = |e: Expr| Box::new(e);
+    let mut out = Vec::new();
+    match expr {
+        Expr::Num(n) if *n != 0 => out.push(Expr::Num(0)),
+        Expr::Float(x) => out.push(Expr::Num(x.round() as i64)),
+        Expr::UnOp(op, e) => {
+            out.push(*e.clone());
+            out.extend(shrink(e).into_iter().map(|s| Expr::UnOp(op.clone(), b(s))));
+        }
+        Expr::BinOp(op, e1, e2) => {
+            out.push(*e1.clone());
+            out.push(*e2.clone());
+            out.extend(
+                shrink(e1)
+                    .into_iter()
+                    .map(|s| Expr::BinOp(op.clone(), b(s), e2.clone())),
+            );
+            out.extend(
+                shrink(e2)
+                    .into_iter()
+                    .map(|s| Expr::BinOp(op.clone(), e1.clone(), b(s))),
+            );
+        }
+        Expr::TriOp(op, e1, e2, e3) => {
+            out.extend([*e1.clone(), *e2.clone(), *e3.clone()]);
+            out.extend(
+                shrink(e1)
+                    .into_iter()
+                    .map(|s| Expr::TriOp(op.clone(), b(s), e2.clone(), e3.clone())),
+            );
+            out.extend(
+                shrink(e2)
+                    .into_iter()
+                    .map(|s| Expr::TriOp(op.clone(), e1.clone(), b(s), e3.clone())),
+            );
+            out.extend(
+                shrink(e3)
+                    .into_iter()
+                    .map(|s| Expr::TriOp(op.clone(), e1.clone(), e2.clone(), b(s))),
+            );
+        }
+        Expr::If(e1, e2, e3) => {
+            out.extend([*e1.clone(), *e2.clone(), *e3.clone()]);
+            out.extend(
+                shrink(e1)
+                    .into_iter()
+                    .map(|s| Expr::If(b(s), e2.clone(), e3.clone())),
+            );
+            out.extend(
+                shrink(e2)
+                    .into_iter()
+                    .map(|s| Expr::If(e1.clone(), b(s), e3.clone())),
+            );
+            out.extend(
+                shrink(e3)
+                    .into_iter()
+                    .map(|s| Expr::If(e1.clone(), e2.clone(), b(s))),
+            );
+        }
+        Expr::Let(bindings, body) => {
+            out.push(*body.clone());
+            for (i, (var, e)) in bindings.iter().enumerate() {
+                out.push(*e.clone());
+                if bindings.len() > 1 {
+                    let mut fewer = bindings.clone();
+                    fewer.remove(i);
+                    out.push(Expr::Let(fewer, body.clone()));
+                }
+                for s in shrink(e) {
+                    let mut smaller = bindings.clone();
+                    smaller[i] = (var.clone(), b(s));
+                    out.push(Expr::Let(smaller, body.clone()));
+                }
+            }
+            out.extend(
+                shrink(body)
+                    .into_iter()
+                    .map(|s| Expr::Let(bindings.clone(), b(s))),
+            );
+        }
+        Expr::Do(exps) => {
+            for (i, e) in exps.iter().enumerate() {
+                out.push(*e.clone());
+                let mut fewer = exps.clone();
+                fewer.remove(i);
+                out.push(Expr::Do(fewer));
+                for s in shrink(e) {
+                    let mut smaller = exps.clone();
+                    *smaller[i] = s;
+                    out.push(Expr::Do(smaller));
+                }
+            }
+        }
+        Expr::FuncDef(name, params, body) => {
+            out.extend(
+                shrink(body)
+                    .into_iter()
+                    .map(|s| Expr::FuncDef(name.clone(), params.clone(), b(s))),
+            );
+        }
+        Expr::FuncCall(f, args) => {
+            for (i, e) in args.iter().enumerate() {
+                out.push(*e.clone());
+                for s in shrink(e) {
+                    let mut smaller = args.clone();
+                    *smaller[i] = s;
+                    out.push(Expr::FuncCall(f.clone(), smaller));
+                }
+            }
+        }
+        _ => (),
+    }
+    out
+}
+
+/// Greedily shrinks a diverging program for as long as the smaller version still diverges.
+/// A closed program only shrinks to closed programs, so it cannot drift into an unbound variable.
+pub fn minimize(divergence: Divergence) -> Divergence {
+    minimize_with(&compile, divergence)
+}
+
+fn minimize_with(compiler: &Compiler, divergence: Divergence) -> Divergence {
+    let closed = is_closed(&[], &divergence.program);
+    let mut current = divergence;
+    'search: loop {
+        for candidate in shrink(&current.program) {
+            if closed && !is_closed(&[], &candidate) {
+                continue;
+            }
+            if let Comparison::Diverge(smaller) = compare_with(compiler, &candidate) {
+                current = smaller;
+                continue 'search;
+            }
+        }
+        return current;
+    }
+}
+
+/// What checking a list of programs found
+pub struct Summary {
+    /// How many programs ran the same both ways
+    pub agreed: usize,
+    /// The programs the compiler rejected as unsupported, with what it did not support
+    pub unsupported: Vec<(String, String)>,
+    /// The first program whose runs disagree, minimized
+    pub divergence: Option<(String, Divergence)>,
+}
+
+/// Checks each program in turn, up to the first divergence
+pub fn check(programs: &[(String, Expr)]) -> Summary {
+    let mut summary = Summary {
+        agreed: 0,
+        unsupported: Vec::new(),
+        divergence: None,
+    };
+    for (name, program) in programs {
+        match compare(program) {
+            Comparison::Agree => summary.agreed += 1,
+            Comparison::Unsupported(what) => summary.unsupported.push((name.clone(), what)),
+            Comparison::Diverge(divergence) => {
+                summary.divergence = Some((name.clone(), minimize(divergence)));
+                break;
+            }
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(source: &str) -> Expr {
+        crate::parse(source).unwrap()
+    }
+
+    #[test]
+    fn agreeing_program() {
+        let expr = program("let x = 5, y = add1(3) in if (x < y) pair(x, y) else { x + y; x - y }");
+        assert!(matches!(compare(&expr), Comparison::Agree));
+    }
+
+    #[test]
+    fn unsupported_program_is_skipped() {
+        let expr = program("{ def f(x) = x; f(1) }");
+        assert!(matches!(compare(&expr), Comparison::Unsupported(_)));
+        let summary = check(&[("f".to_string(), expr)]);
+        assert_eq!((summary.agreed, summary.unsupported.len()), (0, 1));
+        assert!(summary.divergence.is_none());
+    }
+
+    #[test]
+    fn miscompiled_program_is_minimized() {
+        // Gets `<` backwards
+        let miscompile = |expr: Expr| {
+            compile(expr).map(|directives| {
+                directives
+                    .into_iter()
+                    .map(|d| match d {
+                        Directive::Cset(r, c) if c == "lt" => Directive::Cset(r, "ge".to_string()),
+                        d => d,
+                    })
+                    .collect()
+            })
+        };
+        let expr = program("let x = 5, y = add1(3) in if (x < y) 1 else { x + y }");
+        let divergence = match compare_with(&miscompile, &expr) {
+            Comparison::Diverge(divergence) => divergence,
+            other => panic!("expected a divergence, found {:?}", other),
+        };
+        let minimized = minimize_with(&miscompile, divergence);
+        assert_eq!(minimized.program.to_string(), "let x = 0, y = 0 in x < y");
+        assert_eq!(minimized.compiled.unwrap().stdout, "true\n");
+        assert_eq!(minimized.interpreted.unwrap().stdout, "false\n");
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use crate::encode::{Object, Section};
+
+const EM_AARCH64: u16 = 183;
+const ET_REL: u16 = 1;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_SECTION: u8 = 3;
+
+const HEADER_SIZE: u64 = 64;
+const SECTION_HEADER_SIZE: u16 = 64;
+const SYMBOL_SIZE: u64 = 24;
+const RELA_SIZE: u64 = 24;
+
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entsize: u64,
+}
+
+struct StringTable {
+    bytes: Vec<u8>,
+}
+
+impl StringTable {
+    fn new() -> Self {
+        StringTable { bytes: vec![0] }
+    }
+
+    fn add(&mut self, s: &str) -> u32 {
+        let index = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(s.as_bytes());
+        self.bytes.push(0);
+        index
+    }
+}
+
+fn section_flags(section: &Section) -> u64 {
+    match section.name.as_str() {
+        ".text" => SHF_ALLOC | SHF_EXECINSTR,
+        ".rodata" => SHF_ALLOC,
+        _ => SHF_ALLOC | SHF_WRITE,
+    }
+}
+
+fn push_symbol(out: &mut Vec<u8>, name: u32, info: u8, shndx: u16, value: u64) {
+    out.extend_from_slice(&name.to_le_bytes());
+    out.push(info);
+    out.push(0);
+    out.extend_from_slice(&shndx.to_le_bytes());
+    out.extend_from_slice(&value.to_le_bytes());
+    out.extend_from_slice(&0u64.to_le_bytes());
+}
+
+fn pad_to(out: &mut Vec<u8>, align: u64) {
+    while !(out.len() as u64).is_multiple_of(align) {
+        out.push(0);
+    }
+}
+
+/// Writes an ELF64 little-endian relocatable object for AArch64
+pub fn write_elf(object: &Object) -> Vec<u8> {
+    let mut shstrtab = StringTable::new();
+    let mut strtab = StringTable::new();
+
+    // Section indices: null, the object's sections, their .rela sections, then the tables
+    let with_relocs: Vec<usize> = (0..object.sections.len())
+        .filter(|&i| !object.sections[i].relocs.is_empty())
+        .collect();
+    let symtab_index = 1 + object.sections.len() + with_relocs.len();
+    let strtab_index = symtab_index + 1;
+    let shstrtab_index = strtab_index + 1;
+
+    // Locals (section and mapping symbols, local labels) must precede globals
+    let mut symtab = Vec::new();
+    let mut symbol_index = HashMap::new();
+    push_symbol(&mut symtab, 0, 0, 0, 0);
+    for i in 0..object.sections.len() {
+        push_symbol(
+            &mut symtab,
+            0,
+            (STB_LOCAL << 4) | STT_SECTION,
+            (i + 1) as u16,
+            0,
+        );
+    }
+    let mut count = 1 + object.sections.len();
+    for (i, section) in object.sections.iter().enumerate() {
+        for (offset, code) in &section.mapping {
+            let name = strtab.add(if *code { "$x" } else { "$d" });
+            push_symbol(&mut symtab, name, STB_LOCAL << 4, (i + 1) as u16, *offset);
+            count += 1;
+        }
+    }
+    let (locals, globals): (Vec<_>, Vec<_>) = object.symbols.iter().partition(|s| !s.global);
+    let first_global = count + locals.len();
+    for symbol in locals.into_iter().chain(globals) {
+        let name = strtab.add(&symbol.name);
+        let bind = if symbol.global { STB_GLOBAL } else { STB_LOCAL };
+        let shndx = symbol.section.map_or(0, |s| (s + 1) as u16);
+        push_symbol(
+            &mut symtab,
+            name,
+            (bind << 4) | STT_NOTYPE,
+            shndx,
+            symbol.value,
+        );
+        symbol_index.insert(symbol.name.clone(), count as u64);
+        count += 1;
+    }
+
+    let mut out = vec![0; HEADER_SIZE as usize];
+    let mut headers = vec![SectionHeader {
+        name: 0,
+        kind: 0,
+        flags: 0,
+        offset: 0,
+        size: 0,
+        link: 0,
+        info: 0,
+        align: 0,
+        entsize: 0,
+    }];
+
+    for section in &object.sections {
+        pad_to(&mut out, section.align);
+        headers.push(SectionHeader {
+            name: shstrtab.add(&section.name),
+            kind: SHT_PROGBITS,
+            flags: section_flags(section),
+            offset: out.len() as u64,
+            size: section.bytes.len() as u64,
+            link: 0,
+            info: 0,
+            align: section.align,
+            entsize: 0,
+        });
+        out.extend_from_slice(&section.bytes);
+    }
+
+    for &i in &with_relocs {
+        let section = &object.sections[i];
+        pad_to(&mut out, 8);
+        let offset = out.len() as u64;
+        for reloc in &section.relocs {
+            let symbol = symbol_index[&reloc.symbol];
+            out.extend_from_slice(&reloc.offset.to_le_bytes());
+            out.extend_from_slice(&((symbol << 32) | reloc.kind as u64).to_le_bytes());
+            out.extend_from_slice(&reloc.addend.to_le_bytes());
+        }
+        headers.push(SectionHeader {
+            name: shstrtab.add(&format!(".rela{}", section.name)),
+            kind: SHT_RELA,
+            flags: SHF_INFO_LINK,
+            offset,
+            size: section.relocs.len() as u64 * RELA_SIZE,
+            link: symtab_index as u32,
+            info: (i + 1) as u32,
+            align: 8,
+            entsize: RELA_SIZE,
+        });
+    }
+
+    pad_to(&mut out, 8);
+    headers.push(SectionHeader {
+        name: shstrtab.add(".symtab"),
+        kind: SHT_SYMTAB,
+        flags: 0,
+        offset: out.len() as u64,
+        size: symtab.len() as u64,
+        link: strtab_index as u32,
+        info: first_global as u32,
+        align: 8,
+        entsize: SYMBOL_SIZE,
+    });
+    out.extend_from_slice(&symtab);
+
+    headers.push(SectionHeader {
+        name: shstrtab.add(".strtab"),
+        kind: SHT_STRTAB,
+        flags: 0,
+        offset: out.len() as u64,
+        size: strtab.bytes.len() as u64,
+        link: 0,
+        info: 0,
+        align: 1,
+        entsize: 0,
+    });
+    out.extend_from_slice(&strtab.bytes);
+
+    let name = shstrtab.add(".shstrtab");
+    headers.push(SectionHeader {
+        name,
+        kind: SHT_STRTAB,
+        flags: 0,
+        offset: out.len() as u64,
+        size: shstrtab.bytes.len() as u64,
+        link: 0,
+        info: 0,
+        align: 1,
+        entsize: 0,
+    });
+    out.extend_from_slice(&shstrtab.bytes);
+
+    pad_to(&mut out, 8);
+    let section_headers_offset = out.len() as u64;
+    for h in &headers {
+        out.extend_from_slice(&h.name.to_le_bytes());
+        out.extend_from_slice(&h.kind.to_le_bytes());
+        out.extend_from_slice(&h.flags.to_le_bytes());
+        out.extend_from_slice(&0u64.to_le_bytes());
+        out.extend_from_slice(&h.offset.to_le_bytes());
+        out.extend_from_slice(&h.size.to_le_bytes());
+        out.extend_from_slice(&h.link.to_le_bytes());
+        out.extend_from_slice(&h.info.to_le_bytes());
+        out.extend_from_slice(&h.align.to_le_bytes());
+        out.extend_from_slice(&h.entsize.to_le_bytes());
+    }
+
+    let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+    header.extend_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0]);
+    header.extend_from_slice(&[0; 8]);
+    header.extend_from_slice(&ET_REL.to_le_bytes());
+    header.extend_from_slice(&EM_AARCH64.to_le_bytes());
+    header.extend_from_slice(&1u32.to_le_bytes());
+    header.extend_from_slice(&0u64.to_le_bytes()); // e_entry
+    header.extend_from_slice(&0u64.to_le_bytes()); // e_phoff
+    header.extend_from_slice(&section_headers_offset.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    header.extend_from_slice(&(HEADER_SIZE as u16).to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes()); // e_phentsize
+    header.extend_from_slice(&0u16.to_le_bytes()); // e_phnum
+    header.extend_from_slice(&SECTION_HEADER_SIZE.to_le_bytes());
+    header.extend_from_slice(&(headers.len() as u16).to_le_bytes());
+    header.extend_from_slice(&(shstrtab_index as u16).to_le_bytes());
+    out[..HEADER_SIZE as usize].copy_from_slice(&header);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::Directive::*;
+    use crate::encode::{encode, R_AARCH64_CALL26};
+
+    fn u16_at(b: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(b[at..at + 2].try_into().unwrap())
+    }
+
+    fn u32_at(b: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(b[at..at + 4].try_into().unwrap())
+    }
+
+    fn u64_at(b: &[u8], at: usize) -> u64 {
+        u64::from_le_bytes(b[at..at + 8].try_into().unwrap())
+    }
+
+    fn c_str(b: &[u8], at: usize) -> &str {
+        let end = at + b[at..].iter().position(|&c| c == 0).unwrap();
+        std::str::from_utf8(&b[at..end]).unwrap()
+    }
+
+    /// The (type, offset, size, link) of each section header
+    fn sections(elf: &[u8]) -> Vec<(u32, usize, usize, u32)> {
+        let shoff = u64_at(elf, 40) as usize;
+        (0..u16_at(elf, 60) as usize)
+            .map(|i| {
+                let h = shoff + i * SECTION_HEADER_SIZE as usize;
+                (
+                    u32_at(elf, h + 4),
+                    u64_at(elf, h + 24) as usize,
+                    u64_at(elf, h + 32) as usize,
+                    u32_at(elf, h + 40),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn symbols_and_relocations() {
+        let object = encode(&[
+            Global("lisp_entry".to_string()),
+            Extern("lisp_error".to_string()),
+            Label("lisp_entry".to_string()),
+            Bl("lisp_error".to_string()),
+            Ret,
+        ])
+        .unwrap();
+        let elf = write_elf(&object);
+        assert_eq!(elf[..4], [0x7f, b'E', b'L', b'F']);
+        assert_eq!(u16_at(&elf, 16), ET_REL);
+        assert_eq!(u16_at(&elf, 18), EM_AARCH64);
+
+        let sections = sections(&elf);
+        let (_, symtab, symtab_size, strtab) = *sections
+            .iter()
+            .find(|(kind, ..)| *kind == SHT_SYMTAB)
+            .unwrap();
+        let strtab = sections[strtab as usize].1;
+        // (name, binding, section index) of each symbol
+        let symbols: Vec<(&str, u8, u16)> = (0..symtab_size / SYMBOL_SIZE as usize)
+            .map(|i| {
+                let s = symtab + i * SYMBOL_SIZE as usize;
+                let name = c_str(&elf, strtab + u32_at(&elf, s) as usize);
+                (name, elf[s + 4] >> 4, u16_at(&elf, s + 6))
+            })
+            .collect();
+        assert!(symbols.contains(&("lisp_entry", STB_GLOBAL, 1)));
+        assert!(symbols.contains(&("lisp_error", STB_GLOBAL, 0)));
+
+        let (_, rela, rela_size, _) = *sections
+            .iter()
+            .find(|(kind, ..)| *kind == SHT_RELA)
+            .unwrap();
+        assert_eq!(rela_size, RELA_SIZE as usize);
+        let info = u64_at(&elf, rela + 8);
+        assert_eq!(u64_at(&elf, rela), 0);
+        assert_eq!(info as u32, R_AARCH64_CALL26);
+        assert_eq!(symbols[(info >> 32) as usize].0, "lisp_error");
+    }
+}
@@ -0,0 +1,846 @@
+use std::collections::HashMap;
+
+use crate::asm::{Directive, Operand, Register};
+
+/// Relocation types from the AArch64 ELF ABI that the encoder can produce
+pub const R_AARCH64_ABS64: u32 = 257;
+pub const R_AARCH64_ADR_PREL_LO21: u32 = 274;
+pub const R_AARCH64_CONDBR19: u32 = 280;
+pub const R_AARCH64_JUMP26: u32 = 282;
+pub const R_AARCH64_CALL26: u32 = 283;
+
+/// Scratch register used when an operand does not fit in an instruction (IP0)
+const SCRATCH: u32 = 16;
+const ZR: u32 = 31;
+
+#[derive(Clone, Debug)]
+pub struct Reloc {
+    pub offset: u64,
+    pub kind: u32,
+    pub symbol: String,
+    pub addend: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Section {
+    pub name: String,
+    pub bytes: Vec<u8>,
+    pub relocs: Vec<Reloc>,
+    /// Mapping symbols (`$x` for code, `$d` for data) as (offset, is_code)
+    pub mapping: Vec<(u64, bool)>,
+    pub align: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Symbol {
+    pub name: String,
+    /// Index into `Object::sections`, or `None` for undefined symbols
+    pub section: Option<usize>,
+    pub value: u64,
+    pub global: bool,
+}
+
+/// A relocatable object produced from a directive list, ready to be written by `elf::write_elf`
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub sections: Vec<Section>,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Fixup {
+    Branch26 { call: bool },
+    CondBranch19,
+    Adr21,
+    Abs64,
+}
+
+struct PendingFixup {
+    section: usize,
+    offset: u64,
+    kind: Fixup,
+    label: String,
+}
+
+struct Encoder {
+    sections: Vec<Section>,
+    current: usize,
+    labels: HashMap<String, (usize, u64)>,
+    globals: Vec<String>,
+    externs: Vec<String>,
+    fixups: Vec<PendingFixup>,
+}
+
+fn register_number(r: &Register) -> Result<u32, String> {
+    Ok(match r {
+        Register::X0 => 0,
+        Register::X1 => 1,
+        Register::X2 => 2,
+        Register::X3 => 3,
+        Register::X4 => 4,
+        Register::X5 => 5,
+        Register::X6 => 6,
+        Register::X7 => 7,
+        Register::X8 => 8,
+        Register::X9 => 9,
+        Register::X10 => 10,
+        Register::X11 => 11,
+        Register::X12 => 12,
+        Register::X13 => 13,
+        Register::X14 => 14,
+        Register::X15 => 15,
+        Register::X16 => 16,
+        Register::X19 => 19,
+        Register::X20 => 20,
+        Register::X21 => 21,
+        Register::X22 => 22,
+        Register::X23 => 23,
+        Register::X24 => 24,
+        Register::X25 => 25,
+        Register::X26 => 26,
+        Register::X27 => 27,
+        Register::X28 => 28,
+        Register::Fp => 29,
+        Register::Lr => 30,
+        Register::Sp => 31,
+        Register::Virtual(_) => return Err(format!("unallocated register {}", r)),
+    })
+}
+
+fn is_sp(o: &Operand) -> bool {
+    matches!(o, Operand::Reg(Register::Sp))
+}
+
+/// Register operand that must be a general purpose register (number 31 would mean XZR)
+fn gpr(o: &Operand) -> Result<u32, String> {
+    match o {
+        Operand::Reg(Register::Sp) => Err("sp is not allowed here".to_string()),
+        Operand::Reg(r) => register_number(r),
+        _ => Err(format!("expected a register, found {}", o)),
+    }
+}
+
+/// Register operand that may be sp (number 31 means SP)
+fn gpr_or_sp(o: &Operand) -> Result<u32, String> {
+    match o {
+        Operand::Reg(r) => register_number(r),
+        _ => Err(format!("expected a register, found {}", o)),
+    }
+}
+
+fn condition_code(cond: &str) -> Result<u32, String> {
+    match cond {
+        "eq" => Ok(0),
+        "ne" => Ok(1),
+        "cs" | "hs" => Ok(2),
+        "cc" | "lo" => Ok(3),
+        "mi" => Ok(4),
+        "pl" => Ok(5),
+        "vs" => Ok(6),
+        "vc" => Ok(7),
+        "hi" => Ok(8),
+        "ls" => Ok(9),
+        "ge" => Ok(10),
+        "lt" => Ok(11),
+        "gt" => Ok(12),
+        "le" => Ok(13),
+        "al" => Ok(14),
+        _ => Err(format!("unknown condition code: {}", cond)),
+    }
+}
+
+/// Encodes a 64-bit logical immediate as N:immr:imms, if it is representable
+pub fn encode_bitmask(imm: u64) -> Option<u32> {
+    if imm == 0 || imm == u64::MAX {
+        return None;
+    }
+    let mut size = 64;
+    while size > 2 {
+        let half = size / 2;
+        let mask = (1u64 << half) - 1;
+        if imm & mask != (imm >> half) & mask {
+            break;
+        }
+        size = half;
+    }
+    let mask = if size == 64 {
+        u64::MAX
+    } else {
+        (1u64 << size) - 1
+    };
+    let elem = imm & mask;
+    let ones = elem.count_ones();
+    let pattern = (1u64 << ones) - 1;
+    let ror = |v: u64, r: u32| {
+        if r == 0 {
+            v
+        } else {
+            ((v >> r) | (v << (size - r))) & mask
+        }
+    };
+    let immr = (0..size).find(|&r| ror(pattern, r) == elem)?;
+    let n = if size == 64 { 1 } else { 0 };
+    let imms = (!(size * 2 - 1) & 0x3f) | (ones - 1);
+    Some((n << 12) | (immr << 6) | imms)
+}
+
+/// Picks the same single instruction `as` would for `mov Xd, #imm`, falling back to movz/movk
+fn mov_imm(rd: u32, value: i64) -> Vec<u32> {
+    let u = value as u64;
+    for hw in 0..4 {
+        if u & !(0xffff << (16 * hw)) == 0 {
+            return vec![
+                0xD280_0000 | (hw << 21) | ((((u >> (16 * hw)) & 0xffff) as u32) << 5) | rd,
+            ];
+        }
+    }
+    let n = !u;
+    for hw in 0..4 {
+        if n & !(0xffff << (16 * hw)) == 0 {
+            return vec![
+                0x9280_0000 | (hw << 21) | ((((n >> (16 * hw)) & 0xffff) as u32) << 5) | rd,
+            ];
+        }
+    }
+    if let Some(bits) = encode_bitmask(u) {
+        return vec![0xB200_0000 | (bits << 10) | (ZR << 5) | rd];
+    }
+    let mut words = Vec::new();
+    for hw in 0..4 {
+        let chunk = ((u >> (16 * hw)) & 0xffff) as u32;
+        if chunk == 0 {
+            continue;
+        }
+        let opcode = if words.is_empty() {
+            0xD280_0000
+        } else {
+            0xF280_0000
+        };
+        words.push(opcode | (hw << 21) | (chunk << 5) | rd);
+    }
+    words
+}
+
+/// Encodes an add/sub immediate, or `None` when it does not fit in a (shifted) imm12
+fn add_sub_imm(base: u32, rd: u32, rn: u32, imm: i64) -> Option<u32> {
+    if (0..4096).contains(&imm) {
+        Some(base | ((imm as u32) << 10) | (rn << 5) | rd)
+    } else if imm & 0xfff == 0 && (0..4096).contains(&(imm >> 12)) {
+        Some(base | (1 << 22) | (((imm >> 12) as u32) << 10) | (rn << 5) | rd)
+    } else {
+        None
+    }
+}
+
+const ADD_IMM: u32 = 0x9100_0000;
+const ADDS_IMM: u32 = 0xB100_0000;
+const SUB_IMM: u32 = 0xD100_0000;
+const SUBS_IMM: u32 = 0xF100_0000;
+const ADD_REG: u32 = 0x8B00_0000;
+const ADDS_REG: u32 = 0xAB00_0000;
+const SUB_REG: u32 = 0xCB00_0000;
+const SUBS_REG: u32 = 0xEB00_0000;
+/// Offset of the extended-register form (UXTX) from the shifted-register form, needed for sp
+const EXTENDED: u32 = 0x0020_6000;
+
+impl Encoder {
+    fn new() -> Self {
+        Encoder {
+            sections: vec![Section {
+                name: ".text".to_string(),
+                bytes: Vec::new(),
+                relocs: Vec::new(),
+                mapping: Vec::new(),
+                align: 4,
+            }],
+            current: 0,
+            labels: HashMap::new(),
+            globals: Vec::new(),
+            externs: Vec::new(),
+            fixups: Vec::new(),
+        }
+    }
+
+    fn offset(&self) -> u64 {
+        self.sections[self.current].bytes.len() as u64
+    }
+
+    fn mark(&mut self, code: bool) {
+        let offset = self.offset();
+        let section = &mut self.sections[self.current];
+        match section.mapping.last() {
+            Some((_, last)) if *last == code => (),
+            Some((o, _)) if *o == offset => {
+                section.mapping.pop();
+                section.mapping.push((offset, code));
+            }
+            _ => section.mapping.push((offset, code)),
+        }
+    }
+
+    fn word(&mut self, w: u32) {
+        self.mark(true);
+        self.sections[self.current]
+            .bytes
+            .extend_from_slice(&w.to_le_bytes());
+    }
+
+    fn words(&mut self, ws: Vec<u32>) {
+        for w in ws {
+            self.word(w);
+        }
+    }
+
+    fn data(&mut self, bytes: &[u8]) {
+        self.mark(false);
+        self.sections[self.current].bytes.extend_from_slice(bytes);
+    }
+
+    fn fixup(&mut self, kind: Fixup, label: &str) {
+        self.fixups.push(PendingFixup {
+            section: self.current,
+            offset: self.offset(),
+            kind,
+            label: label.to_string(),
+        });
+    }
+
+    fn switch_section(&mut self, name: &str) {
+        let name = match name {
+            "text" | "__TEXT,__text" => ".text",
+            "data" | "__DATA,__data" => ".data",
+            "__TEXT,__cstring" | "__TEXT,__const" => ".rodata",
+            other => other,
+        };
+        self.current = match self.sections.iter().position(|s| s.name == name) {
+            Some(i) => i,
+            None => {
+                self.sections.push(Section {
+                    name: name.to_string(),
+                    bytes: Vec::new(),
+                    relocs: Vec::new(),
+                    mapping: Vec::new(),
+                    align: 1,
+                });
+                self.sections.len() - 1
+            }
+        };
+    }
+
+    /// Materializes `src` in a register, using the scratch register for immediates
+    fn reg_or_scratch(&mut self, src: &Operand) -> Result<u32, String> {
+        match src {
+            Operand::Imm(i) => {
+                self.words(mov_imm(SCRATCH, *i));
+                Ok(SCRATCH)
+            }
+            _ => gpr(src),
+        }
+    }
+
+    /// Add, Sub, their flag-setting forms and Cmp share the immediate/register/extended-register encodings
+    fn arith(
+        &mut self,
+        dest: u32,
+        lhs: &Operand,
+        src: &Operand,
+        imm_ops: (u32, u32),
+        reg_op: u32,
+    ) -> Result<(), String> {
+        let rn = gpr_or_sp(lhs)?;
+        match src {
+            Operand::Imm(i) => {
+                // A negative immediate flips add and sub, the way `as` does it
+                let encoded = match i.checked_neg() {
+                    _ if *i >= 0 => add_sub_imm(imm_ops.0, dest, rn, *i),
+                    Some(n) => add_sub_imm(imm_ops.1, dest, rn, n),
+                    None => None,
+                };
+                match encoded {
+                    Some(w) => self.word(w),
+                    None => {
+                        self.words(mov_imm(SCRATCH, *i));
+                        self.word(reg_op | EXTENDED | (SCRATCH << 16) | (rn << 5) | dest);
+                    }
+                }
+            }
+            _ => {
+                let rm = gpr(src)?;
+                let uses_sp = rn == 31 || (dest == 31 && reg_op != SUBS_REG && reg_op != ADDS_REG);
+                let form = if uses_sp { EXTENDED } else { 0 };
+                self.word(reg_op | form | (rm << 16) | (rn << 5) | dest);
+            }
+        }
+        Ok(())
+    }
+
+    fn logical(
+        &mut self,
+        imm_op: u32,
+        reg_op: u32,
+        dest: &Operand,
+        src: &Operand,
+    ) -> Result<(), String> {
+        let rd = gpr(dest)?;
+        match src {
+            Operand::Imm(i) => match encode_bitmask(*i as u64) {
+                Some(bits) => self.word(imm_op | (bits << 10) | (rd << 5) | rd),
+                None => {
+                    self.words(mov_imm(SCRATCH, *i));
+                    self.word(reg_op | (SCRATCH << 16) | (rd << 5) | rd);
+                }
+            },
+            _ => self.word(reg_op | (gpr(src)? << 16) | (rd << 5) | rd),
+        }
+        Ok(())
+    }
+
+    fn shift(&mut self, dest: &Operand, src: &Operand, kind: u32) -> Result<(), String> {
+        let rd = gpr(dest)?;
+        match src {
+            Operand::Imm(i) if (0..64).contains(i) => {
+                let s = *i as u32;
+                let w = match kind {
+                    // lsl is ubfm with immr = -s mod 64, imms = 63 - s
+                    0 => 0xD340_0000 | (((64 - s) % 64) << 16) | ((63 - s) << 10),
+                    1 => 0xD340_0000 | (s << 16) | (63 << 10),
+                    _ => 0x9340_0000 | (s << 16) | (63 << 10),
+                };
+                self.word(w | (rd << 5) | rd);
+            }
+            Operand::Imm(i) => return Err(format!("shift amount out of range: {}", i)),
+            _ => {
+                let op = [0x9AC0_2000, 0x9AC0_2400, 0x9AC0_2800][kind as usize];
+                self.word(op | (gpr(src)? << 16) | (rd << 5) | rd);
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads and stores: unsigned scaled offset, unscaled (ldur/stur) or register offset
+    fn load_store(&mut self, rt: u32, mem: &Operand, load: bool) -> Result<(), String> {
+        let (base, offset) = match mem {
+            Operand::MemOffset(offset, base) => (gpr_or_sp(base)?, (**offset).clone()),
+            Operand::RegOffset(base, offset) => (register_number(base)?, Operand::Imm(*offset)),
+            _ => return Err(format!("expected a memory operand, found {}", mem)),
+        };
+        let (scaled, unscaled, register) = if load {
+            (0xF940_0000, 0xF840_0000, 0xF860_6800)
+        } else {
+            (0xF900_0000, 0xF800_0000, 0xF820_6800)
+        };
+        match offset {
+            Operand::Imm(i) if i >= 0 && i % 8 == 0 && i / 8 < 4096 => {
+                self.word(scaled | (((i / 8) as u32) << 10) | (base << 5) | rt)
+            }
+            Operand::Imm(i) if (-256..256).contains(&i) => {
+                self.word(unscaled | (((i as u32) & 0x1ff) << 12) | (base << 5) | rt)
+            }
+            Operand::Imm(i) => {
+                self.words(mov_imm(SCRATCH, i));
+                self.word(register | (SCRATCH << 16) | (base << 5) | rt);
+            }
+            reg => self.word(register | (gpr(&reg)? << 16) | (base << 5) | rt),
+        }
+        Ok(())
+    }
+
+    fn pair(
+        &mut self,
+        r1: &Operand,
+        r2: &Operand,
+        mem: &Operand,
+        load: bool,
+    ) -> Result<(), String> {
+        let (base, offset) = match mem {
+            Operand::MemOffset(offset, base) => match **offset {
+                Operand::Imm(i) => (gpr_or_sp(base)?, i),
+                _ => return Err("stp/ldp need an immediate offset".to_string()),
+            },
+            Operand::RegOffset(base, offset) => (register_number(base)?, *offset),
+            _ => return Err(format!("expected a memory operand, found {}", mem)),
+        };
+        if offset % 8 != 0 || !(-512..512).contains(&offset) {
+            return Err(format!("stp/ldp offset out of range: {}", offset));
+        }
+        let op = if load { 0xA940_0000 } else { 0xA900_0000 };
+        let imm7 = ((offset / 8) as u32) & 0x7f;
+        self.word(op | (imm7 << 15) | (gpr(r2)? << 10) | (base << 5) | gpr(r1)?);
+        Ok(())
+    }
+
+    fn directive(&mut self, directive: &Directive) -> Result<(), String> {
+        match directive {
+            Directive::Global(l) => self.globals.push(l.clone()),
+            Directive::Extern(l) => self.externs.push(l.clone()),
+            Directive::Section(name) => self.switch_section(name),
+            Directive::Label(l) => {
+                if self.labels.contains_key(l) {
+                    return Err(format!("label defined twice: {}", l));
+                }
+                self.labels.insert(l.clone(), (self.current, self.offset()));
+            }
+            Directive::DqLabel(l) => {
+                self.fixup(Fixup::Abs64, l);
+                self.data(&[0; 8]);
+            }
+            Directive::DqString(s) => {
+                self.data(s.as_bytes());
+                self.data(&[0]);
+            }
+            Directive::DqInt(i) => self.data(&i.to_le_bytes()),
+            Directive::Align(n) => {
+                let align = 1u64 << n;
+                let section = &mut self.sections[self.current];
+                section.align = section.align.max(align);
+                while !self.offset().is_multiple_of(align) {
+                    if self.offset().is_multiple_of(4) && self.current == 0 {
+                        self.word(0xD503_201F);
+                    } else {
+                        self.data(&[0]);
+                    }
+                }
+            }
+            Directive::Mov(dest, src) => match src {
+                Operand::Imm(i) => self.words(mov_imm(gpr(dest)?, *i)),
+                _ if is_sp(dest) || is_sp(src) => {
+                    self.word(ADD_IMM | (gpr_or_sp(src)? << 5) | gpr_or_sp(dest)?)
+                }
+                _ => self.word(0xAA00_0000 | (gpr(src)? << 16) | (ZR << 5) | gpr(dest)?),
+            },
+            Directive::Add(dest, src) => {
+                self.arith(gpr_or_sp(dest)?, dest, src, (ADD_IMM, SUB_IMM), ADD_REG)?
+            }
+            Directive::Sub(dest, src) => {
+                self.arith(gpr_or_sp(dest)?, dest, src, (SUB_IMM, ADD_IMM), SUB_REG)?
+            }
+            Directive::Adds(dest, src) => {
+                self.arith(gpr(dest)?, dest, src, (ADDS_IMM, SUBS_IMM), ADDS_REG)?
+            }
+            Directive::Subs(dest, src) => {
+                self.arith(gpr(dest)?, dest, src, (SUBS_IMM, ADDS_IMM), SUBS_REG)?
+            }
+            Directive::Cmp(lhs, src) => self.arith(ZR, lhs, src, (SUBS_IMM, ADDS_IMM), SUBS_REG)?,
+            Directive::Mul(dest, src) => {
+                let rd = gpr(dest)?;
+                let rm = self.reg_or_scratch(src)?;
+                self.word(0x9B00_7C00 | (rm << 16) | (rd << 5) | rd);
+            }
+            Directive::Sdiv(dest, src) => {
+                let rd = gpr(dest)?;
+                let rm = self.reg_or_scratch(src)?;
+                self.word(0x9AC0_0C00 | (rm << 16) | (rd << 5) | rd);
+            }
+            Directive::Lsl(dest, src) => self.shift(dest, src, 0)?,
+            Directive::Lsr(dest, src) => self.shift(dest, src, 1)?,
+            Directive::Asr(dest, src) => self.shift(dest, src, 2)?,
+            Directive::And(dest, src) => self.logical(0x9200_0000, 0x8A00_0000, dest, src)?,
+            Directive::Orr(dest, src) => self.logical(0xB200_0000, 0xAA00_0000, dest, src)?,
+            Directive::Cset(dest, cond) => {
+                // cset is csinc Xd, xzr, xzr with the inverted condition
+                let inverted = condition_code(cond)? ^ 1;
+                self.word(0x9A9F_07E0 | (inverted << 12) | gpr(dest)?);
+            }
+            Directive::Adr(dest, label) => {
+                let rd = gpr(dest)?;
+                self.fixup(Fixup::Adr21, label);
+                self.word(0x1000_0000 | rd);
+            }
+            Directive::B(l) => {
+                self.fixup(Fixup::Branch26 { call: false }, l);
+                self.word(0x1400_0000);
+            }
+            Directive::Bl(l) => {
+                self.fixup(Fixup::Branch26 { call: true }, l);
+                self.word(0x9400_0000);
+            }
+            Directive::Beq(l) => self.cond_branch(0, l),
+            Directive::Bne(l) => self.cond_branch(1, l),
+            Directive::Bge(l) => self.cond_branch(10, l),
+            Directive::Blt(l) => self.cond_branch(11, l),
+            Directive::Bgt(l) => self.cond_branch(12, l),
+            Directive::Ble(l) => self.cond_branch(13, l),
+            Directive::Bls(l) => self.cond_branch(9, l),
+            Directive::Bvs(l) => self.cond_branch(6, l),
+            Directive::Br(r) => self.word(0xD61F_0000 | (gpr(r)? << 5)),
+            Directive::Str(dest, src) => self.load_store(gpr(src)?, dest, false)?,
+            Directive::Ldr(dest, src) => self.load_store(gpr(dest)?, src, true)?,
+            Directive::Stp(r1, r2, dest) => self.pair(r1, r2, dest, false)?,
+            Directive::Ldp(r1, r2, src) => self.pair(r1, r2, src, true)?,
+            Directive::Ret => self.word(0xD65F_03C0),
+            Directive::Comment(_) => (),
+        }
+        Ok(())
+    }
+
+    fn cond_branch(&mut self, cond: u32, label: &str) {
+        self.fixup(Fixup::CondBranch19, label);
+        self.word(0x5400_0000 | cond);
+    }
+
+    fn patch(&mut self, fixup: &PendingFixup, target: u64) -> Result<(), String> {
+        let delta = target as i64 - fixup.offset as i64;
+        let at = fixup.offset as usize;
+        let bytes = &mut self.sections[fixup.section].bytes;
+        let mut word = u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let in_range = |bits: u32, v: i64| v >= -(1 << (bits - 1)) && v < (1 << (bits - 1));
+        match fixup.kind {
+            Fixup::Branch26 { .. } if in_range(26, delta / 4) => {
+                word |= ((delta / 4) as u32) & 0x3ff_ffff
+            }
+            Fixup::CondBranch19 if in_range(19, delta / 4) => {
+                word |= (((delta / 4) as u32) & 0x7_ffff) << 5
+            }
+            Fixup::Adr21 if in_range(21, delta) => {
+                word |= (((delta as u32) & 0x3) << 29) | ((((delta >> 2) as u32) & 0x7_ffff) << 5)
+            }
+            _ => return Err(format!("branch to {} out of range", fixup.label)),
+        }
+        bytes[at..at + 4].copy_from_slice(&word.to_le_bytes());
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Object, String> {
+        let fixups = std::mem::take(&mut self.fixups);
+        for fixup in &fixups {
+            match (self.labels.get(&fixup.label).copied(), fixup.kind) {
+                (Some((section, target)), kind)
+                    if section == fixup.section && !matches!(kind, Fixup::Abs64) =>
+                {
+                    self.patch(fixup, target)?
+                }
+                (_, kind) => {
+                    let kind = match kind {
+                        Fixup::Branch26 { call: true } => R_AARCH64_CALL26,
+                        Fixup::Branch26 { call: false } => R_AARCH64_JUMP26,
+                        Fixup::CondBranch19 => R_AARCH64_CONDBR19,
+                        Fixup::Adr21 => R_AARCH64_ADR_PREL_LO21,
+                        Fixup::Abs64 => R_AARCH64_ABS64,
+                    };
+                    self.sections[fixup.section].relocs.push(Reloc {
+                        offset: fixup.offset,
+                        kind,
+                        symbol: fixup.label.clone(),
+                        addend: 0,
+                    });
+                }
+            }
+        }
+
+        let mut symbols: Vec<Symbol> = self
+            .labels
+            .iter()
+            .map(|(name, (section, value))| Symbol {
+                name: name.clone(),
+                section: Some(*section),
+                value: *value,
+                global: self.globals.contains(name),
+            })
+            .collect();
+        symbols.sort_by_key(|s| (s.section, s.value));
+
+        let referenced = self
+            .sections
+            .iter()
+            .flat_map(|s| s.relocs.iter().map(|r| r.symbol.clone()));
+        for name in self.externs.iter().cloned().chain(referenced) {
+            if !symbols.iter().any(|s| s.name == name) {
+                symbols.push(Symbol {
+                    name,
+                    section: None,
+                    value: 0,
+                    global: true,
+                });
+            }
+        }
+        if let Some(g) = self.globals.iter().find(|g| !self.labels.contains_key(*g)) {
+            return Err(format!("global symbol is never defined: {}", g));
+        }
+
+        Ok(Object {
+            sections: self.sections,
+            symbols,
+        })
+    }
+}
+
+/// Encodes a directive list into AArch64 machine code, resolving local branches and
+/// recording relocations for everything that has to be left to the linker
+pub fn encode(directives: &[Directive]) -> Result<Object, String> {
+    let mut encoder = Encoder::new();
+    for directive in directives {
+        encoder.directive(directive)?;
+    }
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::{
+        Directive::*,
+        Operand::{Imm, MemOffset, Reg},
+        Register::*,
+    };
+
+    /// The words of `.text`. The expected words below are what `as` gives for the instructions
+    /// in the comments next to them.
+    fn words(directives: &[Directive]) -> Vec<u32> {
+        let object = encode(directives).unwrap();
+        object.sections[0]
+            .bytes
+            .chunks(4)
+            .map(|w| u32::from_le_bytes(w.try_into().unwrap()))
+            .collect()
+    }
+
+    fn mem(offset: i64, base: Register) -> Operand {
+        MemOffset(Box::new(Imm(offset)), Box::new(Reg(base)))
+    }
+
+    #[test]
+    fn add_sub() {
+        let cases = [
+            (Add(Reg(X9), Imm(16)), 0x9100_4129),   // add x9, x9, #16
+            (Add(Reg(X9), Imm(4096)), 0x9140_0529), // add x9, x9, #1, lsl #12
+            (Add(Reg(X9), Imm(-8)), 0xD100_2129),   // sub x9, x9, #8
+            (Add(Reg(X9), Reg(X10)), 0x8B0A_0129),  // add x9, x9, x10
+            (Add(Reg(Sp), Reg(X9)), 0x8B29_63FF),   // add sp, sp, x9
+            (Adds(Reg(X9), Imm(1)), 0xB100_0529),   // adds x9, x9, #1
+            (Adds(Reg(X9), Reg(X10)), 0xAB0A_0129), // adds x9, x9, x10
+            (Sub(Reg(X9), Imm(8)), 0xD100_2129),    // sub x9, x9, #8
+            (Sub(Reg(Sp), Imm(32)), 0xD100_83FF),   // sub sp, sp, #32
+            (Sub(Reg(X9), Reg(X10)), 0xCB0A_0129),  // sub x9, x9, x10
+            (Subs(Reg(X9), Imm(1)), 0xF100_0529),   // subs x9, x9, #1
+            (Subs(Reg(X9), Reg(X10)), 0xEB0A_0129), // subs x9, x9, x10
+            (Cmp(Reg(X9), Imm(5)), 0xF100_153F),    // cmp x9, #5
+            (Mov(Reg(Fp), Reg(Sp)), 0x9100_03FD),   // mov x29, sp
+            (Mov(Reg(X9), Reg(X10)), 0xAA0A_03E9),  // mov x9, x10
+            (Cset(Reg(X9), "eq".to_string()), 0x9A9F_17E9), // cset x9, eq
+        ];
+        for (directive, word) in cases {
+            assert_eq!(
+                words(std::slice::from_ref(&directive)),
+                [word],
+                "{:?}",
+                directive
+            );
+        }
+    }
+
+    #[test]
+    fn extended_register_forms() {
+        // An immediate too wide for imm12 goes through x16
+        assert_eq!(
+            words(&[Add(Reg(X9), Imm(0x11235))]),
+            [
+                0xD282_46B0, // mov x16, #0x1235
+                0xF2A0_0030, // movk x16, #1, lsl #16
+                0x8B30_6129, // add x9, x9, x16, uxtx
+            ]
+        );
+        assert_eq!(
+            words(&[Sub(Reg(Sp), Imm(0x11235))]),
+            [0xD282_46B0, 0xF2A0_0030, 0xCB30_63FF] // sub sp, sp, x16
+        );
+        assert_eq!(
+            words(&[Subs(Reg(X9), Imm(0x11235))]),
+            [0xD282_46B0, 0xF2A0_0030, 0xEB30_6129] // subs x9, x9, x16, uxtx
+        );
+    }
+
+    #[test]
+    fn loads_and_stores() {
+        let cases = [
+            (Stp(Reg(Fp), Reg(Lr), mem(-16, Sp)), 0xA93F_7BFD), // stp x29, x30, [sp, #-16]
+            (Ldp(Reg(Fp), Reg(Lr), mem(16, Sp)), 0xA941_7BFD),  // ldp x29, x30, [sp, #16]
+            (Ldr(Reg(X9), mem(16, Fp)), 0xF940_0BA9),           // ldr x9, [x29, #16]
+            (Str(mem(8, Fp), Reg(X9)), 0xF900_07A9),            // str x9, [x29, #8]
+            (Ldr(Reg(X9), mem(-24, Fp)), 0xF85E_83A9),          // ldur x9, [x29, #-24]
+            (Str(mem(-8, Fp), Reg(X9)), 0xF81F_83A9),           // stur x9, [x29, #-8]
+        ];
+        for (directive, word) in cases {
+            assert_eq!(
+                words(std::slice::from_ref(&directive)),
+                [word],
+                "{:?}",
+                directive
+            );
+        }
+    }
+
+    #[test]
+    fn move_immediates() {
+        let cases: [(i64, &[u32]); 5] = [
+            (42, &[0xD280_0549]),      // mov x9, #42 (movz)
+            (0x10000, &[0xD2A0_0029]), // mov x9, #0x10000 (movz, lsl #16)
+            (-2, &[0x9280_0029]),      // mov x9, #-2 (movn)
+            // mov x9, #0x5555555555555555 (orr with a bitmask immediate)
+            (0x5555_5555_5555_5555, &[0xB200_F3E9]),
+            // movz x9, #0x5678; movk x9, #0x1234, lsl #16
+            (0x1234_5678, &[0xD28A_CF09, 0xF2A2_4689]),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(words(&[Mov(Reg(X9), Imm(value))]), expected, "{:#x}", value);
+        }
+        // orr x9, x9, #0xff
+        assert_eq!(words(&[Orr(Reg(X9), Imm(0xff))]), [0xB240_1D29]);
+    }
+
+    #[test]
+    fn bitmasks() {
+        assert_eq!(encode_bitmask(0x5555_5555_5555_5555), Some(0x03C));
+        assert_eq!(encode_bitmask(0xff), Some(0x1007));
+        assert_eq!(encode_bitmask(0), None);
+        assert_eq!(encode_bitmask(u64::MAX), None);
+        assert_eq!(encode_bitmask(0x1234_5678), None);
+    }
+
+    #[test]
+    fn branches() {
+        let code = [
+            Label("top".to_string()),
+            Beq("top".to_string()),
+            Blt("next".to_string()),
+            Bls("next".to_string()),
+            Bl("top".to_string()),
+            B("next".to_string()),
+            Label("next".to_string()),
+            Br(Reg(X9)),
+            Ret,
+            Align(4),
+        ];
+        assert_eq!(
+            words(&code),
+            [
+                0x5400_0000, // b.eq top
+                0x5400_008B, // b.lt next
+                0x5400_0069, // b.ls next
+                0x97FF_FFFD, // bl top
+                0x1400_0001, // b next
+                0xD61F_0120, // br x9
+                0xD65F_03C0, // ret
+                0xD503_201F, // nop, as padding
+            ]
+        );
+    }
+
+    #[test]
+    fn relocations() {
+        let object = encode(&[
+            Global("lisp_entry".to_string()),
+            Extern("lisp_error".to_string()),
+            Label("lisp_entry".to_string()),
+            Bl("lisp_error".to_string()),
+            Ret,
+        ])
+        .unwrap();
+        let relocs = &object.sections[0].relocs;
+        assert_eq!(relocs.len(), 1);
+        assert_eq!(
+            (relocs[0].offset, relocs[0].kind, relocs[0].symbol.as_str()),
+            (0, R_AARCH64_CALL26, "lisp_error")
+        );
+        assert_eq!(object.sections[0].bytes[..4], 0x9400_0000u32.to_le_bytes());
+    }
+}
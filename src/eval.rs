@@ -0,0 +1,495 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::ast::{float_literal, BinaryOp, Expr, TernaryOp, UnaryOp};
+use crate::bignum::BigInt;
+use crate::compile::{NUM_MAX, NUM_MIN};
+use crate::sim::Output;
+
+/// Deepest nesting of evaluations, counting each subexpression and call, before evaluation gives up
+const MAX_DEPTH: usize = 100_000;
+
+/// Stack for the thread `on_stack` evaluates on, enough for `MAX_DEPTH` nested evaluations even
+/// in a debug build, where one takes up to about 4KiB
+const STACK_SIZE: usize = 1 << 30;
+
+/// Runtime values, with the same shapes the compiled code tags
+#[derive(Clone, Debug)]
+pub enum Value {
+    Num(i64),
+    /// A number outside the fixnum range, which the compiled code keeps on the heap
+    Big(Rc<BigInt>),
+    Float(f64),
+    Bool(bool),
+    Nil,
+    Pair(Rc<(Value, Value)>),
+    Vector(Rc<RefCell<Vec<Value>>>),
+    Function(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// A runtime error the compiled code reports through `lisp_error`
+    Stuck(String),
+    Unbound(String),
+    /// A literal no parsed program can hold, rejected as the compiler rejects it
+    NumberOutOfRange(i64),
+    DepthExceeded,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Stuck(msg) => write!(f, "Stuck[{}]", msg),
+            Error::Unbound(name) => write!(f, "Unbound variable: {}", name),
+            Error::NumberOutOfRange(n) => write!(f, "{} does not fit in a tagged number", n),
+            Error::DepthExceeded => write!(f, "Recursion deeper than {} levels", MAX_DEPTH),
+        }
+    }
+}
+
+/// Prints exactly like `print_value` in runtime.c
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Num(n) => write!(f, "{}", n),
+            Value::Big(n) => write!(f, "{}", n),
+            Value::Float(x) => write!(f, "{}", float_literal(*x)),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Nil => write!(f, "()"),
+            Value::Pair(p) => write!(f, "(pair {} {})", p.0, p.1),
+            Value::Vector(v) => {
+                let elements: Vec<String> = v.borrow().iter().map(|e| e.to_string()).collect();
+                write!(f, "[{}]", elements.join(" "))
+            }
+            Value::Function(name) => write!(f, "<function {}>", name),
+        }
+    }
+}
+
+/// A fixnum when `n` fits in one and a bignum otherwise, like `make_number` in runtime.c
+fn integer(n: BigInt) -> Value {
+    match n.to_i64() {
+        Some(i) if (NUM_MIN..=NUM_MAX).contains(&i) => Value::Num(i),
+        _ => Value::Big(Rc::new(n)),
+    }
+}
+
+fn big(v: &Value, op: &dyn fmt::Display) -> Result<BigInt, Error> {
+    match v {
+        Value::Num(n) => Ok(BigInt::from_i64(*n)),
+        Value::Big(n) => Ok(n.as_ref().clone()),
+        _ => Err(Error::Stuck(op.to_string())),
+    }
+}
+
+fn num(v: &Value, op: &dyn fmt::Display) -> Result<i64, Error> {
+    match v {
+        Value::Num(n) => Ok(*n),
+        _ => Err(Error::Stuck(op.to_string())),
+    }
+}
+
+fn float(v: &Value, op: &dyn fmt::Display) -> Result<f64, Error> {
+    match v {
+        Value::Num(n) => Ok(*n as f64),
+        Value::Big(n) => Ok(n.to_f64()),
+        Value::Float(x) => Ok(*x),
+        _ => Err(Error::Stuck(op.to_string())),
+    }
+}
+
+/// Both operands as doubles when either is a float, which makes the whole operation work on floats
+fn floats(a: &Value, b: &Value, op: &dyn fmt::Display) -> Result<Option<(f64, f64)>, Error> {
+    match (a, b) {
+        (Value::Float(_), _) | (_, Value::Float(_)) => Ok(Some((float(a, op)?, float(b, op)?))),
+        _ => Ok(None),
+    }
+}
+
+/// `floor` or `round` of a float, leaving integers alone
+fn whole(v: Value, op: &UnaryOp, f: fn(f64) -> f64) -> Result<Value, Error> {
+    match v {
+        Value::Num(_) | Value::Big(_) => Ok(v),
+        Value::Float(x) if x.is_finite() => Ok(integer(BigInt::from_f64(f(x)))),
+        _ => Err(Error::Stuck(op.to_string())),
+    }
+}
+
+fn vector(v: &Value, op: &dyn fmt::Display) -> Result<Rc<RefCell<Vec<Value>>>, Error> {
+    match v {
+        Value::Vector(v) => Ok(v.clone()),
+        _ => Err(Error::Stuck(op.to_string())),
+    }
+}
+
+fn index(v: &Rc<RefCell<Vec<Value>>>, i: &Value, op: &dyn fmt::Display) -> Result<usize, Error> {
+    match num(i, op)? {
+        i if i >= 0 && (i as usize) < v.borrow().len() => Ok(i as usize),
+        _ => Err(Error::Stuck(op.to_string())),
+    }
+}
+
+/// Identity for heap values and equality for immediates, like comparing tagged words, except
+/// that numbers compare by value as `lisp_equal` does
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(_), _) | (_, Value::Float(_)) => {
+            matches!(floats(a, b, &"=="), Ok(Some((x, y))) if x == y)
+        }
+        (Value::Num(x), Value::Num(y)) => x == y,
+        (Value::Big(x), Value::Big(y)) => x == y,
+        (Value::Bool(x), Value::Bool(y)) => x == y,
+        (Value::Nil, Value::Nil) => true,
+        (Value::Pair(x), Value::Pair(y)) => Rc::ptr_eq(x, y),
+        (Value::Vector(x), Value::Vector(y)) => Rc::ptr_eq(x, y),
+        (Value::Function(x), Value::Function(y)) => x == y,
+        _ => false,
+    }
+}
+
+fn is_list(v: &Value) -> bool {
+    match v {
+        Value::Nil => true,
+        Value::Pair(p) => is_list(&p.1),
+        _ => false,
+    }
+}
+
+/// Evaluates expressions, keeping function definitions between calls to `eval`
+#[derive(Default)]
+pub struct Interpreter {
+    functions: HashMap<String, (Vec<String>, Expr)>,
+    depth: usize,
+    overflow_checks: bool,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An interpreter that gets stuck on overflow, like code compiled with overflow checks
+    pub fn with_overflow_checks() -> Self {
+        Interpreter {
+            overflow_checks: true,
+            ..Self::default()
+        }
+    }
+
+    /// The result of `+`, `-`, `add1` or `sub1`: a float if either operand is one, otherwise an
+    /// integer promoted to a bignum when it leaves the fixnum range
+    fn arithmetic(
+        &self,
+        op: &dyn fmt::Display,
+        a: &Value,
+        b: &Value,
+        subtract: bool,
+    ) -> Result<Value, Error> {
+        if let Some((x, y)) = floats(a, b, op)? {
+            return Ok(Value::Float(if subtract { x - y } else { x + y }));
+        }
+        let (x, y) = (big(a, op)?, big(b, op)?);
+        let result = integer(if subtract { x.sub(&y) } else { x.add(&y) });
+        if self.overflow_checks && matches!(result, Value::Big(_)) {
+            return Err(Error::Stuck("overflow".to_string()));
+        }
+        Ok(result)
+    }
+
+    pub fn eval(&mut self, expr: &Expr) -> Result<Value, Error> {
+        self.eval_expr(&HashMap::new(), expr)
+    }
+
+    fn define(&mut self, name: &str, params: &[String], body: &Expr) -> Value {
+        self.functions
+            .insert(name.to_string(), (params.to_vec(), body.clone()));
+        Value::Function(name.to_string())
+    }
+
+    fn eval_unary(&self, op: &UnaryOp, v: Value) -> Result<Value, Error> {
+        match op {
+            UnaryOp::Add1 => self.arithmetic(op, &v, &Value::Num(1), false),
+            UnaryOp::Sub1 => self.arithmetic(op, &v, &Value::Num(1), true),
+            UnaryOp::IsZero => Ok(Value::Bool(float(&v, op)? == 0.0)),
+            UnaryOp::IsNum => Ok(Value::Bool(matches!(
+                v,
+                Value::Num(_) | Value::Big(_) | Value::Float(_)
+            ))),
+            UnaryOp::Not => Ok(Value::Bool(matches!(v, Value::Bool(false)))),
+            UnaryOp::IsPair => Ok(Value::Bool(matches!(v, Value::Pair(_)))),
+            UnaryOp::Left => match v {
+                Value::Pair(p) => Ok(p.0.clone()),
+                _ => Err(Error::Stuck(op.to_string())),
+            },
+            UnaryOp::Right => match v {
+                Value::Pair(p) => Ok(p.1.clone()),
+                _ => Err(Error::Stuck(op.to_string())),
+            },
+            UnaryOp::IsList => Ok(Value::Bool(is_list(&v))),
+            UnaryOp::IsVector => Ok(Value::Bool(matches!(v, Value::Vector(_)))),
+            UnaryOp::VectorLength => Ok(Value::Num(vector(&v, op)?.borrow().len() as i64)),
+            UnaryOp::Floor => whole(v, op, f64::floor),
+            UnaryOp::Round => whole(v, op, f64::round),
+            UnaryOp::ToFloat => Ok(Value::Float(float(&v, op)?)),
+        }
+    }
+
+    fn eval_binary(&self, op: &BinaryOp, a: Value, b: Value) -> Result<Value, Error> {
+        match op {
+            BinaryOp::Add => self.arithmetic(op, &a, &b, false),
+            BinaryOp::Sub => self.arithmetic(op, &a, &b, true),
+            BinaryOp::Eq => Ok(Value::Bool(same(&a, &b))),
+            BinaryOp::Lt => match floats(&a, &b, op)? {
+                Some((x, y)) => Ok(Value::Bool(x < y)),
+                None => Ok(Value::Bool(big(&a, op)? < big(&b, op)?)),
+            },
+            BinaryOp::Pair => Ok(Value::Pair(Rc::new((a, b)))),
+            BinaryOp::Vector => match num(&a, op)? {
+                n if n >= 0 => Ok(Value::Vector(Rc::new(RefCell::new(vec![b; n as usize])))),
+                _ => Err(Error::Stuck(op.to_string())),
+            },
+            BinaryOp::VectorGet => {
+                let v = vector(&a, op)?;
+                let i = index(&v, &b, op)?;
+                let element = v.borrow()[i].clone();
+                Ok(element)
+            }
+        }
+    }
+
+    fn eval_ternary(&self, op: &TernaryOp, a: Value, b: Value, c: Value) -> Result<Value, Error> {
+        match op {
+            TernaryOp::VectorSet => {
+                let v = vector(&a, op)?;
+                let i = index(&v, &b, op)?;
+                v.borrow_mut()[i] = c;
+                Ok(a)
+            }
+        }
+    }
+
+    fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, Error> {
+        let (params, body) = self
+            .functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::Unbound(name.to_string()))?;
+        if params.len() != args.len() {
+            return Err(Error::Stuck(name.to_string()));
+        }
+        let env = params.into_iter().zip(args).collect();
+        self.eval_expr(&env, &body)
+    }
+
+    fn eval_expr(&mut self, env: &HashMap<String, Value>, expr: &Expr) -> Result<Value, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::DepthExceeded);
+        }
+        self.depth += 1;
+        let result = self.eval_nested(env, expr);
+        self.depth -= 1;
+        result
+    }
+
+    fn eval_nested(&mut self, env: &HashMap<String, Value>, expr: &Expr) -> Result<Value, Error> {
+        match expr {
+            Expr::Num(n) if !(NUM_MIN..=NUM_MAX).contains(n) => Err(Error::NumberOutOfRange(*n)),
+            Expr::Num(n) => Ok(Value::Num(*n)),
+            Expr::Float(x) => Ok(Value::Float(*x)),
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+            Expr::Unit => Ok(Value::Nil),
+            Expr::Id(name) => match env.get(name) {
+                Some(v) => Ok(v.clone()),
+                None if self.functions.contains_key(name) => Ok(Value::Function(name.clone())),
+                None => Err(Error::Unbound(name.clone())),
+            },
+            Expr::UnOp(op, e) => {
+                let v = self.eval_expr(env, e)?;
+                self.eval_unary(op, v)
+            }
+            Expr::BinOp(op, e1, e2) => {
+                let a = self.eval_expr(env, e1)?;
+                let b = self.eval_expr(env, e2)?;
+                self.eval_binary(op, a, b)
+            }
+            Expr::TriOp(op, e1, e2, e3) => {
+                let a = self.eval_expr(env, e1)?;
+                let b = self.eval_expr(env, e2)?;
+                let c = self.eval_expr(env, e3)?;
+                self.eval_ternary(op, a, b, c)
+            }
+            Expr::If(test, then_expr, else_expr) => match self.eval_expr(env, test)? {
+                Value::Bool(false) => self.eval_expr(env, else_expr),
+                _ => self.eval_expr(env, then_expr),
+            },
+            Expr::Let(bindings, body) => {
+                let mut new_env = env.clone();
+                for (var, exp) in bindings {
+                    let v = self.eval_expr(&new_env, exp)?;
+                    new_env.insert(var.clone(), v);
+                }
+                self.eval_expr(&new_env, body)
+            }
+            Expr::Do(exps) => {
+                // Definitions in a block are visible to the whole block, so they can call each other
+                for e in exps {
+                    if let Expr::FuncDef(name, params, body) = e.as_ref() {
+                        self.define(name, params, body);
+                    }
+                }
+                let mut result = Value::Nil;
+                for e in exps {
+                    result = self.eval_expr(env, e)?;
+                }
+                Ok(result)
+            }
+            Expr::FuncDef(name, params, body) => Ok(self.define(name, params, body)),
+            Expr::FuncCall(callee, args) => {
+                let name = match self.eval_expr(env, callee)? {
+                    Value::Function(name) => name,
+                    _ => return Err(Error::Stuck("call".to_string())),
+                };
+                let mut values = Vec::new();
+                for arg in args {
+                    values.push(self.eval_expr(env, arg)?);
+                }
+                self.call(&name, values)
+            }
+        }
+    }
+}
+
+/// Runs a program the way the compiled binary would, printing the result or the `lisp_error` text
+pub fn run(expr: &Expr) -> Result<Output, String> {
+    run_with(Interpreter::new(), expr)
+}
+
+/// `run` with an interpreter the caller set up
+pub fn run_with(mut interpreter: Interpreter, expr: &Expr) -> Result<Output, String> {
+    on_stack(|| match interpreter.eval(expr) {
+        Ok(v) => Ok(Output {
+            stdout: format!("{}\n", v),
+            exit_code: 0,
+        }),
+        Err(e @ Error::Stuck(_)) => Ok(Output {
+            stdout: e.to_string(),
+            exit_code: 1,
+        }),
+        Err(e) => Err(e.to_string()),
+    })
+}
+
+/// Runs `f` on a thread with a stack large enough that deep recursion in the evaluated program
+/// ends in `Error::DepthExceeded` rather than overflowing the native stack
+pub fn on_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("failed to spawn the evaluation thread")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::compile;
+
+    #[test]
+    fn out_of_range_literal() {
+        let expr = Expr::BinOp(
+            BinaryOp::Add,
+            Box::new(Expr::Num(NUM_MAX + 1)),
+            Box::new(Expr::Num(1)),
+        );
+        let compiled = compile(expr.clone()).unwrap_err().to_string();
+        assert_eq!(run(&expr).unwrap_err(), compiled);
+    }
+
+    fn eval_source(source: &str) -> Result<String, Error> {
+        let expr = crate::parse(source).unwrap();
+        on_stack(|| Interpreter::new().eval(&expr).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn operators() {
+        let cases = [
+            ("add1(41)", "42"),
+            ("sub1(0)", "-1"),
+            ("isZero(0)", "true"),
+            ("isZero(0.0)", "true"),
+            ("isNum(true)", "false"),
+            ("isNum(1.5)", "true"),
+            ("!(false)", "true"),
+            ("!(0)", "false"),
+            ("isPair(pair(1, 2))", "true"),
+            ("left(pair(1, 2))", "1"),
+            ("right(pair(1, 2))", "2"),
+            ("isList(pair(1, {}))", "true"),
+            ("isList(pair(1, 2))", "false"),
+            ("isVector(vector(2, 0))", "true"),
+            ("vectorLength(vector(3, 0))", "3"),
+            ("floor(2.5)", "2"),
+            ("round(2.5)", "3"),
+            ("toFloat(3)", "3.0"),
+            ("1 + 2", "3"),
+            ("1 - 2.5", "-1.5"),
+            ("3 == 3", "true"),
+            ("pair(1, 2) == pair(1, 2)", "false"),
+            ("1 < 2", "true"),
+            ("2.5 < 2", "false"),
+            ("pair(1, true)", "(pair 1 true)"),
+            ("vector(2, {})", "[() ()]"),
+            ("vectorGet(vector(2, 7), 1)", "7"),
+            ("vectorSet(vector(2, 0), 0, 5)", "[5 0]"),
+            ("2305843009213693951 + 1", "2305843009213693952"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(eval_source(source).unwrap(), expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let stuck = |op: &str| Err(Error::Stuck(op.to_string()));
+        let cases = [
+            ("add1(true)", stuck("add1")),
+            ("1 < {}", stuck("<")),
+            ("left(1)", stuck("left")),
+            ("right({})", stuck("right")),
+            ("vectorLength(1)", stuck("vectorLength")),
+            ("vector(-1, 0)", stuck("vector")),
+            ("vectorGet(vector(2, 0), 2)", stuck("vectorGet")),
+            ("vectorSet(vector(2, 0), -1, 0)", stuck("vectorSet")),
+            ("{ def f(x) = x; f(1, 2) }", stuck("f")),
+            ("{ def f(x) = x; let g = 1 in g(2) }", stuck("call")),
+            ("x", Err(Error::Unbound("x".to_string()))),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(eval_source(source), expected, "{}", source);
+        }
+        let mut interpreter = Interpreter::with_overflow_checks();
+        let expr = crate::parse("add1(2305843009213693951)").unwrap();
+        assert_eq!(
+            interpreter.eval(&expr).unwrap_err(),
+            Error::Stuck("overflow".to_string())
+        );
+    }
+
+    #[test]
+    fn deep_recursion() {
+        let source = "{ def f(n) = if (isZero(n)) 0 else add1(f(sub1(n))); f(1000000) }";
+        assert_eq!(eval_source(source), Err(Error::DepthExceeded));
+        let source = "{ def f(n) = if (isZero(n)) 0 else add1(f(sub1(n))); f(20000) }";
+        assert_eq!(eval_source(source).unwrap(), "20000");
+        assert_eq!(
+            run(&crate::parse("{ def f(n) = f(n); f(0) }").unwrap()).unwrap_err(),
+            Error::DepthExceeded.to_string()
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::{
+    anf::{Anf, Atom, Op, Value},
+    ast::{BinaryOp, UnaryOp},
+    compile::{NUM_MAX, NUM_MIN},
+};
+
+/// Folds the steps of a program whose operands are known and propagates what variables are
+/// bound to atoms, dropping those bindings. An `if` on a known condition becomes the branch it
+/// takes.
+///
+/// Only steps that cannot fail are folded, so that an error like `true + 1` still stops the
+/// program when it runs, and only to results that fit in a fixnum, leaving promotion to a
+/// bignum or an overflow error to the runtime. Floats are left alone.
+pub fn fold(anf: Anf) -> Anf {
+    fold_tail(anf, &mut HashMap::new())
+}
+
+fn fold_tail(anf: Anf, atoms: &mut HashMap<String, Atom>) -> Anf {
+    let mut bindings = Vec::new();
+    let value = fold_into(anf, atoms, &mut bindings);
+    bindings
+        .into_iter()
+        .rev()
+        .fold(Anf::Value(value), |body, (var, value)| {
+            Anf::Let(var, value, Box::new(body))
+        })
+}
+
+/// The value of `anf`, after the bindings it keeps, which go onto `bindings`
+fn fold_into(
+    anf: Anf,
+    atoms: &mut HashMap<String, Atom>,
+    bindings: &mut Vec<(String, Value)>,
+) -> Value {
+    let mut anf = anf;
+    loop {
+        let (var, value, body) = match anf {
+            Anf::Let(var, value, body) => (var, value, *body),
+            Anf::Value(value) => return fold_value(value, atoms, bindings),
+        };
+        // Variables are bound once, so one map serves the whole program
+        match fold_value(value, atoms, bindings) {
+            Value::Op(Op::Atom(atom)) => {
+                atoms.insert(var, atom);
+            }
+            value => bindings.push((var, value)),
+        }
+        anf = body;
+    }
+}
+
+fn fold_value(
+    value: Value,
+    atoms: &mut HashMap<String, Atom>,
+    bindings: &mut Vec<(String, Value)>,
+) -> Value {
+    match value {
+        Value::Op(op) => Value::Op(fold_op(substitute(op, atoms))),
+        Value::If(cond, then_branch, else_branch) => match propagate(cond, atoms) {
+            cond @ Atom::Var(_) => Value::If(
+                cond,
+                Box::new(fold_tail(*then_branch, atoms)),
+                Box::new(fold_tail(*else_branch, atoms)),
+            ),
+            Atom::Bool(false) => fold_into(*else_branch, atoms, bindings),
+            _ => fold_into(*then_branch, atoms, bindings),
+        },
+    }
+}
+
+fn propagate(atom: Atom, atoms: &HashMap<String, Atom>) -> Atom {
+    match atom {
+        Atom::Var(var) => atoms.get(&var).cloned().unwrap_or(Atom::Var(var)),
+        literal => literal,
+    }
+}
+
+fn substitute(op: Op, atoms: &HashMap<String, Atom>) -> Op {
+    let p = |a| propagate(a, atoms);
+    match op {
+        Op::Atom(a) => Op::Atom(p(a)),
+        Op::UnOp(op, a) => Op::UnOp(op, p(a)),
+        Op::BinOp(op, a1, a2) => Op::BinOp(op, p(a1), p(a2)),
+        Op::TriOp(op, a1, a2, a3) => Op::TriOp(op, p(a1), p(a2), p(a3)),
+    }
+}
+
+/// A number as an atom, if it fits in a fixnum
+fn fixnum(n: Option<i64>) -> Option<Atom> {
+    n.filter(|n| (NUM_MIN..=NUM_MAX).contains(n)).map(Atom::Num)
+}
+
+/// Numbers, booleans and `()`, whose equality does not depend on how they are stored
+fn exact(atom: &Atom) -> bool {
+    matches!(atom, Atom::Num(_) | Atom::Bool(_) | Atom::Unit)
+}
+
+fn fold_op(op: Op) -> Op {
+    let folded = match &op {
+        Op::UnOp(UnaryOp::Add1, Atom::Num(n)) => fixnum(n.checked_add(1)),
+        Op::UnOp(UnaryOp::Sub1, Atom::Num(n)) => fixnum(n.checked_sub(1)),
+        Op::UnOp(UnaryOp::IsZero, Atom::Num(n)) => Some(Atom::Bool(*n == 0)),
+        // Anything but false counts as true
+        Op::UnOp(UnaryOp::Not, a) if !matches!(a, Atom::Var(_)) => {
+            Some(Atom::Bool(*a == Atom::Bool(false)))
+        }
+        Op::BinOp(BinaryOp::Add, Atom::Num(x), Atom::Num(y)) => fixnum(x.checked_add(*y)),
+        Op::BinOp(BinaryOp::Sub, Atom::Num(x), Atom::Num(y)) => fixnum(x.checked_sub(*y)),
+        Op::BinOp(BinaryOp::Lt, Atom::Num(x), Atom::Num(y)) => Some(Atom::Bool(x < y)),
+        Op::BinOp(BinaryOp::Eq, a1, a2) if exact(a1) && exact(a2) => Some(Atom::Bool(a1 == a2)),
+        _ => None,
+    };
+    folded.map_or(op, Op::Atom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{anf, compile::compile_with, sim, Options};
+
+    fn folded(source: &str) -> Anf {
+        fold(anf::lower(&crate::parse(source).unwrap()).unwrap())
+    }
+
+    /// What a fully folded program is left as
+    fn constant(anf: &Anf) -> Option<&Atom> {
+        match anf {
+            Anf::Value(Value::Op(Op::Atom(atom))) => Some(atom),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn folds_to_a_constant() {
+        let anf = folded("let x = 5, y = 3 in { x + y; x - y }");
+        assert_eq!(constant(&anf), Some(&Atom::Num(2)), "{}", anf);
+        let anf = folded("let x = 1 in if (isZero(sub1(x))) !(x == 2) else false");
+        assert_eq!(constant(&anf), Some(&Atom::Bool(true)), "{}", anf);
+    }
+
+    #[test]
+    fn constant_condition_drops_the_dead_branch() {
+        let anf = folded("if (1 < 2) 10 else 20");
+        assert_eq!(constant(&anf), Some(&Atom::Num(10)), "{}", anf);
+        // Only the branch taken is left, still computed at run time
+        let anf = folded("let y = true in if (5 < 3) add1(y) else sub1(y)");
+        assert!(
+            matches!(
+                &anf,
+                Anf::Value(Value::Op(Op::UnOp(UnaryOp::Sub1, Atom::Bool(true))))
+            ),
+            "{}",
+            anf
+        );
+    }
+
+    #[test]
+    fn unknown_condition_keeps_both_branches() {
+        let anf = folded("let x = 5 in if (isNum(x) == add1(true)) 1 else 2");
+        let mut tail = &anf;
+        while let Anf::Let(_, _, body) = tail {
+            tail = body;
+        }
+        assert!(
+            matches!(tail, Anf::Value(Value::If(Atom::Var(_), ..))),
+            "{}",
+            anf
+        );
+    }
+
+    #[test]
+    fn errors_stay_at_run_time() {
+        let anf = folded("add1(true)");
+        assert!(
+            matches!(
+                &anf,
+                Anf::Value(Value::Op(Op::UnOp(UnaryOp::Add1, Atom::Bool(true))))
+            ),
+            "{}",
+            anf
+        );
+        // Leaves the fixnum range, so the runtime promotes it or reports the overflow
+        let overflow = format!("{} + 1", NUM_MAX);
+        let anf = folded(&overflow);
+        assert!(constant(&anf).is_none(), "{}", anf);
+
+        let options = Options {
+            opt_level: 1,
+            overflow_checks: true,
+            ..Options::default()
+        };
+        for (source, error) in [("add1(true)", "Stuck"), (overflow.as_str(), "overflow")] {
+            let expr = crate::parse(source).unwrap();
+            let output = sim::simulate(&compile_with(expr, &options).unwrap()).unwrap();
+            assert_ne!(output.exit_code, 0, "{}", source);
+            assert!(
+                output.stdout.contains(error),
+                "{}: {}",
+                source,
+                output.stdout
+            );
+        }
+    }
+}
@@ -0,0 +1,612 @@
+use crate::ast::{level, BinaryOp, Expr};
+use crate::diagnostics::line_column;
+use crate::lexer::{Lexer, Tok};
+
+pub const DEFAULT_WIDTH: usize = 80;
+const INDENT: usize = 2;
+
+/// A layout that can be printed flat on one line or broken across lines
+#[derive(Clone, Debug)]
+enum Doc {
+    Text(String),
+    /// A space when flat, a newline when broken
+    Line,
+    /// Nothing when flat, a newline when broken
+    SoftLine,
+    /// Always a newline, so no enclosing group can be flat
+    HardLine,
+    Nest(usize, Box<Doc>),
+    /// Printed flat if the whole group fits in the remaining width
+    Group(Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+fn text(s: impl Into<String>) -> Doc {
+    Doc::Text(s.into())
+}
+
+fn nest(indent: usize, doc: Doc) -> Doc {
+    Doc::Nest(indent, Box::new(doc))
+}
+
+fn group(doc: Doc) -> Doc {
+    Doc::Group(Box::new(doc))
+}
+
+fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+/// `docs` separated by `separator` followed by `line`
+fn join(docs: Vec<Doc>, separator: &str, line: Doc) -> Doc {
+    let mut out = Vec::new();
+    for (i, doc) in docs.into_iter().enumerate() {
+        if i > 0 {
+            out.push(text(separator));
+            out.push(line.clone());
+        }
+        out.push(doc);
+    }
+    concat(out)
+}
+
+/// `name(a, b)`, with the arguments on their own indented lines if they do not fit
+fn application(name: String, args: Vec<Doc>) -> Doc {
+    if args.is_empty() {
+        return text(format!("{}()", name));
+    }
+    group(concat(vec![
+        text(format!("{}(", name)),
+        nest(
+            INDENT,
+            concat(vec![Doc::SoftLine, join(args, ",", Doc::Line)]),
+        ),
+        Doc::SoftLine,
+        text(")"),
+    ]))
+}
+
+/// The comments around the statements of one block, or around the whole program
+#[derive(Debug, Default)]
+struct Comments<'a> {
+    /// For each statement, the comments before it
+    leading: Vec<Vec<&'a str>>,
+    /// For each statement, the comments after it on its last line
+    trailing: Vec<Vec<&'a str>>,
+    /// Comments on lines of their own after the last statement
+    end: Vec<&'a str>,
+}
+
+impl<'a> Comments<'a> {
+    fn leading(&self, i: usize) -> &[&'a str] {
+        self.leading.get(i).map_or(&[], |c| c)
+    }
+
+    fn trailing(&self, i: usize) -> &[&'a str] {
+        self.trailing.get(i).map_or(&[], |c| c)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.leading
+            .iter()
+            .chain(&self.trailing)
+            .all(|c| c.is_empty())
+            && self.end.is_empty()
+    }
+
+    fn push_leading(&mut self, i: usize, comment: &'a str) {
+        self.leading
+            .resize(self.leading.len().max(i + 1), Vec::new());
+        self.leading[i].push(comment);
+    }
+
+    fn push_trailing(&mut self, i: usize, comment: &'a str) {
+        self.trailing
+            .resize(self.trailing.len().max(i + 1), Vec::new());
+        self.trailing[i].push(comment);
+    }
+}
+
+/// For each binding of a `let`, the comments after it on its last line
+type BindingComments<'a> = Vec<Vec<&'a str>>;
+
+/// A `let` being scanned
+struct OpenLet {
+    /// Index into the comments of every `let`
+    comments: usize,
+    binding: usize,
+    /// How many brackets enclose the `let`
+    depth: usize,
+    /// Whether its `in` has been reached
+    in_body: bool,
+}
+
+/// The comments `attach` found, for blocks and for `let` bindings, each in source order
+struct Attached<'a> {
+    blocks: Vec<Comments<'a>>,
+    lets: Vec<BindingComments<'a>>,
+}
+
+/// A block being scanned, or the whole program
+struct Open<'a> {
+    comments: usize,
+    statement: usize,
+    started: bool,
+    last_line: usize,
+    pending: Vec<(usize, &'a str)>,
+}
+
+/// Collects the comments of the program and of each block, blocks in the order their `{`
+/// appears. A comment on the line a `let` binding ends on, after the binding, its `,` or the
+/// `in` after the last binding, stays with that binding. Other comments inside a statement,
+/// outside any nested block, move before the statement.
+fn attach(source: &str) -> Attached<'_> {
+    let line = |offset| line_column(source, offset).0;
+    let mut all = vec![Comments::default()];
+    let mut lets: Vec<BindingComments> = Vec::new();
+    let mut open = vec![Open {
+        comments: 0,
+        statement: 0,
+        started: false,
+        last_line: 0,
+        pending: Vec::new(),
+    }];
+    let mut open_lets: Vec<OpenLet> = Vec::new();
+    let mut depth = 0;
+    // The line the last token ended on, and whether it was a `,` or `in`
+    let (mut previous_line, mut previous) = (0, None);
+    for (start, tok, end) in Lexer::with_comments(source).map_while(Result::ok) {
+        let closing = tok == Tok::RBrace && open.len() > 1;
+        let top = open.last_mut().unwrap();
+        if let Tok::Comment(text) = tok {
+            let text = text.trim_end();
+            let binding = match (open_lets.last(), previous) {
+                _ if line(start) != previous_line => None,
+                (Some(l), Some(Tok::In)) if l.in_body => Some((l.comments, l.binding)),
+                (Some(l), Some(Tok::Comma)) if !l.in_body => Some((l.comments, l.binding - 1)),
+                (Some(l), _) if !l.in_body => Some((l.comments, l.binding)),
+                _ => None,
+            };
+            match binding {
+                Some((l, i)) => {
+                    let bindings = &mut lets[l];
+                    bindings.resize(bindings.len().max(i + 1), Vec::new());
+                    bindings[i].push(text);
+                }
+                None => top.pending.push((line(start), text)),
+            }
+            continue;
+        }
+        let comments = &mut all[top.comments];
+        for (comment_line, text) in top.pending.drain(..) {
+            // The statement the comment would trail, if it shares that statement's last line
+            let before = if !top.started {
+                top.statement.checked_sub(1)
+            } else if closing {
+                Some(top.statement)
+            } else {
+                None
+            };
+            match before {
+                Some(i) if comment_line == top.last_line => comments.push_trailing(i, text),
+                _ if closing => comments.end.push(text),
+                _ => comments.push_leading(top.statement, text),
+            }
+        }
+        match tok {
+            Tok::Semicolon => {
+                top.statement += 1;
+                top.started = false;
+            }
+            _ if closing => {
+                open.pop();
+            }
+            _ => top.started = true,
+        }
+        let top = open.last_mut().unwrap();
+        top.last_line = line(end);
+        if tok == Tok::LBrace {
+            all.push(Comments::default());
+            open.push(Open {
+                comments: all.len() - 1,
+                statement: 0,
+                started: false,
+                last_line: line(end),
+                pending: Vec::new(),
+            });
+        }
+
+        if matches!(tok, Tok::RParen | Tok::RBrace) {
+            depth -= 1;
+        }
+        // Whatever ends a binding at the depth of a `let` also ends the bodies of the lets
+        // before it there
+        if matches!(
+            tok,
+            Tok::Comma | Tok::Semicolon | Tok::In | Tok::Else | Tok::RParen | Tok::RBrace
+        ) {
+            while let Some(l) = open_lets.last() {
+                if l.depth > depth || (l.depth == depth && l.in_body) {
+                    open_lets.pop();
+                } else {
+                    break;
+                }
+            }
+        }
+        match (tok, open_lets.last_mut()) {
+            (Tok::Let, _) => {
+                lets.push(Vec::new());
+                open_lets.push(OpenLet {
+                    comments: lets.len() - 1,
+                    binding: 0,
+                    depth,
+                    in_body: false,
+                });
+            }
+            (Tok::Comma, Some(l)) if l.depth == depth && !l.in_body => l.binding += 1,
+            (Tok::In, Some(l)) if l.depth == depth && !l.in_body => l.in_body = true,
+            _ => (),
+        }
+        if matches!(tok, Tok::LParen | Tok::LBrace) {
+            depth += 1;
+        }
+        previous_line = line(end);
+        previous = Some(tok);
+    }
+    let root = open.swap_remove(0);
+    for (comment_line, text) in root.pending {
+        if root.started && comment_line == root.last_line {
+            all[0].push_trailing(0, text);
+        } else {
+            all[0].end.push(text);
+        }
+    }
+    Attached { blocks: all, lets }
+}
+
+/// Builds the layout of a tree, pairing each block with the comments of the next `{` in the
+/// source, and each `let` with those of the next `let`; the grammar never reorders either
+struct Printer<'a> {
+    blocks: std::vec::IntoIter<Comments<'a>>,
+    lets: std::vec::IntoIter<BindingComments<'a>>,
+}
+
+fn is_block(expr: &Expr) -> bool {
+    matches!(expr, Expr::Do(exprs) if !exprs.is_empty())
+}
+
+/// Flattens a left-nested chain like `a + b - c` into its operands and operators
+fn chain<'a>(expr: &'a Expr, operands: &mut Vec<(Option<&'a BinaryOp>, &'a Expr)>) {
+    match expr {
+        Expr::BinOp(op @ (BinaryOp::Add | BinaryOp::Sub | BinaryOp::Eq | BinaryOp::Lt), l, r)
+            if level(l) == level(expr) =>
+        {
+            chain(l, operands);
+            operands.push((Some(op), r));
+        }
+        Expr::BinOp(op @ (BinaryOp::Add | BinaryOp::Sub | BinaryOp::Eq | BinaryOp::Lt), l, r) => {
+            operands.push((None, l));
+            operands.push((Some(op), r));
+        }
+        _ => operands.push((None, expr)),
+    }
+}
+
+impl Printer<'_> {
+    /// What follows `def f(x) =`, `else` and the like: a block stays on the same line,
+    /// anything else moves to an indented line of its own if it does not fit
+    fn body(&mut self, expr: &Expr) -> Doc {
+        if is_block(expr) {
+            concat(vec![text(" "), self.doc(expr)])
+        } else {
+            nest(INDENT, concat(vec![Doc::Line, self.doc(expr)]))
+        }
+    }
+
+    fn at_level(&mut self, expr: &Expr, required: u8) -> Doc {
+        if level(expr) < required {
+            concat(vec![text("("), self.doc(expr), text(")")])
+        } else {
+            self.doc(expr)
+        }
+    }
+
+    fn block(&mut self, exprs: &[Box<Expr>]) -> Doc {
+        let comments = self.blocks.next().unwrap_or_default();
+        if exprs.is_empty() && comments.is_empty() {
+            return text("{}");
+        }
+        // A block of several statements, or with comments, takes one line per statement
+        let line = if exprs.len() > 1 || !comments.is_empty() {
+            Doc::HardLine
+        } else {
+            Doc::Line
+        };
+        let mut statements = Vec::new();
+        for (i, e) in exprs.iter().enumerate() {
+            statements.push(line.clone());
+            for comment in comments.leading(i) {
+                statements.extend([text(*comment), Doc::HardLine]);
+            }
+            statements.push(self.doc(e));
+            if i + 1 < exprs.len() {
+                statements.push(text(";"));
+            }
+            for comment in comments.trailing(i) {
+                statements.push(text(format!(" {}", comment)));
+            }
+        }
+        for comment in &comments.end {
+            statements.extend([Doc::HardLine, text(*comment)]);
+        }
+        group(concat(vec![
+            text("{"),
+            nest(INDENT, concat(statements)),
+            line,
+            text("}"),
+        ]))
+    }
+
+    /// Children are laid out in source order, so blocks meet their comments in order
+    fn doc(&mut self, expr: &Expr) -> Doc {
+        match expr {
+            Expr::Num(_) | Expr::Float(_) | Expr::Id(_) | Expr::Bool(_) | Expr::Unit => {
+                text(expr.to_string())
+            }
+            Expr::UnOp(op, e) => application(op.to_string(), vec![self.doc(e)]),
+            Expr::BinOp(op @ (BinaryOp::Pair | BinaryOp::Vector | BinaryOp::VectorGet), e1, e2) => {
+                let args = vec![self.doc(e1), self.doc(e2)];
+                application(op.to_string(), args)
+            }
+            Expr::BinOp(..) => {
+                let l = level(expr);
+                let mut operands = Vec::new();
+                chain(expr, &mut operands);
+                let mut parts = Vec::new();
+                for (op, e) in operands {
+                    match op {
+                        None => parts.push(self.at_level(e, l)),
+                        Some(op) => {
+                            parts.push(text(format!(" {}", op)));
+                            parts.push(Doc::Line);
+                            parts.push(self.at_level(e, l + 1));
+                        }
+                    }
+                }
+                let first = parts.remove(0);
+                group(concat(vec![first, nest(INDENT, concat(parts))]))
+            }
+            Expr::TriOp(op, e1, e2, e3) => {
+                let args = vec![self.doc(e1), self.doc(e2), self.doc(e3)];
+                application(op.to_string(), args)
+            }
+            Expr::If(cond, then_branch, else_branch) => {
+                let cond = self.doc(cond);
+                let then_doc = self.body(then_branch);
+                let before_else = if is_block(then_branch) {
+                    text(" ")
+                } else {
+                    Doc::Line
+                };
+                // `else if` chains stay flat instead of drifting right
+                let else_doc = match else_branch.as_ref() {
+                    Expr::If(..) => concat(vec![text(" "), self.doc(else_branch)]),
+                    _ => self.body(else_branch),
+                };
+                group(concat(vec![
+                    text("if ("),
+                    cond,
+                    text(")"),
+                    then_doc,
+                    before_else,
+                    text("else"),
+                    else_doc,
+                ]))
+            }
+            Expr::Let(bindings, let_body) => {
+                let comments = self.lets.next().unwrap_or_default();
+                // A comment runs to the end of its line, so one forces every binding onto its own
+                let line = if comments.iter().all(|c| c.is_empty()) {
+                    Doc::Line
+                } else {
+                    Doc::HardLine
+                };
+                let mut parts = Vec::new();
+                for (i, (var, e)) in bindings.iter().enumerate() {
+                    if i > 0 {
+                        parts.push(line.clone());
+                    }
+                    parts.push(group(concat(vec![
+                        text(format!("{} =", var)),
+                        self.body(e),
+                    ])));
+                    parts.push(text(if i + 1 < bindings.len() { "," } else { " in" }));
+                    for comment in comments.get(i).into_iter().flatten() {
+                        parts.push(text(format!(" {}", comment)));
+                    }
+                }
+                let head = group(concat(vec![text("let "), nest(4, concat(parts))]));
+                group(concat(vec![head, line, self.doc(let_body)]))
+            }
+            Expr::Do(exprs) => self.block(exprs),
+            Expr::FuncDef(name, params, def_body) => group(concat(vec![
+                text(format!("def {}({}) =", name, params.join(", "))),
+                self.body(def_body),
+            ])),
+            Expr::FuncCall(callee, args) => {
+                let callee = match callee.as_ref() {
+                    Expr::Id(name) => name.clone(),
+                    other => format!("({})", other),
+                };
+                application(callee, args.iter().map(|e| self.doc(e)).collect())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Whether `doc` printed flat, followed by `rest` up to its next line break, fits in `width`
+fn fits(mut width: isize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut stack = vec![(Mode::Flat, doc)];
+    let mut rest = rest.iter().rev();
+    loop {
+        let (mode, doc) = match stack.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some((_, mode, doc)) => (*mode, *doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(s) => width -= s.chars().count() as isize,
+            Doc::HardLine => return mode == Mode::Break,
+            Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+            Doc::Line => width -= 1,
+            Doc::SoftLine => (),
+            Doc::Nest(_, d) | Doc::Group(d) => stack.push((mode, d)),
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|d| (mode, d))),
+        }
+        if width < 0 {
+            return false;
+        }
+    }
+}
+
+fn render(doc: &Doc, width: usize) -> String {
+    let mut out = String::new();
+    let mut column = 0;
+    let mut stack = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(s) => {
+                out.push_str(s);
+                column += s.chars().count();
+            }
+            Doc::Line | Doc::SoftLine if mode == Mode::Flat => {
+                if matches!(doc, Doc::Line) {
+                    out.push(' ');
+                    column += 1;
+                }
+            }
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                out.push('\n');
+                out.push_str(&" ".repeat(indent));
+                column = indent;
+            }
+            Doc::Nest(extra, d) => stack.push((indent + extra, mode, d)),
+            Doc::Group(d) => {
+                // A group inside a flat group is flat too
+                let remaining = width as isize - column as isize;
+                let mode = if mode == Mode::Flat || fits(remaining, d, &stack) {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                stack.push((indent, mode, d));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|d| (indent, mode, d))),
+        }
+    }
+    out
+}
+
+/// Pretty-prints `expr`, parsed from `source`, in the canonical layout, breaking lines longer
+/// than `width` and keeping the comments of `source`
+pub fn format(source: &str, expr: &Expr, width: usize) -> String {
+    let attached = attach(source);
+    let mut blocks = attached.blocks.into_iter();
+    let program = blocks.next().unwrap_or_default();
+    let mut printer = Printer {
+        blocks,
+        lets: attached.lets.into_iter(),
+    };
+    let mut parts = Vec::new();
+    for comment in program.leading(0) {
+        parts.extend([text(*comment), Doc::HardLine]);
+    }
+    parts.push(printer.doc(expr));
+    for comment in program.trailing(0) {
+        parts.push(text(format!(" {}", comment)));
+    }
+    for comment in &program.end {
+        parts.extend([Doc::HardLine, text(*comment)]);
+    }
+    format!("{}\n", render(&concat(parts), width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator;
+
+    fn fmt(source: &str) -> String {
+        format(source, &crate::parse(source).unwrap(), DEFAULT_WIDTH)
+    }
+
+    /// Formats `source` and checks that the result parses to the same tree and formats to itself
+    fn round_trip(source: &str) {
+        let expr = crate::parse(source).unwrap();
+        let formatted = format(source, &expr, DEFAULT_WIDTH);
+        let reparsed = crate::parse(&formatted)
+            .unwrap_or_else(|_| panic!("formatted program does not parse:\n{}", formatted));
+        assert_eq!(
+            format!("{:?}", reparsed),
+            format!("{:?}", expr),
+            "formatting changed the program:\n{}",
+            formatted
+        );
+        assert_eq!(fmt(&formatted), formatted, "formatting is not idempotent");
+    }
+
+    #[test]
+    fn samples_round_trip() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|e| e == "lisp") {
+                round_trip(&std::fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn generated_programs_round_trip() {
+        for seed in 0..300 {
+            for depth in [3, 6] {
+                round_trip(&generator::program(seed, depth, seed % 2 == 0).to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn narrow_width_round_trips() {
+        for seed in 0..100 {
+            let source = generator::program(seed, 6, true).to_string();
+            let expr = crate::parse(&source).unwrap();
+            let formatted = format(&source, &expr, 20);
+            assert_eq!(format(&formatted, &expr, 20), formatted);
+        }
+    }
+
+    #[test]
+    fn trailing_comments_stay_with_their_binding() {
+        let source = "let x = 5, // why\n    y = 3 // because\nin x + y\n";
+        assert_eq!(
+            fmt(source),
+            "let x = 5, // why\n    y = 3 in // because\nx + y\n"
+        );
+        round_trip(source);
+    }
+
+    #[test]
+    fn comments_in_blocks() {
+        let source = "// program\n{\n  1; // one\n  // two next\n  2\n  // end\n}\n";
+        assert_eq!(fmt(source), source);
+        round_trip(source);
+    }
+}
@@ -0,0 +1,165 @@
+use std::panic;
+
+use crate::compile::compile;
+use crate::generator::{self, Rng};
+
+/// Tokens spliced into mutated inputs, so the parser sees plausible syntax as well as noise
+const TOKENS: &[&str] = &[
+    "let",
+    "in",
+    "if",
+    "else",
+    "(",
+    ")",
+    "{",
+    "}",
+    ";",
+    ",",
+    "=",
+    "==",
+    "<",
+    "+",
+    "-",
+    "add1",
+    "isZero",
+    "vector",
+    "vectorGet",
+    "vectorSet",
+    "true",
+    "x",
+    "99999999999999999999",
+    "-0",
+    "0x",
+    "0b",
+    "_",
+    "2305843009213693952",
+    ".",
+    "e-",
+    "1.5e308",
+    "floor",
+    "//",
+    "/*",
+    "*/",
+    "\n",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub enum Target {
+    Parser,
+    Compile,
+}
+
+/// An input that made a target panic
+#[derive(Debug)]
+pub struct Crash {
+    pub input: Vec<u8>,
+    pub message: String,
+}
+
+/// Fuzz entry point for the lexer and `ExprParser`: any input must either parse or be rejected, never panic
+pub fn fuzz_parser(data: &[u8]) {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = crate::parse(source);
+    }
+}
+
+/// Fuzz entry point for `compile::compile`: the input seeds a generated program, which must
+/// compile, and print back to source that parses to the same tree
+pub fn fuzz_compile(data: &[u8]) {
+    let seed = data.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x100_0000_01b3)
+    });
+    let well_typed = data.first().is_none_or(|b| b & 1 == 0);
+    let program = generator::compilable_program(seed, 5, well_typed);
+
+    let source = program.to_string();
+    let reparsed = crate::parse(&source).unwrap_or_else(|d| {
+        panic!(
+            "printed program does not parse:\n{}",
+            d.render("<generated>", &source, false)
+        )
+    });
+    assert_eq!(
+        format!("{:?}", reparsed),
+        format!("{:?}", program),
+        "printing changed the program: {}",
+        source
+    );
+    if let Err(e) = compile(program) {
+        panic!("generated program does not compile: {}\n{}", e, source);
+    }
+}
+
+/// Applies a few random edits: splicing tokens or digits, deleting and duplicating ranges
+fn mutate(rng: &mut Rng, input: &mut Vec<u8>) {
+    for _ in 0..1 + rng.below(4) {
+        let at = rng.below(input.len() as u64 + 1) as usize;
+        match rng.below(4) {
+            0 => {
+                let token = TOKENS[rng.below(TOKENS.len() as u64) as usize];
+                input.splice(at..at, format!(" {} ", token).bytes());
+            }
+            1 => {
+                let digits: String = (0..1 + rng.below(24))
+                    .map(|_| (b'0' + rng.below(10) as u8) as char)
+                    .collect();
+                input.splice(at..at, digits.bytes());
+            }
+            2 if !input.is_empty() => {
+                let end = (at + 1 + rng.below(8) as usize).min(input.len());
+                input.drain(at.min(end)..end);
+            }
+            _ => {
+                let end = (at + rng.below(16) as usize).min(input.len());
+                let chunk = input[at.min(end)..end].to_vec();
+                input.splice(at..at, chunk);
+            }
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => "panic with a non-string payload".to_string(),
+        },
+    }
+}
+
+/// Feeds `iterations` random inputs to `target`, returning the first one that panics
+pub fn run(target: Target, iterations: u64, seed: u64) -> Option<Crash> {
+    let mut rng = Rng::new(seed);
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut crash = None;
+    for i in 0..iterations {
+        let input = match target {
+            Target::Parser => {
+                let well_typed = rng.chance(2);
+                let mut bytes = generator::program(seed.wrapping_add(i), 4, well_typed)
+                    .to_string()
+                    .into_bytes();
+                mutate(&mut rng, &mut bytes);
+                bytes
+            }
+            Target::Compile => rng.next_u64().to_le_bytes().to_vec(),
+        };
+        let result = panic::catch_unwind(|| match target {
+            Target::Parser => fuzz_parser(&input),
+            Target::Compile => fuzz_compile(&input),
+        });
+        if let Err(payload) = result {
+            crash = Some(Crash {
+                input,
+                message: panic_message(payload),
+            });
+            break;
+        }
+    }
+
+    panic::set_hook(hook);
+    crash
+}
@@ -0,0 +1,258 @@
+use crate::ast::{BinaryOp, Expr, TernaryOp, UnaryOp};
+use crate::compile::NUM_SHIFT;
+
+/// xorshift64*, enough randomness for generating test programs without a dependency
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    pub fn chance(&mut self, one_in: u64) -> bool {
+        self.below(one_in) == 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Ty {
+    Num,
+    Bool,
+    /// A vector of numbers with a length known at generation time
+    Vector(i64),
+}
+
+struct Gen {
+    rng: Rng,
+    scope: Vec<(String, Ty)>,
+    /// How many operands may still be given a value of the wrong type
+    mistakes: u32,
+    /// Whether to use vectors and the predicates on heap values, which the compiler lacks
+    vectors: bool,
+    fresh: usize,
+}
+
+fn b(e: Expr) -> Box<Expr> {
+    Box::new(e)
+}
+
+impl Gen {
+    fn fresh_name(&mut self) -> String {
+        self.fresh += 1;
+        format!("v{}", self.fresh)
+    }
+
+    fn variable(&mut self, ty: Ty) -> Option<Expr> {
+        let candidates: Vec<&String> = self
+            .scope
+            .iter()
+            .filter(|(_, t)| *t == ty)
+            .map(|(name, _)| name)
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let i = self.rng.below(candidates.len() as u64) as usize;
+        Some(Expr::Id(candidates[i].clone()))
+    }
+
+    fn literal(&mut self, ty: Ty) -> Expr {
+        match ty {
+            Ty::Num if self.rng.chance(20) => {
+                Expr::Num(self.rng.next_u64() as i64 >> (NUM_SHIFT as u64 + self.rng.below(62)))
+            }
+            Ty::Num if self.rng.chance(10) => Expr::Float((self.rng.below(41) as f64 - 20.0) / 4.0),
+            Ty::Num => Expr::Num(self.rng.below(21) as i64 - 10),
+            Ty::Bool => Expr::Bool(self.rng.chance(2)),
+            Ty::Vector(n) => {
+                Expr::BinOp(BinaryOp::Vector, b(Expr::Num(n)), b(self.literal(Ty::Num)))
+            }
+        }
+    }
+
+    /// An operand that should have type `ty`, unless a deliberate type error is spent here
+    fn operand(&mut self, ty: Ty, depth: u32) -> Expr {
+        if self.mistakes > 0 && self.rng.chance(6) {
+            self.mistakes -= 1;
+            let wrong = match ty {
+                Ty::Num if !self.vectors => Ty::Bool,
+                Ty::Num => [Ty::Bool, Ty::Vector(2)][self.rng.below(2) as usize],
+                Ty::Bool | Ty::Vector(_) => Ty::Num,
+            };
+            return self.expr(wrong, depth);
+        }
+        self.expr(ty, depth)
+    }
+
+    fn any_ty(&mut self) -> Ty {
+        let kinds = if self.vectors { 3 } else { 2 };
+        match self.rng.below(kinds) {
+            0 => Ty::Num,
+            1 => Ty::Bool,
+            _ => Ty::Vector(1 + self.rng.below(3) as i64),
+        }
+    }
+
+    fn expr(&mut self, ty: Ty, depth: u32) -> Expr {
+        if depth == 0 || self.rng.chance(5) {
+            return match self.variable(ty) {
+                Some(v) if self.rng.chance(2) => v,
+                _ => self.literal(ty),
+            };
+        }
+        let d = depth - 1;
+        match self.rng.below(5) {
+            0 => self.let_expr(ty, d),
+            1 => Expr::If(
+                b(self.operand(Ty::Bool, d)),
+                b(self.expr(ty, d)),
+                b(self.expr(ty, d)),
+            ),
+            2 => {
+                let first_ty = self.any_ty();
+                Expr::Do(vec![b(self.expr(first_ty, d)), b(self.expr(ty, d))])
+            }
+            _ => self.primitive(ty, d),
+        }
+    }
+
+    fn let_expr(&mut self, ty: Ty, depth: u32) -> Expr {
+        let saved = self.scope.len();
+        let mut bindings = Vec::new();
+        for _ in 0..1 + self.rng.below(3) {
+            let bound_ty = self.any_ty();
+            let e = self.expr(bound_ty, depth);
+            let name = self.fresh_name();
+            self.scope.push((name.clone(), bound_ty));
+            bindings.push((name, b(e)));
+        }
+        let body = self.expr(ty, depth);
+        self.scope.truncate(saved);
+        Expr::Let(bindings, b(body))
+    }
+
+    fn primitive(&mut self, ty: Ty, d: u32) -> Expr {
+        match ty {
+            Ty::Num => match self.rng.below(if self.vectors { 5 } else { 3 }) {
+                0 => {
+                    let op = [
+                        UnaryOp::Add1,
+                        UnaryOp::Sub1,
+                        UnaryOp::Floor,
+                        UnaryOp::Round,
+                        UnaryOp::ToFloat,
+                    ][self.rng.below(5) as usize]
+                        .clone();
+                    Expr::UnOp(op, b(self.operand(Ty::Num, d)))
+                }
+                1 | 2 => {
+                    let op = [BinaryOp::Add, BinaryOp::Sub][self.rng.below(2) as usize].clone();
+                    Expr::BinOp(op, b(self.operand(Ty::Num, d)), b(self.operand(Ty::Num, d)))
+                }
+                3 => {
+                    let n = 1 + self.rng.below(3) as i64;
+                    Expr::UnOp(UnaryOp::VectorLength, b(self.operand(Ty::Vector(n), d)))
+                }
+                _ => {
+                    let n = 1 + self.rng.below(3) as i64;
+                    let index = Expr::Num(self.rng.below(n as u64) as i64);
+                    Expr::BinOp(
+                        BinaryOp::VectorGet,
+                        b(self.operand(Ty::Vector(n), d)),
+                        b(index),
+                    )
+                }
+            },
+            Ty::Bool => match self.rng.below(5) {
+                0 => Expr::UnOp(UnaryOp::IsZero, b(self.operand(Ty::Num, d))),
+                1 => {
+                    let predicates = if self.vectors { 3 } else { 1 };
+                    let op = [UnaryOp::IsNum, UnaryOp::IsVector, UnaryOp::IsPair]
+                        [self.rng.below(predicates) as usize]
+                        .clone();
+                    let any = self.any_ty();
+                    Expr::UnOp(op, b(self.expr(any, d)))
+                }
+                2 => Expr::UnOp(UnaryOp::Not, b(self.operand(Ty::Bool, d))),
+                _ => {
+                    let op = [BinaryOp::Eq, BinaryOp::Lt][self.rng.below(2) as usize].clone();
+                    Expr::BinOp(op, b(self.operand(Ty::Num, d)), b(self.operand(Ty::Num, d)))
+                }
+            },
+            Ty::Vector(n) => match self.rng.below(2) {
+                0 => Expr::BinOp(
+                    BinaryOp::Vector,
+                    b(Expr::Num(n)),
+                    b(self.operand(Ty::Num, d)),
+                ),
+                _ => {
+                    let index = Expr::Num(self.rng.below(n as u64) as i64);
+                    Expr::TriOp(
+                        TernaryOp::VectorSet,
+                        b(self.operand(Ty::Vector(n), d)),
+                        b(index),
+                        b(self.operand(Ty::Num, d)),
+                    )
+                }
+            },
+        }
+    }
+}
+
+/// Generates a random program; with `well_typed` false, a few operands get values of the wrong type
+pub fn program(seed: u64, depth: u32, well_typed: bool) -> Expr {
+    generate(seed, depth, well_typed, true)
+}
+
+/// Generates a random program like `program`, but only from what the compiler supports: no
+/// vectors, `isVector` or `isPair`
+pub fn compilable_program(seed: u64, depth: u32, well_typed: bool) -> Expr {
+    generate(seed, depth, well_typed, false)
+}
+
+fn generate(seed: u64, depth: u32, well_typed: bool, vectors: bool) -> Expr {
+    let mut rng = Rng::new(seed);
+    let ty = match rng.below(if vectors { 3 } else { 2 }) {
+        0 => Ty::Num,
+        1 => Ty::Bool,
+        _ => Ty::Vector(2),
+    };
+    let mut generator = Gen {
+        rng,
+        scope: Vec::new(),
+        mistakes: if well_typed { 0 } else { 2 },
+        vectors,
+        fresh: 0,
+    };
+    generator.expr(ty, depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::compile;
+
+    #[test]
+    fn compilable_programs_compile() {
+        for seed in 0..300 {
+            for well_typed in [true, false] {
+                let program = compilable_program(seed, 5, well_typed);
+                if let Err(e) = compile(program.clone()) {
+                    panic!("{} does not compile: {}", program, e);
+                }
+            }
+        }
+    }
+}
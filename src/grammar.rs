@@ -1,7 +1,7 @@
-// auto-generated: "lalrpop 0.22.0"
-// sha3: 911378f90c035baa1fc0a96f7fdaec97f4bae29b8f458a8f9b6c2fc156ae3f09
-use std::str::FromStr;
+// auto-generated: "lalrpop 0.22.2"
+// sha3: d2b1fca99bf06a8974da97bd1fff1f0656d966e51a4b6b01d0d26937487105be
 use crate::ast::{Expr, BinaryOp, UnaryOp, TernaryOp};
+use crate::lexer::{LexicalError, Tok};
 #[allow(unused_extern_crates)]
 extern crate lalrpop_util as __lalrpop_util;
 #[allow(unused_imports)]
@@ -10,195 +10,269 @@ use self::__lalrpop_util::state_machine as __state_machine;
 extern crate alloc;
 
 #[rustfmt::skip]
-#[allow(explicit_outlives_requirements, non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens, clippy::needless_lifetimes, clippy::type_complexity, clippy::needless_return, clippy::too_many_arguments, clippy::never_loop, clippy::match_single_binding, clippy::needless_raw_string_hashes)]
+#[allow(explicit_outlives_requirements, non_snake_case, non_camel_case_types, unused_mut, unused_variables, unused_imports, unused_parens, clippy::needless_lifetimes, clippy::type_complexity, clippy::needless_return, clippy::too_many_arguments, clippy::match_single_binding)]
 mod __parse__Expr {
 
-    use std::str::FromStr;
     use crate::ast::{Expr, BinaryOp, UnaryOp, TernaryOp};
+    use crate::lexer::{LexicalError, Tok};
     #[allow(unused_extern_crates)]
     extern crate lalrpop_util as __lalrpop_util;
     #[allow(unused_imports)]
     use self::__lalrpop_util::state_machine as __state_machine;
     #[allow(unused_extern_crates)]
     extern crate alloc;
-    use self::__lalrpop_util::lexer::Token;
+    use super::__ToTriple;
     #[allow(dead_code)]
     pub(crate) enum __Symbol<'input>
      {
-        Variant0(&'input str),
-        Variant1(Box<Expr>),
-        Variant2(alloc::vec::Vec<Box<Expr>>),
-        Variant3(BinaryOp),
-        Variant4((String, Box<Expr>)),
-        Variant5(Vec<(String, Box<Expr>)>),
-        Variant6(Option<Box<Expr>>),
-        Variant7(String),
-        Variant8(i64),
-        Variant9(TernaryOp),
-        Variant10(UnaryOp),
+        Variant0(Tok<'input>),
+        Variant1(i64),
+        Variant2(f64),
+        Variant3(&'input str),
+        Variant4(Box<Expr>),
+        Variant5(alloc::vec::Vec<Box<Expr>>),
+        Variant6(String),
+        Variant7(alloc::vec::Vec<String>),
+        Variant8(BinaryOp),
+        Variant9((String, Box<Expr>)),
+        Variant10(Vec<(String, Box<Expr>)>),
+        Variant11(Vec<Box<Expr>>),
+        Variant12(Vec<String>),
+        Variant13(Option<Box<Expr>>),
+        Variant14(Option<String>),
+        Variant15(TernaryOp),
+        Variant16(UnaryOp),
     }
     const __ACTION: &[i8] = &[
         // State 0
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 1
-        0, 0, 0, 0, -15, 0, -15, 0, -15, 46, 0, 47, 0, -15, 0, 0, -15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -15,
+        0, -38, 0, -38, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -38, 0, -38, -38, -38, 0, 64, 63, 0, 0, 0,
         // State 2
-        0, 0, 0, 0, -9, 48, -9, 49, -9, -9, 0, -9, 0, -9, 0, 0, -9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -9,
+        0, -22, 0, -22, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -22, 0, -22, -22, -22, 0, -22, -22, 65, 66, 0,
         // State 3
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 4
-        0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 5
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 55,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 6
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 0, 0, 33, 34, 35, 36, 37, 38, 0, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 73, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 7
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 0, 0, 33, 34, 35, 36, 37, 38, 0, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 8
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        0, 0, 0, 0, 0, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 9
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, -27, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 10
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        0, 0, 0, 0, 0, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 11
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 62,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 12
-        0, 0, 0, 0, -8, 48, -8, 49, -8, -8, 0, -8, 0, -8, 0, 0, -8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -8,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 13
-        0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 14
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 83, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 15
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        0, -21, 0, -21, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -21, 0, -21, -21, -21, 0, -21, -21, 65, 66, 0,
         // State 16
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, -29, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 17
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 44, 0, -31, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 18
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 19
-        44, 45, 29, 4, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 31, 32, 0, 33, 34, 35, 36, 37, 38, 5, 39, 40, 41, 42, 43, 6, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 20
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 21
-        0, 0, 0, 0, -34, -34, -34, -34, -34, -34, 0, -34, 0, -34, 0, 0, -34, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -34,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 22
-        0, 0, 0, 0, -21, -21, -21, -21, -21, -21, 0, -21, 0, -21, 0, 0, -21, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -21,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 23
-        0, 0, 0, 0, -14, 0, -14, 0, -14, 0, 0, 0, 0, -14, 0, 0, -14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -14,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 44, 0, -33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 24
-        0, 0, 0, 0, -13, 0, -13, 0, -13, 0, 0, 0, 0, -13, 0, 0, -13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -13,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 25
-        0, 0, 0, 0, -18, -18, -18, -18, -18, -18, 0, -18, 0, -18, 0, 0, -18, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -18,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 26
-        0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 27
-        0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        6, 0, 45, 0, 5, 58, 41, 40, 56, 50, 47, 48, 51, 54, 46, 49, 61, 53, 59, 60, 62, 43, 55, 57, 52, 42, 44, 4, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 39,
         // State 28
-        0, 0, 0, -42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 29
-        0, 0, 0, -38, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -37, 0, -37, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -37, 0, -37, -37, -37, 0, 0, 0, 0, 0, 0,
         // State 30
-        0, 0, 0, 0, -20, -20, -20, -20, -20, -20, 0, -20, 0, -20, 0, 0, -20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -20,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 31
-        0, 0, 0, 11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -62, 0, -62, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -62, 0, -62, -62, -62, 0, -62, -62, -62, -62, 0,
         // State 32
-        0, 0, 0, -46, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -45, 0, -45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, -45, 0, -45, -45, -45, 0, -45, -45, -45, -45, 0,
         // State 33
-        0, 0, 0, -41, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -36, 0, -36, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -36, 0, -36, -36, -36, 0, 0, 0, 0, 0, 0,
         // State 34
-        0, 0, 0, -43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -35, 0, -35, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -35, 0, -35, -35, -35, 0, 0, 0, 0, 0, 0,
         // State 35
-        0, 0, 0, -47, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -41, 0, -41, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -41, 0, -41, -41, -41, 0, -41, -41, -41, -41, 0,
         // State 36
-        0, 0, 0, -40, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 37
-        0, 0, 0, -44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 38
-        0, 0, 0, -45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -70, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 39
-        0, 0, 0, -39, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -66, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 40
-        0, 0, 0, 0, -19, -19, -19, -19, -19, -19, 0, -19, 0, -19, 0, 0, -19, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -19,
+        0, -44, 0, -44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -44, 0, -44, -44, -44, 0, -44, -44, -44, -44, 0,
         // State 41
-        0, 0, 0, -48, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -42, 0, -42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -42, 0, -42, -42, -42, 0, -42, -42, -42, -42, 0,
         // State 42
-        0, 0, 0, -37, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -77, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 43
-        0, 0, 0, 0, -32, -32, -32, -32, -32, -32, 0, -32, 0, -32, 0, 0, -32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -32,
+        0, -55, 0, -55, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -55, -55, 0, -55, -55, -55, -55, -55, -55, -55, -55, 0,
         // State 44
-        0, 0, 0, 0, -29, -29, -29, -29, -29, -29, -29, -29, 0, -29, 0, 0, -29, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -29,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 45
-        -7, -7, -7, -7, 0, 0, 0, 0, 0, 0, 0, 0, -7, 0, -7, 0, 0, -7, -7, -7, -7, -7, -7, 0, -7, -7, -7, -7, -7, -7, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -74, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 46
-        -6, -6, -6, -6, 0, 0, 0, 0, 0, 0, 0, 0, -6, 0, -6, 0, 0, -6, -6, -6, -6, -6, -6, 0, -6, -6, -6, -6, -6, -6, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -69, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 47
-        -35, -35, -35, -35, 0, 0, 0, 0, 0, 0, 0, 0, -35, 0, -35, 0, 0, -35, -35, -35, -35, -35, -35, 0, -35, -35, -35, -35, -35, -35, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -71, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 48
-        -36, -36, -36, -36, 0, 0, 0, 0, 0, 0, 0, 0, -36, 0, -36, 0, 0, -36, -36, -36, -36, -36, -36, 0, -36, -36, -36, -36, -36, -36, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -75, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 49
-        0, 0, 0, 0, 59, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -68, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 50
-        0, 0, 0, 0, 0, 0, -11, 0, 0, 0, 0, 0, 0, 0, 0, 0, -11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -72, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 51
-        0, 0, 0, 0, 0, 0, 14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -60, 0, -60, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -60, 0, -60, -60, -60, 0, -60, -60, -60, -60, 0,
         // State 52
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -18, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 53
-        0, 0, 0, 0, 0, 0, 0, 0, 63, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -73, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 54
-        0, 0, 0, 0, -24, -24, -24, -24, -24, -24, 0, -24, 0, -24, 0, 0, -24, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -24,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -78, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 55
-        0, 0, 0, 0, -33, -33, -33, -33, -33, -33, 0, -33, 0, -33, 0, 0, -33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -33,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -67, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 56
-        0, 0, 0, 0, 0, 0, 17, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -79, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 57
-        0, 0, 0, 0, 65, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -43, 0, -43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -43, 0, -43, -43, -43, 0, -43, -43, -43, -43, 0,
         // State 58
-        0, 0, 0, 0, -22, -22, -22, -22, -22, -22, 0, -22, 0, -22, 0, 0, -22, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -22,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -19, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 59
-        0, 0, 0, 0, 18, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 60
-        0, 0, 0, 0, 0, 0, 0, 0, 69, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 70,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -76, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 61
-        0, 0, 0, 0, -26, -26, -26, -26, -26, -26, 0, -26, 0, -26, 0, 0, -26, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -26,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -65, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 62
-        -4, -4, -4, -4, 0, 0, 0, 0, 0, 0, 0, 0, -4, 0, -4, -4, 0, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,
+        0, 0, 0, 0, 0, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, -17, 0, -17, 0, 0, 0, 0, 0, 0, 0, 0, -17,
         // State 63
-        0, 0, 0, 0, -23, -23, -23, -23, -23, -23, 0, -23, 0, -23, 0, 0, -23, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -23,
+        0, 0, 0, 0, 0, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, -16, 0, -16, 0, 0, 0, 0, 0, 0, 0, 0, -16,
         // State 64
-        0, 0, 0, 0, -27, -27, -27, -27, -27, -27, 0, -27, 0, -27, 0, 0, -27, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -27,
+        0, 0, 0, 0, 0, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, -63, 0, -63, 0, 0, 0, 0, 0, 0, 0, 0, -63,
         // State 65
-        0, 0, 0, 0, 0, 0, -12, 0, 0, 0, 0, 0, 0, 0, 0, 0, -12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, -64, 0, -64, 0, 0, 0, 0, 0, 0, 0, 0, -64,
         // State 66
-        0, 0, 0, 0, -31, 0, -31, 0, -31, 0, 0, 0, 0, -31, 0, 0, -31, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -31,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 67
-        0, 0, 0, 0, 0, 0, -10, 0, 0, 0, 0, 0, 0, 0, 0, 0, -10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 18, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         // State 68
-        -5, -5, -5, -5, 0, 0, 0, 0, 0, 0, 0, 0, -5, 0, -5, -5, 0, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5,
+        0, -24, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -24, 0, 0, 0, 0, 0, 0, 0,
         // State 69
-        0, 0, 0, 0, -25, -25, -25, -25, -25, -25, 0, -25, 0, -25, 0, 0, -25, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -25,
+        0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 19, 0, 0, 0, 0, 0, 0, 0,
         // State 70
-        0, 0, 0, 0, 0, 0, 19, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 21, 0, 0, 0, 0, 0,
         // State 71
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 85, 0, 84, 0, 0, 0, 0, 0, 0,
         // State 72
-        0, 0, 0, 0, 75, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, -49, 0, -49, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -49, 0, -49, -49, -49, 0, -49, -49, -49, -49, 0,
         // State 73
-        0, 0, 0, 0, -30, 0, -30, 0, -30, 0, 0, 0, 0, -30, 0, 0, -30, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -30,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 22, 0, 0, 0, 0, 0, 0, 0,
         // State 74
-        0, 0, 0, 0, -28, -28, -28, -28, -28, -28, 0, -28, 0, -28, 0, 0, -28, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -28,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 87, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 75
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -26, 0, 0, 88, 0, 0, 0, 0, 0, 0, 0,
+        // State 76
+        0, -61, 0, -61, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -61, 0, -61, -61, -61, 0, -61, -61, -61, -61, 0,
+        // State 77
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 23, 0, 0, 0, 0, 0, 0, 0,
+        // State 78
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 89, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 79
+        0, -47, 0, -47, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -47, 0, -47, -47, -47, 0, -47, -47, -47, -47, 0,
+        // State 80
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 25, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 81
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 96, 0, 95, 0, 0, 0, 0, 0, 0,
+        // State 82
+        0, -51, 0, -51, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -51, 0, -51, -51, -51, 0, -51, -51, -51, -51, 0,
+        // State 83
+        -9, 0, -9, 0, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, -9, 0, -9, -9, 0, 0, 0, 0, 0, 0, 0, -9,
+        // State 84
+        0, -48, 0, -48, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -48, 0, -48, -48, -48, 0, -48, -48, -48, -48, 0,
+        // State 85
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -28, 0, 0, 98, 0, 0, 0, 0, 0, 0, 0,
+        // State 86
+        0, -46, 0, -46, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -46, 0, -46, -46, -46, 0, -46, -46, -46, -46, 0,
+        // State 87
+        -4, 0, -4, 0, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, 0, 0, 0, 0, 0, 0, 0, 0, -4,
+        // State 88
+        0, -52, 0, -52, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -52, 0, -52, -52, -52, 0, -52, -52, -52, -52, 0,
+        // State 89
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 101, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 90
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -30, 0, 0, 102, 0, 0, 0, 0, 0, 0, 0,
+        // State 91
+        0, -25, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -25, 0, 0, 0, 0, 0, 0, 0,
+        // State 92
+        0, -59, 0, -59, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -59, 0, -59, -59, -59, 0, 0, 0, 0, 0, 0,
+        // State 93
+        0, -23, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -23, 0, 0, 0, 0, 0, 0, 0,
+        // State 94
+        -10, 0, -10, 0, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, -10, 0, -10, -10, 0, 0, 0, 0, 0, 0, 0, -10,
+        // State 95
+        0, -50, 0, -50, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -50, 0, -50, -50, -50, 0, -50, -50, -50, -50, 0,
+        // State 96
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 104, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 97
+        -5, 0, -5, 0, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, -5, 0, 0, 0, 0, 0, 0, 0, 0, -5,
+        // State 98
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 26, 0, 0, 0, 0, 0, 0, 0,
+        // State 99
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -32, 0, 0, 105, 0, 0, 0, 0, 0, 0, 0,
+        // State 100
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 27, 0, 0, 0, 0, 0,
+        // State 101
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -14, 0, -14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 102
+        0, 0, 0, 28, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 103
+        0, -53, 0, -53, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -53, 0, -53, -53, -53, 0, -53, -53, -53, -53, 0,
+        // State 104
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -15, 0, -15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 105
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 109, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        // State 106
+        0, -34, 0, -34, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -34, 0, -34, -34, -34, 0, 0, 0, 0, 0, 0,
+        // State 107
+        0, -58, 0, -58, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -58, 0, -58, -58, -58, 0, 0, 0, 0, 0, 0,
+        // State 108
+        0, -54, 0, -54, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, -54, 0, -54, -54, -54, 0, -54, -54, -54, -54, 0,
     ];
     fn __action(state: i8, integer: usize) -> i8 {
-        __ACTION[(state as usize) * 31 + integer]
+        __ACTION[(state as usize) * 39 + integer]
     }
     const __EOF_ACTION: &[i8] = &[
         // State 0
         0,
         // State 1
-        -15,
+        -38,
         // State 2
-        -9,
+        -22,
         // State 3
         0,
         // State 4
@@ -218,13 +292,13 @@ mod __parse__Expr {
         // State 11
         0,
         // State 12
-        -8,
+        0,
         // State 13
         0,
         // State 14
         0,
         // State 15
-        0,
+        -21,
         // State 16
         0,
         // State 17
@@ -234,17 +308,17 @@ mod __parse__Expr {
         // State 19
         0,
         // State 20
-        -49,
+        0,
         // State 21
-        -34,
+        0,
         // State 22
-        -21,
+        0,
         // State 23
-        -14,
+        0,
         // State 24
-        -13,
+        0,
         // State 25
-        -18,
+        0,
         // State 26
         0,
         // State 27
@@ -252,19 +326,19 @@ mod __parse__Expr {
         // State 28
         0,
         // State 29
-        0,
+        -37,
         // State 30
-        -20,
+        -80,
         // State 31
-        0,
+        -62,
         // State 32
-        0,
+        -45,
         // State 33
-        0,
+        -36,
         // State 34
-        0,
+        -35,
         // State 35
-        0,
+        -41,
         // State 36
         0,
         // State 37
@@ -274,15 +348,15 @@ mod __parse__Expr {
         // State 39
         0,
         // State 40
-        -19,
+        -44,
         // State 41
-        0,
+        -42,
         // State 42
         0,
         // State 43
-        -32,
+        -55,
         // State 44
-        -29,
+        0,
         // State 45
         0,
         // State 46
@@ -296,132 +370,223 @@ mod __parse__Expr {
         // State 50
         0,
         // State 51
-        0,
+        -60,
         // State 52
         0,
         // State 53
         0,
         // State 54
-        -24,
+        0,
         // State 55
-        -33,
+        0,
         // State 56
         0,
         // State 57
-        0,
+        -43,
         // State 58
-        -22,
+        0,
         // State 59
         0,
         // State 60
         0,
         // State 61
-        -26,
+        0,
         // State 62
         0,
         // State 63
-        -23,
+        0,
         // State 64
-        -27,
+        0,
         // State 65
         0,
         // State 66
-        -31,
+        0,
         // State 67
         0,
         // State 68
         0,
         // State 69
-        -25,
+        0,
         // State 70
         0,
         // State 71
         0,
         // State 72
-        0,
+        -49,
         // State 73
-        -30,
+        0,
         // State 74
-        -28,
+        0,
+        // State 75
+        0,
+        // State 76
+        -61,
+        // State 77
+        0,
+        // State 78
+        0,
+        // State 79
+        -47,
+        // State 80
+        0,
+        // State 81
+        0,
+        // State 82
+        -51,
+        // State 83
+        0,
+        // State 84
+        -48,
+        // State 85
+        0,
+        // State 86
+        -46,
+        // State 87
+        0,
+        // State 88
+        -52,
+        // State 89
+        0,
+        // State 90
+        0,
+        // State 91
+        0,
+        // State 92
+        -59,
+        // State 93
+        0,
+        // State 94
+        0,
+        // State 95
+        -50,
+        // State 96
+        0,
+        // State 97
+        0,
+        // State 98
+        0,
+        // State 99
+        0,
+        // State 100
+        0,
+        // State 101
+        0,
+        // State 102
+        0,
+        // State 103
+        -53,
+        // State 104
+        0,
+        // State 105
+        0,
+        // State 106
+        -34,
+        // State 107
+        -58,
+        // State 108
+        -54,
     ];
     fn __goto(state: i8, nt: usize) -> i8 {
         match nt {
-            2 => 11,
-            3 => 6,
-            4 => 1,
-            5 => match state {
-                13 => 65,
-                _ => 50,
+            2 => 16,
+            5 => 14,
+            8 => 23,
+            9 => 8,
+            10 => 28,
+            11 => 1,
+            12 => match state {
+                18 => 91,
+                _ => 68,
             },
-            6 => 51,
-            7 => match state {
-                3 => 49,
-                5 => 53,
-                8 => 56,
-                9 => 57,
-                10 => 59,
-                11 => 60,
-                14 => 66,
-                15 => 67,
-                16 => 70,
-                17 => 71,
-                18 => 72,
-                19 => 73,
-                _ => 20,
+            13 => 69,
+            14 => 74,
+            15 => 89,
+            16 => 29,
+            17 => match state {
+                3 => 66,
+                6 => 71,
+                7 => 73,
+                9 => 75,
+                11 => 77,
+                12 => 78,
+                13 => 80,
+                14 => 81,
+                16 => 85,
+                19 => 92,
+                20 => 93,
+                21 => 96,
+                22 => 98,
+                24 => 102,
+                25 => 105,
+                26 => 106,
+                27 => 107,
+                _ => 30,
             },
-            9 => match state {
-                7 => 55,
-                _ => 21,
+            19 => match state {
+                10 => 76,
+                _ => 31,
             },
-            10 => match state {
-                4 | 13 => 52,
-                _ => 22,
+            20 => match state {
+                4 => 67,
+                5 | 18 => 70,
+                17 => 90,
+                23 => 99,
+                _ => 32,
             },
-            11 => 23,
-            12 => 24,
-            13 => 25,
-            14 => match state {
-                6 => 12,
+            22 => 33,
+            23 => 34,
+            24 => 35,
+            25 => match state {
+                8 => 15,
                 _ => 2,
             },
-            15 => 7,
-            16 => 26,
-            17 => 27,
+            26 => 10,
+            27 => 36,
+            28 => 37,
             _ => 0,
         }
     }
+    #[allow(clippy::needless_raw_string_hashes)]
     const __TERMINAL: &[&str] = &[
-        r###"r#"-?[0-9]+"#"###,
-        r###"r#"[a-zA-Z][a-zA-Z0-9_]*"#"###,
-        r###""!""###,
-        r###""(""###,
-        r###"")""###,
-        r###""+""###,
-        r###"",""###,
-        r###""-""###,
-        r###"";""###,
-        r###""<""###,
-        r###""=""###,
-        r###""==""###,
-        r###""add1""###,
+        r###""let""###,
+        r###""in""###,
+        r###""if""###,
         r###""else""###,
+        r###""def""###,
+        r###""true""###,
         r###""false""###,
-        r###""if""###,
-        r###""in""###,
-        r###""isList""###,
+        r###""add1""###,
+        r###""sub1""###,
+        r###""isZero""###,
         r###""isNum""###,
         r###""isPair""###,
-        r###""isVector""###,
-        r###""isZero""###,
         r###""left""###,
-        r###""let""###,
         r###""right""###,
-        r###""sub1""###,
-        r###""true""###,
+        r###""isList""###,
+        r###""isVector""###,
         r###""vectorLength""###,
+        r###""pair""###,
+        r###""vector""###,
+        r###""vectorGet""###,
         r###""vectorSet""###,
+        r###""floor""###,
+        r###""round""###,
+        r###""toFloat""###,
+        r###""number""###,
+        r###""float""###,
+        r###""identifier""###,
+        r###""(""###,
+        r###"")""###,
         r###""{""###,
         r###""}""###,
+        r###"",""###,
+        r###"";""###,
+        r###""=""###,
+        r###""==""###,
+        r###""<""###,
+        r###""+""###,
+        r###""-""###,
+        r###""!""###,
     ];
     fn __expected_tokens(__state: i8) -> alloc::vec::Vec<alloc::string::String> {
         __TERMINAL.iter().enumerate().filter_map(|(index, terminal)| {
@@ -451,15 +616,14 @@ mod __parse__Expr {
     struct __StateMachine<'input>
     where 
     {
-        input: &'input str,
         __phantom: core::marker::PhantomData<(&'input ())>,
     }
     impl<'input> __state_machine::ParserDefinition for __StateMachine<'input>
     where 
     {
         type Location = usize;
-        type Error = &'static str;
-        type Token = Token<'input>;
+        type Error = LexicalError;
+        type Token = Tok<'input>;
         type TokenIndex = usize;
         type Symbol = __Symbol<'input>;
         type Success = Box<Expr>;
@@ -490,7 +654,7 @@ mod __parse__Expr {
 
         #[inline]
         fn error_action(&self, state: i8) -> i8 {
-            __action(state, 31 - 1)
+            __action(state, 39 - 1)
         }
 
         #[inline]
@@ -536,7 +700,6 @@ mod __parse__Expr {
             symbols: &mut alloc::vec::Vec<__state_machine::SymbolTriple<Self>>,
         ) -> Option<__state_machine::ParseResult<Self>> {
             __reduce(
-                self.input,
                 action,
                 start_location,
                 states,
@@ -552,43 +715,51 @@ mod __parse__Expr {
     fn __token_to_integer<
         'input,
     >(
-        __token: &Token<'input>,
+        __token: &Tok<'input>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> Option<usize>
     {
         #[warn(unused_variables)]
         match __token {
-            Token(0, _) if true => Some(0),
-            Token(1, _) if true => Some(1),
-            Token(2, _) if true => Some(2),
-            Token(3, _) if true => Some(3),
-            Token(4, _) if true => Some(4),
-            Token(5, _) if true => Some(5),
-            Token(6, _) if true => Some(6),
-            Token(7, _) if true => Some(7),
-            Token(8, _) if true => Some(8),
-            Token(9, _) if true => Some(9),
-            Token(10, _) if true => Some(10),
-            Token(11, _) if true => Some(11),
-            Token(12, _) if true => Some(12),
-            Token(13, _) if true => Some(13),
-            Token(14, _) if true => Some(14),
-            Token(15, _) if true => Some(15),
-            Token(16, _) if true => Some(16),
-            Token(17, _) if true => Some(17),
-            Token(18, _) if true => Some(18),
-            Token(19, _) if true => Some(19),
-            Token(20, _) if true => Some(20),
-            Token(21, _) if true => Some(21),
-            Token(22, _) if true => Some(22),
-            Token(23, _) if true => Some(23),
-            Token(24, _) if true => Some(24),
-            Token(25, _) if true => Some(25),
-            Token(26, _) if true => Some(26),
-            Token(27, _) if true => Some(27),
-            Token(28, _) if true => Some(28),
-            Token(29, _) if true => Some(29),
-            Token(30, _) if true => Some(30),
+            Tok::Let if true => Some(0),
+            Tok::In if true => Some(1),
+            Tok::If if true => Some(2),
+            Tok::Else if true => Some(3),
+            Tok::Def if true => Some(4),
+            Tok::True if true => Some(5),
+            Tok::False if true => Some(6),
+            Tok::Add1 if true => Some(7),
+            Tok::Sub1 if true => Some(8),
+            Tok::IsZero if true => Some(9),
+            Tok::IsNum if true => Some(10),
+            Tok::IsPair if true => Some(11),
+            Tok::Left if true => Some(12),
+            Tok::Right if true => Some(13),
+            Tok::IsList if true => Some(14),
+            Tok::IsVector if true => Some(15),
+            Tok::VectorLength if true => Some(16),
+            Tok::Pair if true => Some(17),
+            Tok::Vector if true => Some(18),
+            Tok::VectorGet if true => Some(19),
+            Tok::VectorSet if true => Some(20),
+            Tok::Floor if true => Some(21),
+            Tok::Round if true => Some(22),
+            Tok::ToFloat if true => Some(23),
+            Tok::Num(_) if true => Some(24),
+            Tok::Float(_) if true => Some(25),
+            Tok::Id(_) if true => Some(26),
+            Tok::LParen if true => Some(27),
+            Tok::RParen if true => Some(28),
+            Tok::LBrace if true => Some(29),
+            Tok::RBrace if true => Some(30),
+            Tok::Comma if true => Some(31),
+            Tok::Semicolon if true => Some(32),
+            Tok::Assign if true => Some(33),
+            Tok::EqEq if true => Some(34),
+            Tok::Lt if true => Some(35),
+            Tok::Plus if true => Some(36),
+            Tok::Minus if true => Some(37),
+            Tok::Bang if true => Some(38),
             _ => None,
         }
     }
@@ -596,13 +767,22 @@ mod __parse__Expr {
         'input,
     >(
         __token_index: usize,
-        __token: Token<'input>,
+        __token: Tok<'input>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> __Symbol<'input>
     {
         #[allow(clippy::manual_range_patterns)]match __token_index {
-            0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 | 24 | 25 | 26 | 27 | 28 | 29 | 30 => match __token {
-                Token(0, __tok0) | Token(1, __tok0) | Token(2, __tok0) | Token(3, __tok0) | Token(4, __tok0) | Token(5, __tok0) | Token(6, __tok0) | Token(7, __tok0) | Token(8, __tok0) | Token(9, __tok0) | Token(10, __tok0) | Token(11, __tok0) | Token(12, __tok0) | Token(13, __tok0) | Token(14, __tok0) | Token(15, __tok0) | Token(16, __tok0) | Token(17, __tok0) | Token(18, __tok0) | Token(19, __tok0) | Token(20, __tok0) | Token(21, __tok0) | Token(22, __tok0) | Token(23, __tok0) | Token(24, __tok0) | Token(25, __tok0) | Token(26, __tok0) | Token(27, __tok0) | Token(28, __tok0) | Token(29, __tok0) | Token(30, __tok0) if true => __Symbol::Variant0(__tok0),
+            0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 | 27 | 28 | 29 | 30 | 31 | 32 | 33 | 34 | 35 | 36 | 37 | 38 => __Symbol::Variant0(__token),
+            24 => match __token {
+                Tok::Num(__tok0) if true => __Symbol::Variant1(__tok0),
+                _ => unreachable!(),
+            },
+            25 => match __token {
+                Tok::Float(__tok0) if true => __Symbol::Variant2(__tok0),
+                _ => unreachable!(),
+            },
+            26 => match __token {
+                Tok::Id(__tok0) if true => __Symbol::Variant3(__tok0),
                 _ => unreachable!(),
             },
             _ => unreachable!(),
@@ -648,26 +828,26 @@ mod __parse__Expr {
             }
             5 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
+                    states_to_pop: 2,
                     nonterminal_produced: 3,
                 }
             }
             6 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 3,
+                    states_to_pop: 0,
+                    nonterminal_produced: 4,
                 }
             }
             7 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 3,
+                    states_to_pop: 1,
                     nonterminal_produced: 4,
                 }
             }
             8 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 4,
+                    states_to_pop: 2,
+                    nonterminal_produced: 5,
                 }
             }
             9 => {
@@ -678,14 +858,14 @@ mod __parse__Expr {
             }
             10 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
+                    states_to_pop: 2,
                     nonterminal_produced: 6,
                 }
             }
             11 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 3,
-                    nonterminal_produced: 6,
+                    states_to_pop: 0,
+                    nonterminal_produced: 7,
                 }
             }
             12 => {
@@ -696,146 +876,146 @@ mod __parse__Expr {
             }
             13 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 7,
+                    states_to_pop: 2,
+                    nonterminal_produced: 8,
                 }
             }
             14 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 7,
+                    states_to_pop: 3,
+                    nonterminal_produced: 8,
                 }
             }
             15 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 8,
+                    nonterminal_produced: 9,
                 }
             }
             16 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 0,
-                    nonterminal_produced: 8,
+                    states_to_pop: 1,
+                    nonterminal_produced: 9,
                 }
             }
             17 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 9,
+                    nonterminal_produced: 10,
                 }
             }
             18 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 9,
+                    nonterminal_produced: 10,
                 }
             }
             19 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 9,
+                    nonterminal_produced: 10,
                 }
             }
             20 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 9,
+                    states_to_pop: 3,
+                    nonterminal_produced: 11,
                 }
             }
             21 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 3,
-                    nonterminal_produced: 9,
+                    states_to_pop: 1,
+                    nonterminal_produced: 11,
                 }
             }
             22 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 3,
-                    nonterminal_produced: 9,
+                    nonterminal_produced: 12,
                 }
             }
             23 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 2,
-                    nonterminal_produced: 9,
+                    states_to_pop: 1,
+                    nonterminal_produced: 13,
                 }
             }
             24 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 4,
-                    nonterminal_produced: 9,
+                    states_to_pop: 3,
+                    nonterminal_produced: 13,
                 }
             }
             25 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 3,
-                    nonterminal_produced: 9,
+                    states_to_pop: 1,
+                    nonterminal_produced: 14,
                 }
             }
             26 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 4,
-                    nonterminal_produced: 9,
+                    states_to_pop: 0,
+                    nonterminal_produced: 14,
                 }
             }
             27 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 8,
-                    nonterminal_produced: 9,
+                    states_to_pop: 2,
+                    nonterminal_produced: 14,
                 }
             }
             28 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 10,
+                    nonterminal_produced: 14,
                 }
             }
             29 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 7,
-                    nonterminal_produced: 11,
+                    states_to_pop: 1,
+                    nonterminal_produced: 15,
                 }
             }
             30 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 4,
-                    nonterminal_produced: 12,
+                    states_to_pop: 0,
+                    nonterminal_produced: 15,
                 }
             }
             31 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 13,
+                    states_to_pop: 2,
+                    nonterminal_produced: 15,
                 }
             }
             32 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 3,
-                    nonterminal_produced: 14,
+                    states_to_pop: 1,
+                    nonterminal_produced: 15,
                 }
             }
             33 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 14,
+                    states_to_pop: 7,
+                    nonterminal_produced: 16,
                 }
             }
             34 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 15,
+                    nonterminal_produced: 17,
                 }
             }
             35 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 15,
+                    nonterminal_produced: 17,
                 }
             }
             36 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 16,
+                    nonterminal_produced: 17,
                 }
             }
             37 => {
@@ -847,94 +1027,279 @@ mod __parse__Expr {
             38 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 18,
                 }
             }
             39 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    states_to_pop: 0,
+                    nonterminal_produced: 18,
                 }
             }
             40 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 19,
                 }
             }
             41 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 19,
                 }
             }
             42 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 19,
                 }
             }
             43 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 19,
                 }
             }
             44 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 19,
                 }
             }
             45 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    states_to_pop: 4,
+                    nonterminal_produced: 19,
                 }
             }
             46 => {
                 __state_machine::SimulatedReduce::Reduce {
-                    states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    states_to_pop: 3,
+                    nonterminal_produced: 19,
                 }
             }
             47 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 3,
+                    nonterminal_produced: 19,
+                }
+            }
+            48 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 2,
+                    nonterminal_produced: 19,
+                }
+            }
+            49 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 4,
+                    nonterminal_produced: 19,
+                }
+            }
+            50 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 3,
+                    nonterminal_produced: 19,
+                }
+            }
+            51 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 4,
+                    nonterminal_produced: 19,
+                }
+            }
+            52 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 6,
+                    nonterminal_produced: 19,
+                }
+            }
+            53 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 8,
+                    nonterminal_produced: 19,
+                }
+            }
+            54 => {
                 __state_machine::SimulatedReduce::Reduce {
                     states_to_pop: 1,
-                    nonterminal_produced: 17,
+                    nonterminal_produced: 20,
                 }
             }
-            48 => __state_machine::SimulatedReduce::Accept,
-            _ => panic!("invalid reduction index {}", __reduce_index)
-        }
-    }
-    pub struct ExprParser {
-        builder: __lalrpop_util::lexer::MatcherBuilder,
-        _priv: (),
-    }
-
-    impl Default for ExprParser { fn default() -> Self { Self::new() } }
-    impl ExprParser {
-        pub fn new() -> ExprParser {
-            let __builder = super::__intern_token::new_builder();
-            ExprParser {
-                builder: __builder,
-                _priv: (),
+            55 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 21,
+                }
             }
-        }
+            56 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 0,
+                    nonterminal_produced: 21,
+                }
+            }
+            57 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 7,
+                    nonterminal_produced: 22,
+                }
+            }
+            58 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 4,
+                    nonterminal_produced: 23,
+                }
+            }
+            59 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 24,
+                }
+            }
+            60 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 3,
+                    nonterminal_produced: 25,
+                }
+            }
+            61 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 25,
+                }
+            }
+            62 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 26,
+                }
+            }
+            63 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 26,
+                }
+            }
+            64 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 27,
+                }
+            }
+            65 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            66 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            67 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            68 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            69 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            70 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            71 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            72 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            73 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            74 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            75 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            76 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            77 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            78 => {
+                __state_machine::SimulatedReduce::Reduce {
+                    states_to_pop: 1,
+                    nonterminal_produced: 28,
+                }
+            }
+            79 => __state_machine::SimulatedReduce::Accept,
+            _ => panic!("invalid reduction index {__reduce_index}",)
+        }
+    }
+    pub struct ExprParser {
+        _priv: (),
+    }
+
+    impl Default for ExprParser { fn default() -> Self { Self::new() } }
+    impl ExprParser {
+        pub fn new() -> ExprParser {
+            ExprParser {
+                _priv: (),
+            }
+        }
 
         #[allow(dead_code)]
         pub fn parse<
             'input,
+            __TOKEN: __ToTriple<'input, >,
+            __TOKENS: IntoIterator<Item=__TOKEN>,
         >(
             &self,
-            input: &'input str,
-        ) -> Result<Box<Expr>, __lalrpop_util::ParseError<usize, Token<'input>, &'static str>>
+            __tokens0: __TOKENS,
+        ) -> Result<Box<Expr>, __lalrpop_util::ParseError<usize, Tok<'input>, LexicalError>>
         {
-            let mut __tokens = self.builder.matcher(input);
+            let __tokens = __tokens0.into_iter();
+            let mut __tokens = __tokens.map(|t| __ToTriple::to_triple(t));
             __state_machine::Parser::drive(
                 __StateMachine {
-                    input,
                     __phantom: core::marker::PhantomData::<(&())>,
                 },
                 __tokens,
@@ -977,168 +1342,260 @@ mod __parse__Expr {
     fn __reduce<
         'input,
     >(
-        input: &'input str,
         __action: i8,
         __lookahead_start: Option<&usize>,
         __states: &mut alloc::vec::Vec<i8>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
-    ) -> Option<Result<Box<Expr>,__lalrpop_util::ParseError<usize, Token<'input>, &'static str>>>
+    ) -> Option<Result<Box<Expr>,__lalrpop_util::ParseError<usize, Tok<'input>, LexicalError>>>
     {
         let (__pop_states, __nonterminal) = match __action {
             0 => {
-                __reduce0(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce0(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             1 => {
-                __reduce1(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce1(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             2 => {
-                __reduce2(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce2(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             3 => {
-                __reduce3(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce3(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             4 => {
-                __reduce4(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce4(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             5 => {
-                __reduce5(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce5(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             6 => {
-                __reduce6(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce6(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             7 => {
-                __reduce7(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce7(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             8 => {
-                __reduce8(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce8(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             9 => {
-                __reduce9(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce9(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             10 => {
-                __reduce10(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce10(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             11 => {
-                __reduce11(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce11(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             12 => {
-                __reduce12(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce12(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             13 => {
-                __reduce13(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce13(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             14 => {
-                __reduce14(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce14(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             15 => {
-                __reduce15(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce15(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             16 => {
-                __reduce16(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce16(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             17 => {
-                __reduce17(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce17(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             18 => {
-                __reduce18(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce18(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             19 => {
-                __reduce19(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce19(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             20 => {
-                __reduce20(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce20(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             21 => {
-                __reduce21(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce21(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             22 => {
-                __reduce22(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce22(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             23 => {
-                __reduce23(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce23(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             24 => {
-                __reduce24(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce24(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             25 => {
-                __reduce25(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce25(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             26 => {
-                __reduce26(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce26(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             27 => {
-                __reduce27(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce27(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             28 => {
-                __reduce28(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce28(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             29 => {
-                __reduce29(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce29(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             30 => {
-                __reduce30(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce30(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             31 => {
-                __reduce31(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce31(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             32 => {
-                __reduce32(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce32(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             33 => {
-                __reduce33(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce33(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             34 => {
-                __reduce34(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce34(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             35 => {
-                __reduce35(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce35(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             36 => {
-                __reduce36(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce36(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             37 => {
-                __reduce37(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce37(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             38 => {
-                __reduce38(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce38(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             39 => {
-                __reduce39(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce39(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             40 => {
-                __reduce40(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce40(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             41 => {
-                __reduce41(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce41(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             42 => {
-                __reduce42(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce42(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             43 => {
-                __reduce43(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce43(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             44 => {
-                __reduce44(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce44(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             45 => {
-                __reduce45(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce45(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             46 => {
-                __reduce46(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce46(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             47 => {
-                __reduce47(input, __lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+                __reduce47(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
             }
             48 => {
+                __reduce48(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            49 => {
+                __reduce49(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            50 => {
+                __reduce50(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            51 => {
+                __reduce51(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            52 => {
+                __reduce52(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            53 => {
+                __reduce53(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            54 => {
+                __reduce54(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            55 => {
+                __reduce55(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            56 => {
+                __reduce56(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            57 => {
+                __reduce57(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            58 => {
+                __reduce58(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            59 => {
+                __reduce59(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            60 => {
+                __reduce60(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            61 => {
+                __reduce61(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            62 => {
+                __reduce62(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            63 => {
+                __reduce63(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            64 => {
+                __reduce64(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            65 => {
+                __reduce65(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            66 => {
+                __reduce66(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            67 => {
+                __reduce67(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            68 => {
+                __reduce68(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            69 => {
+                __reduce69(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            70 => {
+                __reduce70(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            71 => {
+                __reduce71(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            72 => {
+                __reduce72(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            73 => {
+                __reduce73(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            74 => {
+                __reduce74(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            75 => {
+                __reduce75(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            76 => {
+                __reduce76(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            77 => {
+                __reduce77(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            78 => {
+                __reduce78(__lookahead_start, __symbols, core::marker::PhantomData::<(&())>)
+            }
+            79 => {
                 // __Expr = Expr => ActionFn(0);
-                let __sym0 = __pop_Variant1(__symbols);
+                let __sym0 = __pop_Variant4(__symbols);
                 let __start = __sym0.0;
                 let __end = __sym0.2;
-                let __nt = super::__action0::<>(input, __sym0);
+                let __nt = super::__action0::<>(__sym0);
                 return Some(Ok(__nt));
             }
-            _ => panic!("invalid action code {}", __action)
+            _ => panic!("invalid action code {__action}")
         };
         let __states_len = __states.len();
         __states.truncate(__states_len - __pop_states);
@@ -1151,1723 +1608,2484 @@ mod __parse__Expr {
     fn __symbol_type_mismatch() -> ! {
         panic!("symbol type mismatch")
     }
-    fn __pop_Variant4<
+    fn __pop_Variant9<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, (String, Box<Expr>), usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant4(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant9(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant3<
+    fn __pop_Variant8<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, BinaryOp, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant3(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant8(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant1<
+    fn __pop_Variant4<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, Box<Expr>, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant1(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant4(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant6<
+    fn __pop_Variant13<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, Option<Box<Expr>>, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant6(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant13(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant7<
+    fn __pop_Variant14<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, Option<String>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant14(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant6<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, String, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant7(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant6(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant9<
+    fn __pop_Variant15<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, TernaryOp, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant9(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant15(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant10<
+    fn __pop_Variant0<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, Tok<'input>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant0(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant16<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, UnaryOp, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant16(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant10<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, Vec<(String, Box<Expr>)>, usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant10(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
+    fn __pop_Variant11<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, Vec<Box<Expr>>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant11(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
+    fn __pop_Variant12<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, Vec<String>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant12(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
     fn __pop_Variant5<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, Vec<(String, Box<Expr>)>, usize)
+    ) -> (usize, alloc::vec::Vec<Box<Expr>>, usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant5(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
+    fn __pop_Variant7<
+      'input,
+    >(
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
+    ) -> (usize, alloc::vec::Vec<String>, usize)
+     {
+        match __symbols.pop() {
+            Some((__l, __Symbol::Variant7(__v), __r)) => (__l, __v, __r),
+            _ => __symbol_type_mismatch()
+        }
+    }
     fn __pop_Variant2<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
-    ) -> (usize, alloc::vec::Vec<Box<Expr>>, usize)
+    ) -> (usize, f64, usize)
      {
         match __symbols.pop() {
             Some((__l, __Symbol::Variant2(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant8<
+    fn __pop_Variant1<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, i64, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant8(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant1(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
-    fn __pop_Variant0<
+    fn __pop_Variant3<
       'input,
     >(
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>
     ) -> (usize, &'input str, usize)
      {
         match __symbols.pop() {
-            Some((__l, __Symbol::Variant0(__v), __r)) => (__l, __v, __r),
+            Some((__l, __Symbol::Variant3(__v), __r)) => (__l, __v, __r),
             _ => __symbol_type_mismatch()
         }
     }
     fn __reduce0<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Expr> ";") = Expr, ";" => ActionFn(43);
+        // (<Expr> ",") = Expr, "," => ActionFn(64);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant1(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym1.2;
-        let __nt = super::__action43::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
+        let __nt = super::__action64::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
         (2, 0)
     }
     fn __reduce1<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Expr> ";")* =  => ActionFn(41);
+        // (<Expr> ",")* =  => ActionFn(62);
         let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
         let __end = __start;
-        let __nt = super::__action41::<>(input, &__start, &__end);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action62::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (0, 1)
     }
     fn __reduce2<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Expr> ";")* = (<Expr> ";")+ => ActionFn(42);
-        let __sym0 = __pop_Variant2(__symbols);
+        // (<Expr> ",")* = (<Expr> ",")+ => ActionFn(63);
+        let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action42::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action63::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (1, 1)
     }
     fn __reduce3<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Expr> ";")+ = Expr, ";" => ActionFn(46);
+        // (<Expr> ",")+ = Expr, "," => ActionFn(71);
         assert!(__symbols.len() >= 2);
         let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant1(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym1.2;
-        let __nt = super::__action46::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action71::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (2, 2)
     }
     fn __reduce4<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // (<Expr> ";")+ = (<Expr> ";")+, Expr, ";" => ActionFn(47);
+        // (<Expr> ",")+ = (<Expr> ",")+, Expr, "," => ActionFn(72);
         assert!(__symbols.len() >= 3);
         let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant2(__symbols);
+        let __sym1 = __pop_Variant4(__symbols);
+        let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0;
         let __end = __sym2.2;
-        let __nt = super::__action47::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant2(__nt), __end));
+        let __nt = super::__action72::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (3, 2)
     }
     fn __reduce5<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp = "==" => ActionFn(21);
-        let __sym0 = __pop_Variant0(__symbols);
+        // (<Expr> ";") = Expr, ";" => ActionFn(54);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action21::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
-        (1, 3)
+        let __end = __sym1.2;
+        let __nt = super::__action54::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (2, 3)
     }
     fn __reduce6<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinOp = "<" => ActionFn(22);
-        let __sym0 = __pop_Variant0(__symbols);
-        let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action22::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
-        (1, 3)
+        // (<Expr> ";")* =  => ActionFn(52);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
+        let __end = __start;
+        let __nt = super::__action52::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
+        (0, 4)
     }
     fn __reduce7<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinaryExpr = BinaryExpr, BinOp, Term => ActionFn(9);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant3(__symbols);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Expr> ";")* = (<Expr> ";")+ => ActionFn(53);
+        let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0;
-        let __end = __sym2.2;
-        let __nt = super::__action9::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (3, 4)
-    }
-    fn __reduce8<
+        let __end = __sym0.2;
+        let __nt = super::__action53::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
+        (1, 4)
+    }
+    fn __reduce8<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // BinaryExpr = Term => ActionFn(10);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Expr> ";")+ = Expr, ";" => ActionFn(75);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action10::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 4)
+        let __end = __sym1.2;
+        let __nt = super::__action75::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
+        (2, 5)
     }
     fn __reduce9<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Binding = Id, "=", Expr => ActionFn(7);
+        // (<Expr> ";")+ = (<Expr> ";")+, Expr, ";" => ActionFn(76);
         assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant7(__symbols);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant4(__symbols);
+        let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0;
         let __end = __sym2.2;
-        let __nt = super::__action7::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        let __nt = super::__action76::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
         (3, 5)
     }
     fn __reduce10<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Bindings = Binding => ActionFn(5);
-        let __sym0 = __pop_Variant4(__symbols);
+        // (<Id> ",") = Id, "," => ActionFn(61);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action5::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (1, 6)
+        let __end = __sym1.2;
+        let __nt = super::__action61::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
+        (2, 6)
     }
     fn __reduce11<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Bindings = Bindings, ",", Binding => ActionFn(6);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant4(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant5(__symbols);
-        let __start = __sym0.0;
-        let __end = __sym2.2;
-        let __nt = super::__action6::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant5(__nt), __end));
-        (3, 6)
+        // (<Id> ",")* =  => ActionFn(59);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
+        let __end = __start;
+        let __nt = super::__action59::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
+        (0, 7)
     }
     fn __reduce12<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr = Let => ActionFn(1);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Id> ",")* = (<Id> ",")+ => ActionFn(60);
+        let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action1::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
+        let __nt = super::__action60::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
         (1, 7)
     }
     fn __reduce13<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr = If => ActionFn(2);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Id> ",")+ = Id, "," => ActionFn(79);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action2::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 7)
+        let __end = __sym1.2;
+        let __nt = super::__action79::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
+        (2, 8)
     }
     fn __reduce14<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr = BinaryExpr => ActionFn(3);
-        let __sym0 = __pop_Variant1(__symbols);
+        // (<Id> ",")+ = (<Id> ",")+, Id, "," => ActionFn(80);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant6(__symbols);
+        let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action3::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 7)
+        let __end = __sym2.2;
+        let __nt = super::__action80::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
+        (3, 8)
     }
     fn __reduce15<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr? = Expr => ActionFn(39);
-        let __sym0 = __pop_Variant1(__symbols);
+        // BinOp = "==" => ActionFn(26);
+        let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action39::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
-        (1, 8)
+        let __nt = super::__action26::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 9)
     }
     fn __reduce16<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Expr? =  => ActionFn(40);
-        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
-        let __end = __start;
-        let __nt = super::__action40::<>(input, &__start, &__end);
-        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
-        (0, 8)
+        // BinOp = "<" => ActionFn(27);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action27::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 9)
     }
     fn __reduce17<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = Num => ActionFn(13);
-        let __sym0 = __pop_Variant8(__symbols);
+        // BinPrim = "pair" => ActionFn(30);
+        let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action13::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 9)
+        let __nt = super::__action30::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 10)
     }
     fn __reduce18<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "true" => ActionFn(14);
+        // BinPrim = "vector" => ActionFn(31);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action14::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 9)
+        let __nt = super::__action31::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 10)
     }
     fn __reduce19<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "false" => ActionFn(15);
+        // BinPrim = "vectorGet" => ActionFn(32);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action15::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 9)
+        let __nt = super::__action32::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 10)
     }
     fn __reduce20<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = Id => ActionFn(16);
-        let __sym0 = __pop_Variant7(__symbols);
+        // BinaryExpr = BinaryExpr, BinOp, Term => ActionFn(11);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action16::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 9)
+        let __end = __sym2.2;
+        let __nt = super::__action11::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (3, 11)
     }
     fn __reduce21<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "(", Expr, ")" => ActionFn(17);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
+        // BinaryExpr = Term => ActionFn(12);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
-        let __end = __sym2.2;
-        let __nt = super::__action17::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (3, 9)
+        let __end = __sym0.2;
+        let __nt = super::__action12::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 11)
     }
     fn __reduce22<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "{", Expr, "}" => ActionFn(50);
+        // Binding = Id, "=", Expr => ActionFn(9);
         assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant1(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0;
         let __end = __sym2.2;
-        let __nt = super::__action50::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (3, 9)
+        let __nt = super::__action9::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant9(__nt), __end));
+        (3, 12)
     }
     fn __reduce23<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "{", "}" => ActionFn(51);
-        assert!(__symbols.len() >= 2);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Bindings = Binding => ActionFn(7);
+        let __sym0 = __pop_Variant9(__symbols);
         let __start = __sym0.0;
-        let __end = __sym1.2;
-        let __nt = super::__action51::<>(input, __sym0, __sym1);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (2, 9)
+        let __end = __sym0.2;
+        let __nt = super::__action7::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        (1, 13)
     }
     fn __reduce24<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "{", (<Expr> ";")+, Expr, "}" => ActionFn(52);
-        assert!(__symbols.len() >= 4);
-        let __sym3 = __pop_Variant0(__symbols);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant2(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Bindings = Bindings, ",", Binding => ActionFn(8);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant9(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant10(__symbols);
         let __start = __sym0.0;
-        let __end = __sym3.2;
-        let __nt = super::__action52::<>(input, __sym0, __sym1, __sym2, __sym3);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (4, 9)
+        let __end = __sym2.2;
+        let __nt = super::__action8::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        (3, 13)
     }
     fn __reduce25<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = "{", (<Expr> ";")+, "}" => ActionFn(53);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant2(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Comma<Expr> = Expr => ActionFn(83);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
-        let __end = __sym2.2;
-        let __nt = super::__action53::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (3, 9)
+        let __end = __sym0.2;
+        let __nt = super::__action83::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 14)
     }
     fn __reduce26<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = UnOp, "(", Expr, ")" => ActionFn(19);
-        assert!(__symbols.len() >= 4);
-        let __sym3 = __pop_Variant0(__symbols);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant10(__symbols);
-        let __start = __sym0.0;
-        let __end = __sym3.2;
-        let __nt = super::__action19::<>(input, __sym0, __sym1, __sym2, __sym3);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (4, 9)
+        // Comma<Expr> =  => ActionFn(84);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
+        let __end = __start;
+        let __nt = super::__action84::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (0, 14)
     }
     fn __reduce27<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Factor = TriOp, "(", Expr, ",", Expr, ",", Expr, ")" => ActionFn(20);
-        assert!(__symbols.len() >= 8);
-        let __sym7 = __pop_Variant0(__symbols);
-        let __sym6 = __pop_Variant1(__symbols);
-        let __sym5 = __pop_Variant0(__symbols);
-        let __sym4 = __pop_Variant1(__symbols);
-        let __sym3 = __pop_Variant0(__symbols);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant9(__symbols);
+        // Comma<Expr> = (<Expr> ",")+, Expr => ActionFn(85);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant4(__symbols);
+        let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0;
-        let __end = __sym7.2;
-        let __nt = super::__action20::<>(input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6, __sym7);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (8, 9)
+        let __end = __sym1.2;
+        let __nt = super::__action85::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (2, 14)
     }
     fn __reduce28<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Id = r#"[a-zA-Z][a-zA-Z0-9_]*"# => ActionFn(38);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Comma<Expr> = (<Expr> ",")+ => ActionFn(86);
+        let __sym0 = __pop_Variant5(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action38::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant7(__nt), __end));
-        (1, 10)
+        let __nt = super::__action86::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant11(__nt), __end));
+        (1, 14)
     }
     fn __reduce29<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // If = "if", "(", Expr, ")", Expr, "else", Expr => ActionFn(8);
-        assert!(__symbols.len() >= 7);
-        let __sym6 = __pop_Variant1(__symbols);
-        let __sym5 = __pop_Variant0(__symbols);
-        let __sym4 = __pop_Variant1(__symbols);
-        let __sym3 = __pop_Variant0(__symbols);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant0(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Comma<Id> = Id => ActionFn(91);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0;
-        let __end = __sym6.2;
-        let __nt = super::__action8::<>(input, __sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (7, 11)
+        let __end = __sym0.2;
+        let __nt = super::__action91::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant12(__nt), __end));
+        (1, 15)
     }
     fn __reduce30<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Let = "let", Bindings, "in", Expr => ActionFn(4);
-        assert!(__symbols.len() >= 4);
-        let __sym3 = __pop_Variant1(__symbols);
-        let __sym2 = __pop_Variant0(__symbols);
-        let __sym1 = __pop_Variant5(__symbols);
-        let __sym0 = __pop_Variant0(__symbols);
-        let __start = __sym0.0;
-        let __end = __sym3.2;
-        let __nt = super::__action4::<>(input, __sym0, __sym1, __sym2, __sym3);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (4, 12)
+        // Comma<Id> =  => ActionFn(92);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
+        let __end = __start;
+        let __nt = super::__action92::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant12(__nt), __end));
+        (0, 15)
     }
     fn __reduce31<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Num = r#"-?[0-9]+"# => ActionFn(37);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Comma<Id> = (<Id> ",")+, Id => ActionFn(93);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant6(__symbols);
+        let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action37::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
-        (1, 13)
+        let __end = __sym1.2;
+        let __nt = super::__action93::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant12(__nt), __end));
+        (2, 15)
     }
     fn __reduce32<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Term = Term, TermOp, Factor => ActionFn(11);
-        assert!(__symbols.len() >= 3);
-        let __sym2 = __pop_Variant1(__symbols);
-        let __sym1 = __pop_Variant3(__symbols);
-        let __sym0 = __pop_Variant1(__symbols);
+        // Comma<Id> = (<Id> ",")+ => ActionFn(94);
+        let __sym0 = __pop_Variant7(__symbols);
         let __start = __sym0.0;
-        let __end = __sym2.2;
-        let __nt = super::__action11::<>(input, __sym0, __sym1, __sym2);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (3, 14)
+        let __end = __sym0.2;
+        let __nt = super::__action94::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant12(__nt), __end));
+        (1, 15)
     }
     fn __reduce33<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // Term = Factor => ActionFn(12);
-        let __sym0 = __pop_Variant1(__symbols);
+        // Def = "def", Id, "(", Comma<Id>, ")", "=", Expr => ActionFn(5);
+        assert!(__symbols.len() >= 7);
+        let __sym6 = __pop_Variant4(__symbols);
+        let __sym5 = __pop_Variant0(__symbols);
+        let __sym4 = __pop_Variant0(__symbols);
+        let __sym3 = __pop_Variant12(__symbols);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant6(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action12::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
-        (1, 14)
+        let __end = __sym6.2;
+        let __nt = super::__action5::<>(__sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (7, 16)
     }
     fn __reduce34<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // TermOp = "+" => ActionFn(23);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Expr = Let => ActionFn(1);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action23::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
-        (1, 15)
+        let __nt = super::__action1::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 17)
     }
     fn __reduce35<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // TermOp = "-" => ActionFn(24);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Expr = If => ActionFn(2);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action24::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant3(__nt), __end));
-        (1, 15)
+        let __nt = super::__action2::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 17)
     }
     fn __reduce36<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // TriOp = "vectorSet" => ActionFn(36);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Expr = Def => ActionFn(3);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action36::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant9(__nt), __end));
-        (1, 16)
+        let __nt = super::__action3::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 17)
     }
     fn __reduce37<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "add1" => ActionFn(25);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Expr = BinaryExpr => ActionFn(4);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action25::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
+        let __nt = super::__action4::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
         (1, 17)
     }
     fn __reduce38<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "sub1" => ActionFn(26);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Expr? = Expr => ActionFn(50);
+        let __sym0 = __pop_Variant4(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action26::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action50::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant13(__nt), __end));
+        (1, 18)
     }
     fn __reduce39<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "isZero" => ActionFn(27);
-        let __sym0 = __pop_Variant0(__symbols);
-        let __start = __sym0.0;
-        let __end = __sym0.2;
-        let __nt = super::__action27::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        // Expr? =  => ActionFn(51);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
+        let __end = __start;
+        let __nt = super::__action51::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant13(__nt), __end));
+        (0, 18)
     }
     fn __reduce40<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "isNum" => ActionFn(28);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Factor = Num => ActionFn(15);
+        let __sym0 = __pop_Variant1(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action28::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action15::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 19)
     }
     fn __reduce41<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "!" => ActionFn(29);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Factor = "float" => ActionFn(16);
+        let __sym0 = __pop_Variant2(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action29::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action16::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 19)
     }
     fn __reduce42<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "isPair" => ActionFn(30);
+        // Factor = "true" => ActionFn(17);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action30::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action17::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 19)
     }
     fn __reduce43<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "left" => ActionFn(31);
+        // Factor = "false" => ActionFn(18);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action31::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action18::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 19)
     }
     fn __reduce44<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "right" => ActionFn(32);
-        let __sym0 = __pop_Variant0(__symbols);
+        // Factor = Id => ActionFn(19);
+        let __sym0 = __pop_Variant6(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action32::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action19::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 19)
     }
     fn __reduce45<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "isList" => ActionFn(33);
+        // Factor = Id, "(", Comma<Expr>, ")" => ActionFn(20);
+        assert!(__symbols.len() >= 4);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant11(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant6(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym3.2;
+        let __nt = super::__action20::<>(__sym0, __sym1, __sym2, __sym3);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (4, 19)
+    }
+    fn __reduce46<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = "(", Expr, ")" => ActionFn(21);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant4(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym2.2;
+        let __nt = super::__action21::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (3, 19)
+    }
+    fn __reduce47<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = "{", Expr, "}" => ActionFn(87);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant4(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym2.2;
+        let __nt = super::__action87::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (3, 19)
+    }
+    fn __reduce48<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = "{", "}" => ActionFn(88);
+        assert!(__symbols.len() >= 2);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym1.2;
+        let __nt = super::__action88::<>(__sym0, __sym1);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (2, 19)
+    }
+    fn __reduce49<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = "{", (<Expr> ";")+, Expr, "}" => ActionFn(89);
+        assert!(__symbols.len() >= 4);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant5(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym3.2;
+        let __nt = super::__action89::<>(__sym0, __sym1, __sym2, __sym3);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (4, 19)
+    }
+    fn __reduce50<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = "{", (<Expr> ";")+, "}" => ActionFn(90);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant5(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym2.2;
+        let __nt = super::__action90::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (3, 19)
+    }
+    fn __reduce51<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = UnOp, "(", Expr, ")" => ActionFn(23);
+        assert!(__symbols.len() >= 4);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant16(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym3.2;
+        let __nt = super::__action23::<>(__sym0, __sym1, __sym2, __sym3);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (4, 19)
+    }
+    fn __reduce52<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = BinPrim, "(", Expr, ",", Expr, ")" => ActionFn(24);
+        assert!(__symbols.len() >= 6);
+        let __sym5 = __pop_Variant0(__symbols);
+        let __sym4 = __pop_Variant4(__symbols);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant8(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym5.2;
+        let __nt = super::__action24::<>(__sym0, __sym1, __sym2, __sym3, __sym4, __sym5);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (6, 19)
+    }
+    fn __reduce53<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Factor = TriOp, "(", Expr, ",", Expr, ",", Expr, ")" => ActionFn(25);
+        assert!(__symbols.len() >= 8);
+        let __sym7 = __pop_Variant0(__symbols);
+        let __sym6 = __pop_Variant4(__symbols);
+        let __sym5 = __pop_Variant0(__symbols);
+        let __sym4 = __pop_Variant4(__symbols);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant15(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym7.2;
+        let __nt = super::__action25::<>(__sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6, __sym7);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (8, 19)
+    }
+    fn __reduce54<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Id = "identifier" => ActionFn(49);
+        let __sym0 = __pop_Variant3(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action49::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant6(__nt), __end));
+        (1, 20)
+    }
+    fn __reduce55<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Id? = Id => ActionFn(57);
+        let __sym0 = __pop_Variant6(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action57::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant14(__nt), __end));
+        (1, 21)
+    }
+    fn __reduce56<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Id? =  => ActionFn(58);
+        let __start = __lookahead_start.cloned().or_else(|| __symbols.last().map(|s| s.2)).unwrap_or_default();
+        let __end = __start;
+        let __nt = super::__action58::<>(&__start, &__end);
+        __symbols.push((__start, __Symbol::Variant14(__nt), __end));
+        (0, 21)
+    }
+    fn __reduce57<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // If = "if", "(", Expr, ")", Expr, "else", Expr => ActionFn(10);
+        assert!(__symbols.len() >= 7);
+        let __sym6 = __pop_Variant4(__symbols);
+        let __sym5 = __pop_Variant0(__symbols);
+        let __sym4 = __pop_Variant4(__symbols);
+        let __sym3 = __pop_Variant0(__symbols);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant0(__symbols);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym6.2;
+        let __nt = super::__action10::<>(__sym0, __sym1, __sym2, __sym3, __sym4, __sym5, __sym6);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (7, 22)
+    }
+    fn __reduce58<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Let = "let", Bindings, "in", Expr => ActionFn(6);
+        assert!(__symbols.len() >= 4);
+        let __sym3 = __pop_Variant4(__symbols);
+        let __sym2 = __pop_Variant0(__symbols);
+        let __sym1 = __pop_Variant10(__symbols);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
+        let __end = __sym3.2;
+        let __nt = super::__action6::<>(__sym0, __sym1, __sym2, __sym3);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (4, 23)
+    }
+    fn __reduce59<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Num = "number" => ActionFn(48);
+        let __sym0 = __pop_Variant1(__symbols);
+        let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action33::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action48::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant1(__nt), __end));
+        (1, 24)
     }
-    fn __reduce46<
+    fn __reduce60<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Term = Term, TermOp, Factor => ActionFn(13);
+        assert!(__symbols.len() >= 3);
+        let __sym2 = __pop_Variant4(__symbols);
+        let __sym1 = __pop_Variant8(__symbols);
+        let __sym0 = __pop_Variant4(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym2.2;
+        let __nt = super::__action13::<>(__sym0, __sym1, __sym2);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (3, 25)
+    }
+    fn __reduce61<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // Term = Factor => ActionFn(14);
+        let __sym0 = __pop_Variant4(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action14::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant4(__nt), __end));
+        (1, 25)
+    }
+    fn __reduce62<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "isVector" => ActionFn(34);
+        // TermOp = "+" => ActionFn(28);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action34::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action28::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 26)
     }
-    fn __reduce47<
+    fn __reduce63<
         'input,
     >(
-        input: &'input str,
         __lookahead_start: Option<&usize>,
         __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
         _: core::marker::PhantomData<(&'input ())>,
     ) -> (usize, usize)
     {
-        // UnOp = "vectorLength" => ActionFn(35);
+        // TermOp = "-" => ActionFn(29);
         let __sym0 = __pop_Variant0(__symbols);
         let __start = __sym0.0;
         let __end = __sym0.2;
-        let __nt = super::__action35::<>(input, __sym0);
-        __symbols.push((__start, __Symbol::Variant10(__nt), __end));
-        (1, 17)
+        let __nt = super::__action29::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant8(__nt), __end));
+        (1, 26)
+    }
+    fn __reduce64<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // TriOp = "vectorSet" => ActionFn(47);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action47::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant15(__nt), __end));
+        (1, 27)
+    }
+    fn __reduce65<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "add1" => ActionFn(33);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action33::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce66<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "sub1" => ActionFn(34);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action34::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce67<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "isZero" => ActionFn(35);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action35::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce68<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "isNum" => ActionFn(36);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action36::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce69<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "!" => ActionFn(37);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action37::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce70<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "isPair" => ActionFn(38);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action38::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce71<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "left" => ActionFn(39);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action39::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce72<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "right" => ActionFn(40);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action40::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce73<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "isList" => ActionFn(41);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action41::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce74<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "isVector" => ActionFn(42);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action42::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce75<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "vectorLength" => ActionFn(43);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action43::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce76<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "floor" => ActionFn(44);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action44::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce77<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "round" => ActionFn(45);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action45::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+    fn __reduce78<
+        'input,
+    >(
+        __lookahead_start: Option<&usize>,
+        __symbols: &mut alloc::vec::Vec<(usize,__Symbol<'input>,usize)>,
+        _: core::marker::PhantomData<(&'input ())>,
+    ) -> (usize, usize)
+    {
+        // UnOp = "toFloat" => ActionFn(46);
+        let __sym0 = __pop_Variant0(__symbols);
+        let __start = __sym0.0;
+        let __end = __sym0.2;
+        let __nt = super::__action46::<>(__sym0);
+        __symbols.push((__start, __Symbol::Variant16(__nt), __end));
+        (1, 28)
+    }
+}
+#[allow(unused_imports)]
+pub use self::__parse__Expr::ExprParser;
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action0<
+    'input,
+>(
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    __0
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action1<
+    'input,
+>(
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    __0
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action2<
+    'input,
+>(
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    __0
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action3<
+    'input,
+>(
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    __0
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action4<
+    'input,
+>(
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    __0
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action5<
+    'input,
+>(
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, name, _): (usize, String, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, params, _): (usize, Vec<String>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, body, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::FuncDef(name, params, body))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action6<
+    'input,
+>(
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, bindings, _): (usize, Vec<(String, Box<Expr>)>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, body, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::Let(bindings, body))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action7<
+    'input,
+>(
+    (_, binding, _): (usize, (String, Box<Expr>), usize),
+) -> Vec<(String, Box<Expr>)>
+{
+    vec![binding]
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action8<
+    'input,
+>(
+    (_, mut bindings, _): (usize, Vec<(String, Box<Expr>)>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, binding, _): (usize, (String, Box<Expr>), usize),
+) -> Vec<(String, Box<Expr>)>
+{
+    {
+        bindings.push(binding);
+        bindings
     }
 }
-#[allow(unused_imports)]
-pub use self::__parse__Expr::ExprParser;
-#[rustfmt::skip]
-mod __intern_token {
-    #![allow(unused_imports)]
-    use std::str::FromStr;
-    use crate::ast::{Expr, BinaryOp, UnaryOp, TernaryOp};
-    #[allow(unused_extern_crates)]
-    extern crate lalrpop_util as __lalrpop_util;
-    #[allow(unused_imports)]
-    use self::__lalrpop_util::state_machine as __state_machine;
-    #[allow(unused_extern_crates)]
-    extern crate alloc;
-    pub fn new_builder() -> __lalrpop_util::lexer::MatcherBuilder {
-        let __strs: &[(&str, bool)] = &[
-            ("(?:\\-?[0-9]+)", false),
-            ("(?:[A-Za-z][0-9A-Z_a-z]*)", false),
-            ("!", false),
-            ("\\(", false),
-            ("\\)", false),
-            ("\\+", false),
-            (",", false),
-            ("\\-", false),
-            (";", false),
-            ("<", false),
-            ("=", false),
-            ("(?:==)", false),
-            ("(?:add1)", false),
-            ("(?:else)", false),
-            ("(?:false)", false),
-            ("(?:if)", false),
-            ("(?:in)", false),
-            ("(?:isList)", false),
-            ("(?:isNum)", false),
-            ("(?:isPair)", false),
-            ("(?:isVector)", false),
-            ("(?:isZero)", false),
-            ("(?:left)", false),
-            ("(?:let)", false),
-            ("(?:right)", false),
-            ("(?:sub1)", false),
-            ("(?:true)", false),
-            ("(?:vectorLength)", false),
-            ("(?:vectorSet)", false),
-            ("\\{", false),
-            ("\\}", false),
-            (r"\s+", true),
-        ];
-        __lalrpop_util::lexer::MatcherBuilder::new(__strs.iter().copied()).unwrap()
-    }
-}
-pub(crate) use self::__lalrpop_util::lexer::Token;
-
-#[allow(unused_variables)]
+
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action0<
+fn __action9<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
+    (_, id, _): (usize, String, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, expr, _): (usize, Box<Expr>, usize),
+) -> (String, Box<Expr>)
+{
+    (id, expr)
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action10<
+    'input,
+>(
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, cond, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, then_branch, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, else_branch, _): (usize, Box<Expr>, usize),
 ) -> Box<Expr>
 {
-    __0
+    Box::new(Expr::If(cond, then_branch, else_branch))
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action1<
+fn __action11<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
+    (_, l, _): (usize, Box<Expr>, usize),
+    (_, op, _): (usize, BinaryOp, usize),
+    (_, r, _): (usize, Box<Expr>, usize),
 ) -> Box<Expr>
 {
-    __0
+    Box::new(Expr::BinOp(op, l, r))
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action2<
+fn __action12<
     'input,
 >(
-    input: &'input str,
     (_, __0, _): (usize, Box<Expr>, usize),
 ) -> Box<Expr>
 {
     __0
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action3<
+fn __action13<
+    'input,
+>(
+    (_, l, _): (usize, Box<Expr>, usize),
+    (_, op, _): (usize, BinaryOp, usize),
+    (_, r, _): (usize, Box<Expr>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::BinOp(op, l, r))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action14<
     'input,
 >(
-    input: &'input str,
     (_, __0, _): (usize, Box<Expr>, usize),
 ) -> Box<Expr>
 {
     __0
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action4<
+fn __action15<
     'input,
 >(
-    input: &'input str,
-    (_, _, _): (usize, &'input str, usize),
-    (_, bindings, _): (usize, Vec<(String, Box<Expr>)>, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, body, _): (usize, Box<Expr>, usize),
+    (_, __0, _): (usize, i64, usize),
 ) -> Box<Expr>
 {
-    Box::new(Expr::Let(bindings, body))
+    Box::new(Expr::Num(__0))
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action5<
+fn __action16<
     'input,
 >(
-    input: &'input str,
-    (_, binding, _): (usize, (String, Box<Expr>), usize),
-) -> Vec<(String, Box<Expr>)>
+    (_, __0, _): (usize, f64, usize),
+) -> Box<Expr>
 {
-    vec![binding]
+    Box::new(Expr::Float(__0))
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action6<
+fn __action17<
     'input,
 >(
-    input: &'input str,
-    (_, mut bindings, _): (usize, Vec<(String, Box<Expr>)>, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, binding, _): (usize, (String, Box<Expr>), usize),
-) -> Vec<(String, Box<Expr>)>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::Bool(true))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action18<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::Bool(false))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action19<
+    'input,
+>(
+    (_, __0, _): (usize, String, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::Id(__0))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action20<
+    'input,
+>(
+    (_, f, _): (usize, String, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, args, _): (usize, Vec<Box<Expr>>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::FuncCall(Box::new(Expr::Id(f)), args))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action21<
+    'input,
+>(
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, __0, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    __0
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action22<
+    'input,
+>(
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, exprs, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    (_, last, _): (usize, Option<Box<Expr>>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
 {
     {
-        bindings.push(binding);
-        bindings
+        let mut all_exprs = exprs;
+        if let Some(last_expr) = last {
+            all_exprs.push(last_expr);
+        }
+        Box::new(Expr::Do(all_exprs))
     }
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action7<
+fn __action23<
     'input,
 >(
-    input: &'input str,
-    (_, id, _): (usize, String, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, expr, _): (usize, Box<Expr>, usize),
-) -> (String, Box<Expr>)
+    (_, op, _): (usize, UnaryOp, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, e, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::UnOp(op, e))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action24<
+    'input,
+>(
+    (_, op, _): (usize, BinaryOp, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, e1, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, e2, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::BinOp(op, e1, e2))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action25<
+    'input,
+>(
+    (_, op, _): (usize, TernaryOp, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, e1, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, e2, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+    (_, e3, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
+{
+    Box::new(Expr::TriOp(op, e1, e2, e3))
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action26<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::Eq
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action27<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::Lt
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action28<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::Add
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action29<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::Sub
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action30<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::Pair
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action31<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::Vector
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action32<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> BinaryOp
+{
+    BinaryOp::VectorGet
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action33<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
+{
+    UnaryOp::Add1
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action34<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
+{
+    UnaryOp::Sub1
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action35<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
+{
+    UnaryOp::IsZero
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
+fn __action36<
+    'input,
+>(
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    (id, expr)
+    UnaryOp::IsNum
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action8<
+fn __action37<
     'input,
 >(
-    input: &'input str,
-    (_, _, _): (usize, &'input str, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, cond, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, then_branch, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, else_branch, _): (usize, Box<Expr>, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::If(cond, then_branch, else_branch))
+    UnaryOp::Not
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action9<
+fn __action38<
     'input,
 >(
-    input: &'input str,
-    (_, l, _): (usize, Box<Expr>, usize),
-    (_, op, _): (usize, BinaryOp, usize),
-    (_, r, _): (usize, Box<Expr>, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::BinOp(op, l, r))
+    UnaryOp::IsPair
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action10<
+fn __action39<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    __0
+    UnaryOp::Left
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action11<
+fn __action40<
     'input,
 >(
-    input: &'input str,
-    (_, l, _): (usize, Box<Expr>, usize),
-    (_, op, _): (usize, BinaryOp, usize),
-    (_, r, _): (usize, Box<Expr>, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::BinOp(op, l, r))
+    UnaryOp::Right
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action12<
+fn __action41<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    __0
+    UnaryOp::IsList
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action13<
+fn __action42<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, i64, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::Num(__0))
+    UnaryOp::IsVector
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action14<
+fn __action43<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::Bool(true))
+    UnaryOp::VectorLength
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action15<
+fn __action44<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::Bool(false))
+    UnaryOp::Floor
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action16<
+fn __action45<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, String, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    Box::new(Expr::Id(__0))
+    UnaryOp::Round
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action17<
+fn __action46<
     'input,
 >(
-    input: &'input str,
-    (_, _, _): (usize, &'input str, usize),
-    (_, __0, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> UnaryOp
 {
-    __0
+    UnaryOp::ToFloat
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action18<
+fn __action47<
     'input,
 >(
-    input: &'input str,
-    (_, _, _): (usize, &'input str, usize),
-    (_, exprs, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
-    (_, last, _): (usize, Option<Box<Expr>>, usize),
-    (_, _, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, Tok<'input>, usize),
+) -> TernaryOp
 {
-    {
-        let mut all_exprs = exprs;
-        if let Some(last_expr) = last {
-            all_exprs.push(last_expr);
-        }
-        Box::new(Expr::Do(all_exprs))
-    }
+    TernaryOp::VectorSet
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action19<
+fn __action48<
     'input,
 >(
-    input: &'input str,
-    (_, op, _): (usize, UnaryOp, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, e, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, i64, usize),
+) -> i64
 {
-    Box::new(Expr::UnOp(op, e))
+    __0
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action20<
+fn __action49<
     'input,
 >(
-    input: &'input str,
-    (_, op, _): (usize, TernaryOp, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, e1, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, e2, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-    (_, e3, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    (_, __0, _): (usize, &'input str, usize),
+) -> String
 {
-    Box::new(Expr::TriOp(op, e1, e2, e3))
+    __0.to_string()
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action21<
+fn __action50<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> BinaryOp
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> Option<Box<Expr>>
 {
-    BinaryOp::Eq
+    Some(__0)
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action22<
+fn __action51<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> BinaryOp
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> Option<Box<Expr>>
 {
-    BinaryOp::Lt
+    None
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action23<
+fn __action52<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> BinaryOp
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    BinaryOp::Add
+    alloc::vec![]
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action24<
+fn __action53<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> BinaryOp
+    (_, v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    BinaryOp::Sub
+    v
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action25<
+fn __action54<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, __0, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
 {
-    UnaryOp::Add1
+    __0
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action26<
+fn __action55<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, mut v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    (_, e, _): (usize, Option<Box<Expr>>, usize),
+) -> Vec<Box<Expr>>
 {
-    UnaryOp::Sub1
+    match e {
+        None => v,
+        Some(e) => {
+            v.push(e);
+            v
+        }
+    }
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action27<
+fn __action56<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, mut v, _): (usize, alloc::vec::Vec<String>, usize),
+    (_, e, _): (usize, Option<String>, usize),
+) -> Vec<String>
 {
-    UnaryOp::IsZero
+    match e {
+        None => v,
+        Some(e) => {
+            v.push(e);
+            v
+        }
+    }
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action28<
+fn __action57<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, __0, _): (usize, String, usize),
+) -> Option<String>
 {
-    UnaryOp::IsNum
+    Some(__0)
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action29<
+fn __action58<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> Option<String>
 {
-    UnaryOp::Not
+    None
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action30<
+fn __action59<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> alloc::vec::Vec<String>
 {
-    UnaryOp::IsPair
+    alloc::vec![]
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action31<
+fn __action60<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, v, _): (usize, alloc::vec::Vec<String>, usize),
+) -> alloc::vec::Vec<String>
 {
-    UnaryOp::Left
+    v
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action32<
+fn __action61<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, __0, _): (usize, String, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> String
 {
-    UnaryOp::Right
+    __0
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action33<
+fn __action62<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    UnaryOp::IsList
+    alloc::vec![]
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action34<
+fn __action63<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    UnaryOp::IsVector
+    v
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action35<
+fn __action64<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> UnaryOp
+    (_, __0, _): (usize, Box<Expr>, usize),
+    (_, _, _): (usize, Tok<'input>, usize),
+) -> Box<Expr>
 {
-    UnaryOp::VectorLength
+    __0
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action36<
+fn __action65<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> TernaryOp
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    TernaryOp::VectorSet
+    alloc::vec![__0]
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action37<
+fn __action66<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> i64
+    (_, v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    (_, e, _): (usize, Box<Expr>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    i64::from_str(__0).unwrap()
+    { let mut v = v; v.push(e); v }
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action38<
+fn __action67<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, &'input str, usize),
-) -> String
+    (_, __0, _): (usize, Box<Expr>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    __0.to_string()
+    alloc::vec![__0]
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action39<
+fn __action68<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
-) -> Option<Box<Expr>>
+    (_, v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    (_, e, _): (usize, Box<Expr>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    Some(__0)
+    { let mut v = v; v.push(e); v }
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action40<
+fn __action69<
     'input,
 >(
-    input: &'input str,
-    __lookbehind: &usize,
-    __lookahead: &usize,
-) -> Option<Box<Expr>>
+    (_, __0, _): (usize, String, usize),
+) -> alloc::vec::Vec<String>
 {
-    None
+    alloc::vec![__0]
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action41<
+fn __action70<
     'input,
 >(
-    input: &'input str,
-    __lookbehind: &usize,
-    __lookahead: &usize,
-) -> alloc::vec::Vec<Box<Expr>>
+    (_, v, _): (usize, alloc::vec::Vec<String>, usize),
+    (_, e, _): (usize, String, usize),
+) -> alloc::vec::Vec<String>
 {
-    alloc::vec![]
+    { let mut v = v; v.push(e); v }
 }
 
-#[allow(unused_variables)]
-#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action42<
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action71<
     'input,
 >(
-    input: &'input str,
-    (_, v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    __0: (usize, Box<Expr>, usize),
+    __1: (usize, Tok<'input>, usize),
 ) -> alloc::vec::Vec<Box<Expr>>
 {
-    v
+    let __start0 = __0.0;
+    let __end0 = __1.2;
+    let __temp0 = __action64(
+        __0,
+        __1,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action67(
+        __temp0,
+    )
 }
 
-#[allow(unused_variables)]
-#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action43<
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action72<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
-    (_, _, _): (usize, &'input str, usize),
-) -> Box<Expr>
+    __0: (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    __1: (usize, Box<Expr>, usize),
+    __2: (usize, Tok<'input>, usize),
+) -> alloc::vec::Vec<Box<Expr>>
 {
-    __0
+    let __start0 = __1.0;
+    let __end0 = __2.2;
+    let __temp0 = __action64(
+        __1,
+        __2,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action68(
+        __0,
+        __temp0,
+    )
 }
 
-#[allow(unused_variables)]
-#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action44<
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action73<
     'input,
 >(
-    input: &'input str,
-    (_, __0, _): (usize, Box<Expr>, usize),
-) -> alloc::vec::Vec<Box<Expr>>
+    __0: (usize, Option<Box<Expr>>, usize),
+) -> Vec<Box<Expr>>
 {
-    alloc::vec![__0]
+    let __start0 = __0.0;
+    let __end0 = __0.0;
+    let __temp0 = __action62(
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action55(
+        __temp0,
+        __0,
+    )
 }
 
-#[allow(unused_variables)]
-#[allow(clippy::too_many_arguments, clippy::needless_lifetimes, clippy::just_underscores_and_digits)]
-fn __action45<
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action74<
     'input,
 >(
-    input: &'input str,
-    (_, v, _): (usize, alloc::vec::Vec<Box<Expr>>, usize),
-    (_, e, _): (usize, Box<Expr>, usize),
-) -> alloc::vec::Vec<Box<Expr>>
+    __0: (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    __1: (usize, Option<Box<Expr>>, usize),
+) -> Vec<Box<Expr>>
 {
-    { let mut v = v; v.push(e); v }
+    let __start0 = __0.0;
+    let __end0 = __0.2;
+    let __temp0 = __action63(
+        __0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action55(
+        __temp0,
+        __1,
+    )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action46<
+fn __action75<
     'input,
 >(
-    input: &'input str,
     __0: (usize, Box<Expr>, usize),
-    __1: (usize, &'input str, usize),
+    __1: (usize, Tok<'input>, usize),
 ) -> alloc::vec::Vec<Box<Expr>>
 {
     let __start0 = __0.0;
     let __end0 = __1.2;
-    let __temp0 = __action43(
-        input,
+    let __temp0 = __action54(
         __0,
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action44(
-        input,
+    __action65(
         __temp0,
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action47<
+fn __action76<
     'input,
 >(
-    input: &'input str,
     __0: (usize, alloc::vec::Vec<Box<Expr>>, usize),
     __1: (usize, Box<Expr>, usize),
-    __2: (usize, &'input str, usize),
+    __2: (usize, Tok<'input>, usize),
 ) -> alloc::vec::Vec<Box<Expr>>
 {
     let __start0 = __1.0;
     let __end0 = __2.2;
-    let __temp0 = __action43(
-        input,
+    let __temp0 = __action54(
         __1,
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action45(
-        input,
+    __action66(
         __0,
         __temp0,
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action48<
+fn __action77<
     'input,
 >(
-    input: &'input str,
-    __0: (usize, &'input str, usize),
+    __0: (usize, Tok<'input>, usize),
     __1: (usize, Option<Box<Expr>>, usize),
-    __2: (usize, &'input str, usize),
+    __2: (usize, Tok<'input>, usize),
 ) -> Box<Expr>
 {
     let __start0 = __0.2;
     let __end0 = __1.0;
-    let __temp0 = __action41(
-        input,
+    let __temp0 = __action52(
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action18(
-        input,
+    __action22(
         __0,
         __temp0,
         __1,
@@ -2875,28 +4093,24 @@ fn __action48<
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action49<
+fn __action78<
     'input,
 >(
-    input: &'input str,
-    __0: (usize, &'input str, usize),
+    __0: (usize, Tok<'input>, usize),
     __1: (usize, alloc::vec::Vec<Box<Expr>>, usize),
     __2: (usize, Option<Box<Expr>>, usize),
-    __3: (usize, &'input str, usize),
+    __3: (usize, Tok<'input>, usize),
 ) -> Box<Expr>
 {
     let __start0 = __1.0;
     let __end0 = __1.2;
-    let __temp0 = __action42(
-        input,
+    let __temp0 = __action53(
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action18(
-        input,
+    __action22(
         __0,
         __temp0,
         __2,
@@ -2904,82 +4118,238 @@ fn __action49<
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action50<
+fn __action79<
+    'input,
+>(
+    __0: (usize, String, usize),
+    __1: (usize, Tok<'input>, usize),
+) -> alloc::vec::Vec<String>
+{
+    let __start0 = __0.0;
+    let __end0 = __1.2;
+    let __temp0 = __action61(
+        __0,
+        __1,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action69(
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action80<
+    'input,
+>(
+    __0: (usize, alloc::vec::Vec<String>, usize),
+    __1: (usize, String, usize),
+    __2: (usize, Tok<'input>, usize),
+) -> alloc::vec::Vec<String>
+{
+    let __start0 = __1.0;
+    let __end0 = __2.2;
+    let __temp0 = __action61(
+        __1,
+        __2,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action70(
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action81<
+    'input,
+>(
+    __0: (usize, Option<String>, usize),
+) -> Vec<String>
+{
+    let __start0 = __0.0;
+    let __end0 = __0.0;
+    let __temp0 = __action59(
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action56(
+        __temp0,
+        __0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action82<
+    'input,
+>(
+    __0: (usize, alloc::vec::Vec<String>, usize),
+    __1: (usize, Option<String>, usize),
+) -> Vec<String>
+{
+    let __start0 = __0.0;
+    let __end0 = __0.2;
+    let __temp0 = __action60(
+        __0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action56(
+        __temp0,
+        __1,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action83<
+    'input,
+>(
+    __0: (usize, Box<Expr>, usize),
+) -> Vec<Box<Expr>>
+{
+    let __start0 = __0.0;
+    let __end0 = __0.2;
+    let __temp0 = __action50(
+        __0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action73(
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action84<
+    'input,
+>(
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> Vec<Box<Expr>>
+{
+    let __start0 = *__lookbehind;
+    let __end0 = *__lookahead;
+    let __temp0 = __action51(
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action73(
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action85<
+    'input,
+>(
+    __0: (usize, alloc::vec::Vec<Box<Expr>>, usize),
+    __1: (usize, Box<Expr>, usize),
+) -> Vec<Box<Expr>>
+{
+    let __start0 = __1.0;
+    let __end0 = __1.2;
+    let __temp0 = __action50(
+        __1,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action74(
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action86<
+    'input,
+>(
+    __0: (usize, alloc::vec::Vec<Box<Expr>>, usize),
+) -> Vec<Box<Expr>>
+{
+    let __start0 = __0.2;
+    let __end0 = __0.2;
+    let __temp0 = __action51(
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action74(
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action87<
     'input,
 >(
-    input: &'input str,
-    __0: (usize, &'input str, usize),
+    __0: (usize, Tok<'input>, usize),
     __1: (usize, Box<Expr>, usize),
-    __2: (usize, &'input str, usize),
+    __2: (usize, Tok<'input>, usize),
 ) -> Box<Expr>
 {
     let __start0 = __1.0;
     let __end0 = __1.2;
-    let __temp0 = __action39(
-        input,
+    let __temp0 = __action50(
         __1,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action48(
-        input,
+    __action77(
         __0,
         __temp0,
         __2,
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action51<
+fn __action88<
     'input,
 >(
-    input: &'input str,
-    __0: (usize, &'input str, usize),
-    __1: (usize, &'input str, usize),
+    __0: (usize, Tok<'input>, usize),
+    __1: (usize, Tok<'input>, usize),
 ) -> Box<Expr>
 {
     let __start0 = __0.2;
     let __end0 = __1.0;
-    let __temp0 = __action40(
-        input,
+    let __temp0 = __action51(
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action48(
-        input,
+    __action77(
         __0,
         __temp0,
         __1,
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action52<
+fn __action89<
     'input,
 >(
-    input: &'input str,
-    __0: (usize, &'input str, usize),
+    __0: (usize, Tok<'input>, usize),
     __1: (usize, alloc::vec::Vec<Box<Expr>>, usize),
     __2: (usize, Box<Expr>, usize),
-    __3: (usize, &'input str, usize),
+    __3: (usize, Tok<'input>, usize),
 ) -> Box<Expr>
 {
     let __start0 = __2.0;
     let __end0 = __2.2;
-    let __temp0 = __action39(
-        input,
+    let __temp0 = __action50(
         __2,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action49(
-        input,
+    __action78(
         __0,
         __1,
         __temp0,
@@ -2987,28 +4357,24 @@ fn __action52<
     )
 }
 
-#[allow(unused_variables)]
 #[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
     clippy::just_underscores_and_digits)]
-fn __action53<
+fn __action90<
     'input,
 >(
-    input: &'input str,
-    __0: (usize, &'input str, usize),
+    __0: (usize, Tok<'input>, usize),
     __1: (usize, alloc::vec::Vec<Box<Expr>>, usize),
-    __2: (usize, &'input str, usize),
+    __2: (usize, Tok<'input>, usize),
 ) -> Box<Expr>
 {
     let __start0 = __1.2;
     let __end0 = __2.0;
-    let __temp0 = __action40(
-        input,
+    let __temp0 = __action51(
         &__start0,
         &__end0,
     );
     let __temp0 = (__start0, __temp0, __end0);
-    __action49(
-        input,
+    __action78(
         __0,
         __1,
         __temp0,
@@ -3016,21 +4382,103 @@ fn __action53<
     )
 }
 
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action91<
+    'input,
+>(
+    __0: (usize, String, usize),
+) -> Vec<String>
+{
+    let __start0 = __0.0;
+    let __end0 = __0.2;
+    let __temp0 = __action57(
+        __0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action81(
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action92<
+    'input,
+>(
+    __lookbehind: &usize,
+    __lookahead: &usize,
+) -> Vec<String>
+{
+    let __start0 = *__lookbehind;
+    let __end0 = *__lookahead;
+    let __temp0 = __action58(
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action81(
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action93<
+    'input,
+>(
+    __0: (usize, alloc::vec::Vec<String>, usize),
+    __1: (usize, String, usize),
+) -> Vec<String>
+{
+    let __start0 = __1.0;
+    let __end0 = __1.2;
+    let __temp0 = __action57(
+        __1,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action82(
+        __0,
+        __temp0,
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::needless_lifetimes,
+    clippy::just_underscores_and_digits)]
+fn __action94<
+    'input,
+>(
+    __0: (usize, alloc::vec::Vec<String>, usize),
+) -> Vec<String>
+{
+    let __start0 = __0.2;
+    let __end0 = __0.2;
+    let __temp0 = __action58(
+        &__start0,
+        &__end0,
+    );
+    let __temp0 = (__start0, __temp0, __end0);
+    __action82(
+        __0,
+        __temp0,
+    )
+}
+
 #[allow(clippy::type_complexity, dead_code)]
-pub  trait __ToTriple<'input, >
+pub trait __ToTriple<'input, >
 {
-    fn to_triple(self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>>;
+    fn to_triple(self) -> Result<(usize,Tok<'input>,usize), __lalrpop_util::ParseError<usize, Tok<'input>, LexicalError>>;
 }
 
-impl<'input, > __ToTriple<'input, > for (usize, Token<'input>, usize)
+impl<'input, > __ToTriple<'input, > for (usize, Tok<'input>, usize)
 {
-    fn to_triple(self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>> {
+    fn to_triple(self) -> Result<(usize,Tok<'input>,usize), __lalrpop_util::ParseError<usize, Tok<'input>, LexicalError>> {
         Ok(self)
     }
 }
-impl<'input, > __ToTriple<'input, > for Result<(usize, Token<'input>, usize), &'static str>
+impl<'input, > __ToTriple<'input, > for Result<(usize, Tok<'input>, usize), LexicalError>
 {
-    fn to_triple(self) -> Result<(usize,Token<'input>,usize), __lalrpop_util::ParseError<usize, Token<'input>, &'static str>> {
+    fn to_triple(self) -> Result<(usize,Tok<'input>,usize), __lalrpop_util::ParseError<usize, Tok<'input>, LexicalError>> {
         self.map_err(|error| __lalrpop_util::ParseError::User { error })
     }
 }
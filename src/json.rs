@@ -0,0 +1,325 @@
+use std::fmt;
+
+/// Just enough JSON for machine-readable diagnostics and the language server protocol
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Json {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) if n.is_finite() => write!(f, "{}", n),
+            Json::Number(_) => write!(f, "null"),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn error<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!("{} at byte {}", what, self.at))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.at..];
+        self.at += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.at..].chars().next()
+    }
+
+    fn eat(&mut self, literal: &str) -> bool {
+        if self.text[self.at..].starts_with(literal) {
+            self.at += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.eat(literal) {
+            Ok(())
+        } else {
+            self.error(&format!("expected `{}`", literal))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') if self.eat("null") => Ok(Json::Null),
+            Some('t') if self.eat("true") => Ok(Json::Bool(true)),
+            Some('f') if self.eat("false") => Ok(Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => {
+                self.at += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.eat("]") {
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    if self.eat("]") {
+                        return Ok(Json::Array(items));
+                    }
+                    self.expect(",")?;
+                }
+            }
+            Some('{') => {
+                self.at += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.eat("}") {
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    if self.eat("}") {
+                        return Ok(Json::Object(fields));
+                    }
+                    self.expect(",")?;
+                }
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.at;
+                let rest = &self.text[start..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                    .unwrap_or(rest.len());
+                self.at += len;
+                match rest[..len].parse() {
+                    Ok(n) => Ok(Json::Number(n)),
+                    Err(_) => self.error("invalid number"),
+                }
+            }
+            _ => self.error("expected a value"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.eat("\"") {
+            return self.error("expected a string");
+        }
+        let mut out = String::new();
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => return self.error("unterminated string"),
+            };
+            self.at += c.len_utf8();
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escape = self.peek().unwrap_or(' ');
+                    self.at += 1;
+                    match escape {
+                        '"' | '\\' | '/' => out.push(escape),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let mut unit = self.hex4()?;
+                            // A surrogate pair spells one character outside the basic plane
+                            if (0xd800..0xdc00).contains(&unit) && self.eat("\\u") {
+                                let low = self.hex4()?;
+                                unit = 0x10000
+                                    + ((unit - 0xd800) << 10)
+                                    + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            out.push(char::from_u32(unit).unwrap_or('\u{fffd}'));
+                        }
+                        _ => return self.error("invalid escape"),
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits = self.text.get(self.at..self.at + 4).unwrap_or("");
+        match u32::from_str_radix(digits, 16) {
+            Ok(unit) if digits.len() == 4 => {
+                self.at += 4;
+                Ok(unit)
+            }
+            _ => self.error("invalid \\u escape"),
+        }
+    }
+}
+
+/// Parses one JSON document
+pub fn parse(text: &str) -> Result<Json, String> {
+    let mut parser = Parser { text, at: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.at == text.len() {
+        Ok(value)
+    } else {
+        parser.error("trailing characters")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_escape_and_read_back() {
+        let text = "say \"hi\"\\\n\t\u{1}é𝄞";
+        let json = Json::object(vec![("text", text.into()), ("none", Json::Null)]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"text":"say \"hi\"\\\n\t\u0001é𝄞","none":null}"#
+        );
+        assert_eq!(parse(&json.to_string()).unwrap(), json);
+        assert_eq!(parse(r#""𝄞é\/""#).unwrap(), Json::from("𝄞é/"));
+    }
+
+    #[test]
+    fn diagnostics_read_back() {
+        let source = "let x = 1 in\n  add1(y)";
+        let (_, diagnostics) = crate::analyze(source);
+        for diagnostic in &diagnostics.0 {
+            let json = diagnostic.to_json("main.lisp", source);
+            assert_eq!(parse(&json.to_string()).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn malformed_documents() {
+        assert_eq!(parse("[1,").unwrap_err(), "expected a value at byte 3");
+        assert_eq!(parse(r#"{"a" 1}"#).unwrap_err(), "expected `:` at byte 5");
+        assert_eq!(
+            parse(r#""abc"#).unwrap_err(),
+            "unterminated string at byte 4"
+        );
+        assert_eq!(parse("1 2").unwrap_err(), "trailing characters at byte 2");
+    }
+}
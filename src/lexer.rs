@@ -0,0 +1,451 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+use crate::ast::float_literal;
+use crate::compile::{NUM_MAX, NUM_MIN};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tok<'input> {
+    Let,
+    In,
+    If,
+    Else,
+    Def,
+    True,
+    False,
+    Add1,
+    Sub1,
+    IsZero,
+    IsNum,
+    IsPair,
+    Left,
+    Right,
+    IsList,
+    IsVector,
+    VectorLength,
+    Pair,
+    Vector,
+    VectorGet,
+    VectorSet,
+    Floor,
+    Round,
+    ToFloat,
+    Num(i64),
+    Float(f64),
+    Id(&'input str),
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Comma,
+    Semicolon,
+    Assign,
+    EqEq,
+    Lt,
+    Plus,
+    Minus,
+    Bang,
+    /// Only produced by `Lexer::with_comments`; the text includes the delimiters
+    Comment(&'input str),
+}
+
+/// Words the grammar reserves, which never name a variable or function
+pub const KEYWORDS: &[(&str, Tok<'static>)] = &[
+    ("let", Tok::Let),
+    ("in", Tok::In),
+    ("if", Tok::If),
+    ("else", Tok::Else),
+    ("def", Tok::Def),
+    ("true", Tok::True),
+    ("false", Tok::False),
+    ("add1", Tok::Add1),
+    ("sub1", Tok::Sub1),
+    ("isZero", Tok::IsZero),
+    ("isNum", Tok::IsNum),
+    ("isPair", Tok::IsPair),
+    ("left", Tok::Left),
+    ("right", Tok::Right),
+    ("isList", Tok::IsList),
+    ("isVector", Tok::IsVector),
+    ("vectorLength", Tok::VectorLength),
+    ("pair", Tok::Pair),
+    ("vector", Tok::Vector),
+    ("vectorGet", Tok::VectorGet),
+    ("vectorSet", Tok::VectorSet),
+    ("floor", Tok::Floor),
+    ("round", Tok::Round),
+    ("toFloat", Tok::ToFloat),
+];
+
+impl fmt::Display for Tok<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Tok::Num(n) => return write!(f, "{}", n),
+            Tok::Float(x) => return write!(f, "{}", float_literal(*x)),
+            Tok::Id(s) | Tok::Comment(s) => s,
+            Tok::LParen => "(",
+            Tok::RParen => ")",
+            Tok::LBrace => "{",
+            Tok::RBrace => "}",
+            Tok::Comma => ",",
+            Tok::Semicolon => ";",
+            Tok::Assign => "=",
+            Tok::EqEq => "==",
+            Tok::Lt => "<",
+            Tok::Plus => "+",
+            Tok::Minus => "-",
+            Tok::Bang => "!",
+            keyword => {
+                let (word, _) = KEYWORDS.iter().find(|(_, k)| k == keyword).unwrap();
+                word
+            }
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LexicalError {
+    InvalidCharacter {
+        at: usize,
+        c: char,
+    },
+    /// A `/*` without its `*/`
+    UnterminatedComment {
+        at: usize,
+    },
+    /// Digits the literal's base does not allow, or a prefix with no digits after it
+    MalformedNumber {
+        span: Range<usize>,
+    },
+    /// A number that does not fit in a tagged word
+    NumberOutOfRange {
+        span: Range<usize>,
+    },
+    /// A float too large for a double
+    FloatOutOfRange {
+        span: Range<usize>,
+    },
+}
+
+impl LexicalError {
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            LexicalError::InvalidCharacter { at, c } => *at..at + c.len_utf8(),
+            LexicalError::UnterminatedComment { at } => *at..at + 2,
+            LexicalError::MalformedNumber { span }
+            | LexicalError::NumberOutOfRange { span }
+            | LexicalError::FloatOutOfRange { span } => span.clone(),
+        }
+    }
+}
+
+impl fmt::Display for LexicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexicalError::InvalidCharacter { c, .. } => write!(f, "unexpected character `{}`", c),
+            LexicalError::UnterminatedComment { .. } => write!(f, "unterminated block comment"),
+            LexicalError::MalformedNumber { .. } => write!(f, "malformed number"),
+            LexicalError::NumberOutOfRange { .. } => write!(f, "number out of range"),
+            LexicalError::FloatOutOfRange { .. } => write!(f, "float out of range"),
+        }
+    }
+}
+
+pub type Spanned<'input> = Result<(usize, Tok<'input>, usize), LexicalError>;
+
+/// Whether every `_` in a literal's digits sits between two digits, so `1_000` is allowed but
+/// `1_`, `1__0` and `1_.5` are not
+fn separated(digits: &str, radix: u32) -> bool {
+    let chars: Vec<char> = digits.chars().collect();
+    chars.iter().enumerate().all(|(i, c)| {
+        *c != '_'
+            || (i > 0
+                && i + 1 < chars.len()
+                && chars[i - 1].is_digit(radix)
+                && chars[i + 1].is_digit(radix))
+    })
+}
+
+/// The value of a literal such as `-42`, `1_000`, `0xff`, `0b1010`, `2.5` or `1e-3`
+fn number(text: &str, span: Range<usize>) -> Result<Tok<'static>, LexicalError> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x" | "0X") => (16, &unsigned[2..]),
+        Some("0b" | "0B") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+    if !separated(digits, radix) {
+        return Err(LexicalError::MalformedNumber { span });
+    }
+    if radix == 10 && digits.contains(['.', 'e', 'E']) {
+        return match text.replace('_', "").parse::<f64>() {
+            Ok(x) if x.is_finite() => Ok(Tok::Float(x)),
+            Ok(_) => Err(LexicalError::FloatOutOfRange { span }),
+            Err(_) => Err(LexicalError::MalformedNumber { span }),
+        };
+    }
+    let digits = digits.replace('_', "");
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(LexicalError::MalformedNumber { span });
+    }
+    // Anything too long for an i128 is out of range anyway
+    let magnitude = i128::from_str_radix(&digits, radix).unwrap_or(i128::MAX);
+    let value = if negative { -magnitude } else { magnitude };
+    if (NUM_MIN as i128..=NUM_MAX as i128).contains(&value) {
+        Ok(Tok::Num(value as i64))
+    } else {
+        Err(LexicalError::NumberOutOfRange { span })
+    }
+}
+
+/// Splits source into tokens with their byte offsets. Whitespace, newlines included, only
+/// separates tokens, so offsets always point into the original text.
+pub struct Lexer<'input> {
+    source: &'input str,
+    chars: Peekable<CharIndices<'input>>,
+    comments: bool,
+}
+
+impl<'input> Lexer<'input> {
+    pub fn new(source: &'input str) -> Self {
+        Lexer {
+            source,
+            chars: source.char_indices().peekable(),
+            comments: false,
+        }
+    }
+
+    /// A lexer that also yields comments, for tools that must keep them
+    pub fn with_comments(source: &'input str) -> Self {
+        Lexer {
+            comments: true,
+            ..Lexer::new(source)
+        }
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.source.len(), |(i, _)| *i)
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> usize {
+        while self.chars.next_if(|(_, c)| predicate(*c)).is_some() {}
+        self.offset()
+    }
+
+    /// Reads the rest of a number. Letters and underscores are taken in too, so `12ab` is one
+    /// malformed number rather than a number followed by a name.
+    fn number(&mut self, start: usize) -> Spanned<'input> {
+        let source = self.source;
+        let word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let mut end = self.take_while(word);
+        let hex = source[start..end]
+            .trim_start_matches('-')
+            .get(..2)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("0x"));
+        // A fraction, and a sign after the `e` of an exponent, continue a decimal literal
+        let rest = &source[end..];
+        if !hex && rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            self.chars.next();
+            end = self.take_while(word);
+        }
+        if !hex && source[start..end].ends_with(['e', 'E']) && source[end..].starts_with(['+', '-'])
+        {
+            self.chars.next();
+            end = self.take_while(word);
+        }
+        let tok = number(&self.source[start..end], start..end)?;
+        Ok((start, tok, end))
+    }
+
+    /// Skips a block comment after its opening `/*`; block comments nest
+    fn block_comment(&mut self, start: usize) -> Result<usize, LexicalError> {
+        let mut depth = 1;
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '/' if self.chars.next_if(|(_, c)| *c == '*').is_some() => depth += 1,
+                '*' if self.chars.next_if(|(_, c)| *c == '/').is_some() => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(self.offset());
+                    }
+                }
+                _ => (),
+            }
+        }
+        Err(LexicalError::UnterminatedComment { at: start })
+    }
+}
+
+impl<'input> Iterator for Lexer<'input> {
+    type Item = Spanned<'input>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, c) = self.chars.next()?;
+            let symbol = |tok: Tok<'input>| Some(Ok((start, tok, start + c.len_utf8())));
+            return match c {
+                c if c.is_whitespace() => continue,
+                '/' if self.chars.next_if(|(_, c)| *c == '/').is_some() => {
+                    let end = self.take_while(|c| c != '\n');
+                    if !self.comments {
+                        continue;
+                    }
+                    Some(Ok((start, Tok::Comment(&self.source[start..end]), end)))
+                }
+                '/' if self.chars.next_if(|(_, c)| *c == '*').is_some() => {
+                    match self.block_comment(start) {
+                        Ok(end) if self.comments => {
+                            Some(Ok((start, Tok::Comment(&self.source[start..end]), end)))
+                        }
+                        Ok(_) => continue,
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+                '(' => symbol(Tok::LParen),
+                ')' => symbol(Tok::RParen),
+                '{' => symbol(Tok::LBrace),
+                '}' => symbol(Tok::RBrace),
+                ',' => symbol(Tok::Comma),
+                ';' => symbol(Tok::Semicolon),
+                '<' => symbol(Tok::Lt),
+                '+' => symbol(Tok::Plus),
+                '!' => symbol(Tok::Bang),
+                '=' if self.chars.next_if(|(_, c)| *c == '=').is_some() => {
+                    Some(Ok((start, Tok::EqEq, start + 2)))
+                }
+                '=' => symbol(Tok::Assign),
+                // A minus sign directly before a digit belongs to the number, as `-1`
+                '-' if self.chars.peek().is_some_and(|(_, c)| c.is_ascii_digit()) => {
+                    Some(self.number(start))
+                }
+                '-' => symbol(Tok::Minus),
+                c if c.is_ascii_digit() => Some(self.number(start)),
+                c if c.is_ascii_alphabetic() => {
+                    let end = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                    let word = &self.source[start..end];
+                    let tok = KEYWORDS
+                        .iter()
+                        .find(|(k, _)| *k == word)
+                        .map_or(Tok::Id(word), |(_, tok)| *tok);
+                    Some(Ok((start, tok, end)))
+                }
+                c => Some(Err(LexicalError::InvalidCharacter { at: start, c })),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(source: &str) -> Vec<Tok<'_>> {
+        Lexer::new(source).map(|t| t.unwrap().1).collect()
+    }
+
+    fn error(source: &str) -> (String, Range<usize>) {
+        let e = Lexer::new(source).find_map(Result::err).unwrap();
+        (e.to_string(), e.span())
+    }
+
+    #[test]
+    fn comments() {
+        let source = "1 // line (\n/* block /* nested */ still */ 2 /**/";
+        assert_eq!(tokens(source), [Tok::Num(1), Tok::Num(2)]);
+        let comments: Vec<(usize, Tok<'_>, usize)> =
+            Lexer::with_comments(source).map(Result::unwrap).collect();
+        assert_eq!(
+            comments,
+            [
+                (0, Tok::Num(1), 1),
+                (2, Tok::Comment("// line ("), 11),
+                (12, Tok::Comment("/* block /* nested */ still */"), 42),
+                (43, Tok::Num(2), 44),
+                (45, Tok::Comment("/**/"), 49),
+            ]
+        );
+        assert_eq!(
+            error("1 /* /* */"),
+            ("unterminated block comment".to_string(), 2..4)
+        );
+    }
+
+    #[test]
+    fn symbols_and_words() {
+        assert_eq!(
+            tokens("x==y=-z - 1<!add1_x"),
+            [
+                Tok::Id("x"),
+                Tok::EqEq,
+                Tok::Id("y"),
+                Tok::Assign,
+                Tok::Minus,
+                Tok::Id("z"),
+                Tok::Minus,
+                Tok::Num(1),
+                Tok::Lt,
+                Tok::Bang,
+                Tok::Id("add1_x"),
+            ]
+        );
+        assert_eq!(tokens("x-1"), [Tok::Id("x"), Tok::Num(-1)]);
+        assert_eq!(
+            error("1 # 2"),
+            ("unexpected character `#`".to_string(), 2..3)
+        );
+        assert_eq!(error("é"), ("unexpected character `é`".to_string(), 0..2));
+    }
+
+    #[test]
+    fn numbers() {
+        let cases = [
+            ("1_000", Tok::Num(1000)),
+            ("-0xf_f", Tok::Num(-255)),
+            ("0B1010", Tok::Num(10)),
+            ("0xAb_cD", Tok::Num(0xabcd)),
+            ("2305843009213693951", Tok::Num(NUM_MAX)),
+            ("-2305843009213693952", Tok::Num(NUM_MIN)),
+            ("2.5", Tok::Float(2.5)),
+            ("1_0.2_5", Tok::Float(10.25)),
+            ("1e-3", Tok::Float(0.001)),
+            ("-1.5E+2", Tok::Float(-150.0)),
+            ("0x1e", Tok::Num(30)),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(tokens(source), [expected], "{}", source);
+        }
+        // A dot not followed by a digit ends the number
+        assert_eq!(error("1."), ("unexpected character `.`".to_string(), 1..2));
+    }
+
+    #[test]
+    fn malformed_numbers() {
+        for source in [
+            "1_", "1__0", "1x", "0x", "0x_", "0b102", "12ab", "0x_ff", "1_.5", "1e_3", "1e", "0xg",
+        ] {
+            let source = format!("let x = {} in x", source);
+            let end = source.len() - " in x".len();
+            assert_eq!(
+                error(&source),
+                ("malformed number".to_string(), 8..end),
+                "{}",
+                source
+            );
+        }
+        assert_eq!(
+            error("2305843009213693952"),
+            ("number out of range".to_string(), 0..19)
+        );
+        assert_eq!(
+            error("-0x2000000000000001"),
+            ("number out of range".to_string(), 0..19)
+        );
+        assert_eq!(error("1e999"), ("float out of range".to_string(), 0..5));
+    }
+}
@@ -1 +1,96 @@
+pub mod anf;
+pub mod asm;
+pub mod assemble;
+pub mod ast;
+pub mod bignum;
+pub mod cfg;
+pub mod compile;
+pub mod corpus;
+pub mod dce;
+pub mod diagnostics;
+pub mod difftest;
+pub mod elf;
+pub mod encode;
+pub mod eval;
+pub mod fold;
+pub mod format;
+pub mod fuzz;
+pub mod generator;
+pub mod grammar;
+pub mod json;
+pub mod lexer;
+pub mod lsp;
+pub mod peephole;
+pub mod regalloc;
+pub mod repl;
+pub mod resolve;
+pub mod sim;
+mod utils;
 
+use asm::{string_of_directive_for, Directive, Target};
+use ast::Expr;
+
+pub use compile::Error;
+pub use diagnostics::{Diagnostic, Diagnostics};
+
+/// Settings that change the code `compile` produces
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub target: Target,
+    pub opt_level: u8,
+    /// Stop with an error when arithmetic leaves the range of tagged numbers, instead of
+    /// promoting the result to a bignum
+    pub overflow_checks: bool,
+    /// Clean up the generated instructions with `peephole::optimize`
+    pub peephole: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            target: Target::host(),
+            opt_level: 0,
+            overflow_checks: false,
+            peephole: true,
+        }
+    }
+}
+
+/// Parses a whole program
+pub fn parse(source: &str) -> Result<Expr, Diagnostics> {
+    grammar::ExprParser::new()
+        .parse(lexer::Lexer::new(source))
+        .map(|e| *e)
+        .map_err(|e| Diagnostics(vec![diagnostics::parse_error(source, e)]))
+}
+
+/// Parses a program and resolves its names, returning the tree if it parsed along with
+/// everything worth reporting about it
+pub fn analyze(source: &str) -> (Option<Expr>, Diagnostics) {
+    match parse(source) {
+        Ok(expr) => {
+            let resolution = resolve::resolve(source, &expr);
+            let diagnostics = Diagnostics(diagnostics::name_diagnostics(&resolution));
+            (Some(expr), diagnostics)
+        }
+        Err(diagnostics) => (None, diagnostics),
+    }
+}
+
+/// Compiles a program to the directives of an object with a global `lisp_entry`
+pub fn compile(expr: &Expr, options: &Options) -> Result<Vec<Directive>, Error> {
+    compile::compile_with(expr.clone(), options)
+}
+
+/// Prints directives as assembly for the host
+pub fn emit(directives: &[Directive]) -> String {
+    emit_for(directives, Target::host())
+}
+
+/// Prints directives as assembly for `target`
+pub fn emit_for(directives: &[Directive], target: Target) -> String {
+    directives
+        .iter()
+        .map(|d| format!("{}\n", string_of_directive_for(d, target)))
+        .collect()
+}
@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::json::{self, Json};
+use crate::resolve::{self, Kind, Resolution};
+
+/// The unary primitives offered as completions, with their signatures
+const UNARY_PRIMITIVES: &[(&str, &str)] = &[
+    ("add1", "add1(n): n + 1"),
+    ("sub1", "sub1(n): n - 1"),
+    ("isZero", "isZero(n): whether n is 0"),
+    ("isNum", "isNum(v): whether v is a number"),
+    ("isPair", "isPair(v): whether v is a pair"),
+    ("left", "left(p): the first element of a pair"),
+    ("right", "right(p): the second element of a pair"),
+    (
+        "isList",
+        "isList(v): whether v is a pair chain ending in ()",
+    ),
+    ("isVector", "isVector(v): whether v is a vector"),
+    (
+        "vectorLength",
+        "vectorLength(v): the number of elements of a vector",
+    ),
+    ("floor", "floor(n): the largest integer not above n"),
+    (
+        "round",
+        "round(n): the nearest integer to n, halves away from zero",
+    ),
+    ("toFloat", "toFloat(n): n as a float"),
+];
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_REQUEST: i64 = -32600;
+const PARSE_ERROR: i64 = -32700;
+
+/// Reads one `Content-Length` framed message, or `None` at end of input
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_message(writer: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// LSP counts characters in UTF-16 code units
+fn position(source: &str, offset: usize) -> Json {
+    let before = &source[..offset.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Json::object(vec![
+        ("line", before.matches('\n').count().into()),
+        (
+            "character",
+            before[line_start..].encode_utf16().count().into(),
+        ),
+    ])
+}
+
+fn range(source: &str, span: &Range<usize>) -> Json {
+    Json::object(vec![
+        ("start", position(source, span.start)),
+        ("end", position(source, span.end)),
+    ])
+}
+
+fn offset(source: &str, position: &Json) -> Option<usize> {
+    let line = position.get("line")?.as_usize()?;
+    let character = position.get("character")?.as_usize()?;
+    let line_start = match line {
+        0 => 0,
+        _ => source.match_indices('\n').nth(line - 1)?.0 + 1,
+    };
+    let mut units = 0;
+    for (i, c) in source[line_start..].char_indices() {
+        if units >= character || c == '\n' {
+            return Some(line_start + i);
+        }
+        units += c.len_utf16();
+    }
+    Some(source.len())
+}
+
+fn response(id: &Json, result: Json) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("id", id.clone()),
+        ("result", result),
+    ])
+}
+
+fn error_response(id: &Json, code: i64, message: &str) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("id", id.clone()),
+        (
+            "error",
+            Json::object(vec![
+                ("code", Json::Number(code as f64)),
+                ("message", message.into()),
+            ]),
+        ),
+    ])
+}
+
+fn notification(method: &str, params: Json) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("method", method.into()),
+        ("params", params),
+    ])
+}
+
+/// An open file, with its names resolved whenever it parses
+struct Document {
+    text: String,
+    resolution: Option<Resolution>,
+}
+
+fn lsp_diagnostic(uri: &str, text: &str, diagnostic: &Diagnostic) -> Json {
+    let span = diagnostic.primary_span().unwrap_or(0..0);
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+    };
+    let message = [vec![diagnostic.message.clone()], diagnostic.notes.clone()]
+        .concat()
+        .join("\n");
+    let related = diagnostic
+        .labels
+        .iter()
+        .filter(|l| !l.primary)
+        .map(|l| {
+            Json::object(vec![
+                (
+                    "location",
+                    Json::object(vec![("uri", uri.into()), ("range", range(text, &l.span))]),
+                ),
+                ("message", l.message.as_str().into()),
+            ])
+        })
+        .collect();
+    Json::object(vec![
+        ("range", range(text, &span)),
+        ("severity", Json::Number(severity as f64)),
+        ("code", diagnostic.code.map_or(Json::Null, Json::from)),
+        ("source", "asm".into()),
+        ("message", message.into()),
+        ("relatedInformation", Json::Array(related)),
+    ])
+}
+
+fn hover_text(definition: &resolve::Definition) -> String {
+    match definition.kind {
+        Kind::Variable => format!("let {}", definition.name),
+        Kind::Parameter => format!("(parameter) {}", definition.name),
+        Kind::Function => format!("def {}({})", definition.name, definition.params.join(", ")),
+    }
+}
+
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Server::default()
+    }
+
+    /// Stores the new text of a document and returns the diagnostics to publish for it
+    fn update(&mut self, uri: &str, text: String) -> Json {
+        let (expr, diagnostics) = crate::analyze(&text);
+        let resolution = expr.map(|e| resolve::resolve(&text, &e));
+        let published = diagnostics
+            .0
+            .iter()
+            .map(|d| lsp_diagnostic(uri, &text, d))
+            .collect();
+        self.documents
+            .insert(uri.to_string(), Document { text, resolution });
+        notification(
+            "textDocument/publishDiagnostics",
+            Json::object(vec![
+                ("uri", uri.into()),
+                ("diagnostics", Json::Array(published)),
+            ]),
+        )
+    }
+
+    /// The document and byte offset a `TextDocumentPositionParams` points at
+    fn locate<'a>(&'a self, params: &'a Json) -> Option<(&'a str, &'a Document, usize)> {
+        let uri = params.get("textDocument")?.get("uri")?.as_str()?;
+        let document = self.documents.get(uri)?;
+        let offset = offset(&document.text, params.get("position")?)?;
+        Some((uri, document, offset))
+    }
+
+    fn hover(&self, params: &Json) -> Option<Json> {
+        let (_, document, offset) = self.locate(params)?;
+        let (span, definition) = document.resolution.as_ref()?.definition_at(offset)?;
+        Some(Json::object(vec![
+            (
+                "contents",
+                Json::object(vec![
+                    ("kind", "markdown".into()),
+                    (
+                        "value",
+                        format!("```\n{}\n```", hover_text(definition)).into(),
+                    ),
+                ]),
+            ),
+            ("range", range(&document.text, &span)),
+        ]))
+    }
+
+    fn definition(&self, params: &Json) -> Option<Json> {
+        let (uri, document, offset) = self.locate(params)?;
+        let (_, definition) = document.resolution.as_ref()?.definition_at(offset)?;
+        Some(Json::object(vec![
+            ("uri", uri.into()),
+            ("range", range(&document.text, &definition.span)),
+        ]))
+    }
+
+    fn document_symbols(&self, params: &Json) -> Option<Json> {
+        let uri = params.get("textDocument")?.get("uri")?.as_str()?;
+        let document = self.documents.get(uri)?;
+        let symbols = document
+            .resolution
+            .as_ref()?
+            .definitions
+            .iter()
+            .filter(|d| d.kind == Kind::Function)
+            .map(|d| {
+                Json::object(vec![
+                    ("name", d.name.as_str().into()),
+                    ("detail", format!("({})", d.params.join(", ")).into()),
+                    ("kind", Json::Number(12.0)),
+                    ("range", range(&document.text, &d.span)),
+                    ("selectionRange", range(&document.text, &d.span)),
+                ])
+            })
+            .collect();
+        Some(Json::Array(symbols))
+    }
+
+    fn completion(&self) -> Json {
+        Json::Array(
+            UNARY_PRIMITIVES
+                .iter()
+                .map(|(label, detail)| {
+                    Json::object(vec![
+                        ("label", (*label).into()),
+                        ("kind", Json::Number(3.0)),
+                        ("detail", (*detail).into()),
+                    ])
+                })
+                .collect(),
+        )
+    }
+
+    fn request(&mut self, method: &str, params: &Json) -> Option<Json> {
+        match method {
+            "initialize" => Some(Json::object(vec![
+                (
+                    "capabilities",
+                    Json::object(vec![
+                        ("textDocumentSync", Json::Number(1.0)),
+                        ("hoverProvider", true.into()),
+                        ("definitionProvider", true.into()),
+                        ("documentSymbolProvider", true.into()),
+                        ("completionProvider", Json::object(vec![])),
+                    ]),
+                ),
+                ("serverInfo", Json::object(vec![("name", "asm-lsp".into())])),
+            ])),
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Json::Null)
+            }
+            "textDocument/hover" => Some(self.hover(params).unwrap_or(Json::Null)),
+            "textDocument/definition" => Some(self.definition(params).unwrap_or(Json::Null)),
+            "textDocument/documentSymbol" => {
+                Some(self.document_symbols(params).unwrap_or(Json::Array(vec![])))
+            }
+            "textDocument/completion" => Some(self.completion()),
+            _ => None,
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: &Json) -> Option<Json> {
+        let uri = params.get("textDocument")?.get("uri")?.as_str()?;
+        match method {
+            "textDocument/didOpen" => {
+                let text = params.get("textDocument")?.get("text")?.as_str()?;
+                Some(self.update(uri, text.to_string()))
+            }
+            // The server asks for full syncs, so the last change holds the whole text
+            "textDocument/didChange" => {
+                let text = params
+                    .get("contentChanges")?
+                    .as_array()?
+                    .last()?
+                    .get("text")?;
+                Some(self.update(uri, text.as_str()?.to_string()))
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                Some(notification(
+                    "textDocument/publishDiagnostics",
+                    Json::object(vec![
+                        ("uri", uri.into()),
+                        ("diagnostics", Json::Array(vec![])),
+                    ]),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Handles one message, returning what to send back
+    pub fn handle(&mut self, message: &Json) -> Option<Json> {
+        let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Json::Null);
+        match message.get("id") {
+            Some(id) if self.shutdown => Some(error_response(
+                id,
+                INVALID_REQUEST,
+                "the server is shutting down",
+            )),
+            Some(id) => Some(match self.request(method, &params) {
+                Some(result) => response(id, result),
+                None => error_response(id, METHOD_NOT_FOUND, &format!("unknown method {}", method)),
+            }),
+            None => self.notify(method, &params),
+        }
+    }
+}
+
+/// Serves one client until it sends `exit`, returning the process exit code the protocol asks for
+pub fn serve(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<i32> {
+    let mut server = Server::new();
+    while let Some(body) = read_message(reader)? {
+        let message = match json::parse(&body) {
+            Ok(message) => message,
+            Err(e) => {
+                write_message(writer, &error_response(&Json::Null, PARSE_ERROR, &e))?;
+                continue;
+            }
+        };
+        if message.get("method").and_then(|m| m.as_str()) == Some("exit") {
+            return Ok(if server.shutdown { 0 } else { 1 });
+        }
+        if let Some(reply) = server.handle(&message) {
+            write_message(writer, &reply)?;
+        }
+    }
+    Ok(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(body: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    /// Runs the server over the framed `requests`, returning its exit code and replies
+    fn session(requests: &[&str]) -> (i32, Vec<Json>) {
+        let input: String = requests.iter().map(|r| frame(r)).collect();
+        let mut output = Vec::new();
+        let code = serve(&mut input.as_bytes(), &mut output).unwrap();
+        let mut reader = output.as_slice();
+        let mut replies = Vec::new();
+        while let Some(body) = read_message(&mut reader).unwrap() {
+            replies.push(json::parse(&body).unwrap());
+        }
+        (code, replies)
+    }
+
+    fn open(text: &str) -> String {
+        Json::object(vec![
+            ("jsonrpc", "2.0".into()),
+            ("method", "textDocument/didOpen".into()),
+            (
+                "params",
+                Json::object(vec![(
+                    "textDocument",
+                    Json::object(vec![
+                        ("uri", "file:///a.lisp".into()),
+                        ("text", text.into()),
+                    ]),
+                )]),
+            ),
+        ])
+        .to_string()
+    }
+
+    #[test]
+    fn initialize_and_shut_down() {
+        let (code, replies) = session(&[
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"workspace/symbol","params":{}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+            r#"{"jsonrpc":"2.0","id":4,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","method":"exit"}"#,
+        ]);
+        assert_eq!(code, 0);
+        let replies: Vec<String> = replies.iter().map(|r| r.to_string()).collect();
+        assert_eq!(
+            replies,
+            [
+                concat!(
+                    r#"{"jsonrpc":"2.0","id":1,"result":{"capabilities":{"textDocumentSync":1,"#,
+                    r#""hoverProvider":true,"definitionProvider":true,"#,
+                    r#""documentSymbolProvider":true,"completionProvider":{}},"#,
+                    r#""serverInfo":{"name":"asm-lsp"}}}"#
+                ),
+                concat!(
+                    r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"#,
+                    r#""message":"unknown method workspace/symbol"}}"#
+                ),
+                r#"{"jsonrpc":"2.0","id":3,"result":null}"#,
+                concat!(
+                    r#"{"jsonrpc":"2.0","id":4,"error":{"code":-32600,"#,
+                    r#""message":"the server is shutting down"}}"#
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exit_without_shutdown_and_bad_json() {
+        let (code, replies) = session(&["{", r#"{"jsonrpc":"2.0","method":"exit"}"#]);
+        assert_eq!(code, 1);
+        assert_eq!(replies.len(), 1);
+        assert_eq!(
+            replies[0].get("error").unwrap().get("code"),
+            Some(&Json::Number(PARSE_ERROR as f64))
+        );
+    }
+
+    /// Positions count UTF-16 code units, so `𝄞` is two characters wide and `é` one
+    #[test]
+    fn published_diagnostics() {
+        let text = "/* 𝄞é */ add1(y)";
+        let (_, replies) = session(&[&open(text)]);
+        assert_eq!(
+            replies[0].to_string(),
+            concat!(
+                r#"{"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{"#,
+                r#""uri":"file:///a.lisp","diagnostics":[{"range":{"#,
+                r#""start":{"line":0,"character":15},"end":{"line":0,"character":16}},"#,
+                r#""severity":1,"code":"E0101","source":"asm","#,
+                r#""message":"unbound identifier `y`","relatedInformation":[]}]}}"#
+            )
+        );
+
+        let (_, replies) = session(&[&open("pair(1,\n  2")]);
+        let diagnostic = &replies[0]
+            .get("params")
+            .unwrap()
+            .get("diagnostics")
+            .unwrap()
+            .as_array()
+            .unwrap()[0];
+        assert_eq!(
+            diagnostic.get("message").unwrap().as_str(),
+            Some("unexpected end of input\nexpected `)`")
+        );
+        let related = &diagnostic
+            .get("relatedInformation")
+            .unwrap()
+            .as_array()
+            .unwrap()[0];
+        assert_eq!(
+            related
+                .get("location")
+                .unwrap()
+                .get("range")
+                .unwrap()
+                .to_string(),
+            r#"{"start":{"line":0,"character":4},"end":{"line":0,"character":5}}"#
+        );
+    }
+
+    #[test]
+    fn hover_converts_positions() {
+        let text = "let x = 1 in\n/* 𝄞 */ add1(x)";
+        let hover = r#"{"jsonrpc":"2.0","id":1,"method":"textDocument/hover","params":{
+            "textDocument":{"uri":"file:///a.lisp"},"position":{"line":1,"character":14}}}"#;
+        let (_, replies) = session(&[&open(text), hover]);
+        assert_eq!(
+            replies[1].get("result").unwrap().to_string(),
+            concat!(
+                r#"{"contents":{"kind":"markdown","value":"```\nlet x\n```"},"#,
+                r#""range":{"start":{"line":1,"character":14},"end":{"line":1,"character":15}}}"#
+            )
+        );
+        assert_eq!(
+            offset(text, &position(text, text.len() - 2)),
+            Some(text.len() - 2)
+        );
+    }
+}
@@ -1,17 +1,17 @@
-mod asm;
-mod assemble;
-pub mod ast;
-mod compile;
-pub mod grammar;
-mod utils;
-use asm::string_of_directive;
-use ast::*;
-use compile::compile;
+mod cli;
+use asm::asm::{Directive, Target};
+use asm::ast::Expr;
+use asm::diagnostics::{Diagnostic, Diagnostics, Severity};
+use asm::lexer::{Lexer, Tok};
+use asm::{assemble, emit_for};
+use asm::{corpus, difftest, encode, eval, fuzz, generator, repl, sim};
+use cli::{Emit, ErrorFormat, Options};
 use std::env;
 use std::fs::{self, File};
-use std::io::{self, Read};
-use std::path::Path;
-use std::ptr::hash;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
 fn read_file(path: &Path) -> io::Result<String> {
     let mut file = File::open(path)?;
@@ -20,56 +20,489 @@ fn read_file(path: &Path) -> io::Result<String> {
     Ok(contents)
 }
 
-fn parse_and_compile(contents: &str) -> Result<String, String> {
-    let parsed = grammar::ExprParser::new().parse(contents);
-    let mut output = String::new();
-    if let Ok(expr_vec) = parsed {
-        let directives = compile(*expr_vec);
-        for directive in directives {
-            output.push_str(&format!("{}\n", string_of_directive(&directive)));
-        }
-        Ok(output)
+/// Reads a source file, or stdin when the path is `-`
+fn read_source(path: &Path) -> Result<String, String> {
+    let contents = if path == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).map(|_| contents)
     } else {
-        Err("Failed to parse input".to_string())
+        read_file(path)
+    };
+    contents.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_output(output: Option<&Path>, contents: &str) -> Result<(), String> {
+    match output {
+        None => {
+            print!("{}", contents);
+            Ok(())
+        }
+        Some(path) => fs::write(path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e)),
     }
 }
 
-fn parse_file(path: &Path) -> Result<(), String> {
-    let contents = read_file(path).map_err(|e| format!("Error reading file: {}", e))?;
-    let cleaned = contents.replace('\n', "");
-    let output = parse_and_compile(&cleaned)?;
-    println!("{}", output);
+/// Writes an object file or executable. ELF objects are encoded in-process; on macOS the
+/// assembly goes through the system `cc`, since there is no Mach-O writer.
+fn build(
+    options: &Options,
+    emit: Emit,
+    directives: &[Directive],
+    output: &Path,
+) -> Result<(), String> {
+    let object_file = match emit {
+        Emit::Obj => output.to_path_buf(),
+        _ => output.with_extension("o"),
+    };
+    match options.target {
+        Target::Linux => assemble::assemble_object(&object_file, directives)?,
+        Target::MacOs => {
+            let asm_file = output.with_extension("s");
+            write_output(Some(&asm_file), &emit_for(directives, options.target))?;
+            let assembled = assemble::assemble_with_cc(&asm_file, &object_file);
+            let _ = fs::remove_file(&asm_file);
+            assembled?
+        }
+    }
+    if emit == Emit::Exe {
+        let linked = assemble::link(&object_file, &options.runtime, output);
+        let _ = fs::remove_file(&object_file);
+        linked?
+    }
     Ok(())
 }
 
-fn main() -> Result<(), String> {
-    let args: Vec<String> = env::args().collect();
+/// The source being compiled, kept to show excerpts of it in diagnostics
+struct Reporter {
+    file: String,
+    source: String,
+    format: ErrorFormat,
+    color: bool,
+}
+
+impl Reporter {
+    fn new(options: &Options) -> Self {
+        Reporter {
+            file: options.input.display().to_string(),
+            source: String::new(),
+            format: options.error_format,
+            color: io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+
+    fn report(&self, diagnostics: &Diagnostics) {
+        for diagnostic in &diagnostics.0 {
+            match self.format {
+                ErrorFormat::Human => {
+                    eprintln!(
+                        "{}",
+                        diagnostic.render(&self.file, &self.source, self.color)
+                    )
+                }
+                ErrorFormat::Json => eprintln!("{}", diagnostic.to_json(&self.file, &self.source)),
+            }
+        }
+    }
+
+    /// Reads and parses the input, reporting any warnings
+    fn load(&mut self, path: &Path) -> Result<Expr, Diagnostics> {
+        self.source = read_source(path).map_err(failure)?;
+        let (expr, diagnostics) = asm::analyze(&self.source);
+        match expr {
+            Some(expr) if !diagnostics.has_errors() => {
+                self.report(&diagnostics);
+                Ok(expr)
+            }
+            _ => Err(diagnostics),
+        }
+    }
+}
+
+/// A problem with no place in the source, such as a file that cannot be written
+fn failure(message: String) -> Diagnostics {
+    Diagnostics(vec![Diagnostic::new(Severity::Error, None, message)])
+}
 
-    if args.len() > 1 {
-        // If an argument is provided, compile the specified file
-        let input_file = Path::new(&args[1]);
-        return parse_file(input_file);
+/// `check`, `emit-ast`, `emit-asm` and `build`, which differ only in what they write out
+fn compile_command(command: &str, args: &[String]) -> Result<(), String> {
+    let options = cli::parse_options(args).unwrap_or_else(|e| usage_error(&e));
+    let mut reporter = Reporter::new(&options);
+    if let Err(diagnostics) = compile_file(command, &options, &mut reporter) {
+        reporter.report(&diagnostics);
+        process::exit(1);
     }
+    Ok(())
+}
+
+fn compile_file(
+    command: &str,
+    options: &Options,
+    reporter: &mut Reporter,
+) -> Result<(), Diagnostics> {
+    let emit = options.emit.or(match command {
+        "check" => None,
+        "emit-ast" => Some(Emit::Ast),
+        "emit-asm" => Some(Emit::Asm),
+        _ => Some(Emit::Exe),
+    });
+    let output = match (&options.output, emit) {
+        (Some(path), _) => Some(path.clone()),
+        (None, Some(emit)) if command == "build" => {
+            let stem = options.input.file_stem().unwrap_or("a".as_ref());
+            Some(PathBuf::from(stem).with_extension(emit.extension()))
+        }
+        _ => None,
+    };
 
-    // If no argument is provided, process all .lisp files in the samples directory
-    let samples_dir = Path::new("samples");
-    if !samples_dir.exists() {
-        return Err("Samples directory does not exist".to_string());
+    let expr = reporter.load(&options.input)?;
+    if emit == Some(Emit::Ast) {
+        return write_output(output.as_deref(), &format!("{:#?}\n", expr)).map_err(failure);
+    }
+    let compile_options = asm::Options {
+        target: options.target,
+        opt_level: options.opt_level,
+        overflow_checks: options.overflow_checks,
+        peephole: options.peephole,
+    };
+    if let Some(Emit::Anf | Emit::CfgDot) = emit {
+        let anf = asm::compile::lower(&expr, &compile_options).map_err(|e| {
+            Diagnostics(vec![asm::diagnostics::compile_error(&reporter.source, &e)])
+        })?;
+        let text = match emit {
+            Some(Emit::Anf) => anf.to_string(),
+            _ => asm::cfg::build(anf).to_dot(),
+        };
+        return write_output(output.as_deref(), &text).map_err(failure);
+    }
+    let directives = asm::compile(&expr, &compile_options)
+        .map_err(|e| Diagnostics(vec![asm::diagnostics::compile_error(&reporter.source, &e)]))?;
+    match emit {
+        None => encode::encode(&directives).map(|_| ()),
+        Some(Emit::Asm) => write_output(output.as_deref(), &emit_for(&directives, options.target)),
+        Some(emit) => build(options, emit, &directives, output.as_deref().unwrap()),
     }
+    .map_err(failure)
+}
 
-    let entries =
-        fs::read_dir(samples_dir).map_err(|e| format!("Error reading directory: {}", e))?;
+/// Executes the compiled program in the built-in AArch64 simulator,
+/// or with `--interp` evaluates the AST directly
+fn run_file(options: &Options) -> Result<(), String> {
+    let mut reporter = Reporter::new(options);
+    let result = reporter.load(&options.input).and_then(|expr| {
+        if options.interp {
+            let interpreter = if options.overflow_checks {
+                eval::Interpreter::with_overflow_checks()
+            } else {
+                eval::Interpreter::new()
+            };
+            eval::run_with(interpreter, &expr).map_err(failure)
+        } else {
+            let compile_options = asm::Options {
+                opt_level: options.opt_level,
+                overflow_checks: options.overflow_checks,
+                peephole: options.peephole,
+                ..asm::Options::default()
+            };
+            let directives = asm::compile(&expr, &compile_options).map_err(|e| {
+                Diagnostics(vec![asm::diagnostics::compile_error(&reporter.source, &e)])
+            })?;
+            sim::simulate(&directives).map_err(failure)
+        }
+    });
+    match result {
+        Ok(output) => {
+            print!("{}", output.stdout);
+            process::exit(output.exit_code);
+        }
+        Err(diagnostics) => {
+            reporter.report(&diagnostics);
+            process::exit(1);
+        }
+    }
+}
 
+fn sample_files_with(extension: &str) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir("samples").map_err(|e| format!("Error reading directory: {}", e))?;
+    let mut files = Vec::new();
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Error reading directory entry: {}", e))?;
-        let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("lisp") {
-            match parse_file(&path) {
-                Ok(_) => (),
-                Err(e) => eprintln!("Error parsing file {:?}: {}", path, e),
+        let path = entry
+            .map_err(|e| format!("Error reading directory entry: {}", e))?
+            .path();
+        if path.extension().and_then(|s| s.to_str()) == Some(extension) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn test(args: &[String]) -> Result<(), String> {
+    let mut files = Vec::new();
+    let mut timeout = Duration::from_secs(5);
+    let mut interp = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--interp" => interp = true,
+            "--timeout" => {
+                let secs = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or("--timeout expects a number of seconds")?;
+                timeout = Duration::from_secs_f64(secs);
             }
+            flag if flag.starts_with('-') => usage_error(&format!("unknown option {}", flag)),
+            file => files.push(Path::new(file).to_path_buf()),
         }
     }
+    if files.is_empty() {
+        files = sample_files_with("in")?;
+    }
 
+    let (mut passed, mut failed, mut unparseable) = (0, 0, 0);
+    for path in files {
+        let contents = read_file(&path).map_err(|e| format!("Error reading file: {}", e))?;
+        let cases = corpus::parse_corpus(&contents)
+            .map_err(|e| format!("Error reading corpus {}: {}", path.display(), e))?;
+        for case in cases {
+            match corpus::run_case(&case, timeout, interp) {
+                corpus::Outcome::Pass => passed += 1,
+                corpus::Outcome::Fail { actual } => {
+                    failed += 1;
+                    println!("FAIL {}: {}", path.display(), case.name);
+                    println!("  - expected: {}", case.expected);
+                    println!("  + actual:   {}", actual);
+                }
+                corpus::Outcome::Unparseable => {
+                    unparseable += 1;
+                    println!("UNPARSEABLE {}: {}", path.display(), case.name);
+                }
+                corpus::Outcome::Timeout => {
+                    failed += 1;
+                    println!(
+                        "FAIL {}: {} (timed out after {:?})",
+                        path.display(),
+                        case.name,
+                        timeout
+                    );
+                }
+            }
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} unparseable",
+        passed, failed, unparseable
+    );
+    if failed > 0 {
+        process::exit(1);
+    }
     Ok(())
 }
+
+fn difftest(args: &[String]) -> Result<(), String> {
+    let mut files = Vec::new();
+    let mut random = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--random" => {
+                random = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or("--random expects a number of programs")?;
+            }
+            flag if flag.starts_with('-') => usage_error(&format!("unknown option {}", flag)),
+            file => files.push(Path::new(file).to_path_buf()),
+        }
+    }
+    let paths = if files.is_empty() && random == 0 {
+        sample_files_with("lisp")?
+    } else {
+        files
+    };
+    let mut programs: Vec<(String, Expr)> = (0..random)
+        .map(|seed| {
+            (
+                format!("generated #{}", seed),
+                generator::compilable_program(seed, 5, true),
+            )
+        })
+        .collect();
+    for path in paths {
+        let contents = read_file(&path).map_err(|e| format!("Error reading file: {}", e))?;
+        match asm::parse(&contents) {
+            Ok(expr) => programs.push((path.display().to_string(), expr)),
+            Err(_) => eprintln!("Skipping {}: failed to parse input", path.display()),
+        }
+    }
+    let summary = difftest::check(&programs);
+    for (name, what) in &summary.unsupported {
+        eprintln!("Skipping {}: {}", name, what);
+    }
+    match summary.divergence {
+        None => {
+            println!(
+                "{} programs agree, {} unsupported by the compiler",
+                summary.agreed,
+                summary.unsupported.len()
+            );
+            Ok(())
+        }
+        Some((name, divergence)) => {
+            println!("{} diverges; minimized reproduction:", name);
+            println!("{}", divergence.report());
+            process::exit(1);
+        }
+    }
+}
+
+/// The comments of a program, in sorted order since formatting may move them
+fn comments(source: &str) -> Vec<String> {
+    let mut comments: Vec<String> = Lexer::with_comments(source)
+        .filter_map(|token| match token {
+            Ok((_, Tok::Comment(text), _)) => Some(text.trim_end().to_string()),
+            _ => None,
+        })
+        .collect();
+    comments.sort();
+    comments
+}
+
+/// Rewrites files in the canonical layout, or with `--check` only reports the ones that differ
+fn fmt(args: &[String]) -> Result<(), String> {
+    let mut files = Vec::new();
+    let mut check = false;
+    let mut width = asm::format::DEFAULT_WIDTH;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--width" => {
+                width = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or("--width expects a number of columns")?;
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                usage_error(&format!("unknown option {}", flag))
+            }
+            file => files.push(PathBuf::from(file)),
+        }
+    }
+    if files.is_empty() {
+        return Err("fmt: no input files (use - for stdin)".to_string());
+    }
+
+    let mut unformatted = 0;
+    for path in files {
+        let reporter = Reporter {
+            file: path.display().to_string(),
+            source: read_source(&path)?,
+            format: ErrorFormat::Human,
+            color: io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        };
+        let expr = match asm::parse(&reporter.source) {
+            Ok(expr) => expr,
+            Err(diagnostics) => {
+                reporter.report(&diagnostics);
+                process::exit(1);
+            }
+        };
+        let formatted = asm::format::format(&reporter.source, &expr, width);
+        // The layout must never change what the program means, nor lose a comment
+        match asm::parse(&formatted) {
+            Ok(reparsed)
+                if format!("{:?}", reparsed) == format!("{:?}", expr)
+                    && comments(&formatted) == comments(&reporter.source) => {}
+            _ => {
+                return Err(format!(
+                    "formatting {} changed its syntax tree or lost a comment",
+                    path.display()
+                ))
+            }
+        }
+        if check {
+            if formatted != reporter.source {
+                println!("{} is not formatted", path.display());
+                unformatted += 1;
+            }
+        } else if path == Path::new("-") {
+            print!("{}", formatted);
+        } else if formatted != reporter.source {
+            fs::write(&path, &formatted)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+    }
+    if unformatted > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn fuzz(args: &[String]) -> Result<(), String> {
+    let target = match args.first().map(|s| s.as_str()) {
+        Some("parser") => fuzz::Target::Parser,
+        Some("compile") => fuzz::Target::Compile,
+        _ => return Err("fuzz: expected a target, `parser` or `compile`".to_string()),
+    };
+    let mut iterations = 10_000;
+    let mut seed = 0;
+    let mut args = args[1..].iter();
+    while let Some(arg) = args.next() {
+        let value = args.next().and_then(|s| s.parse().ok());
+        match arg.as_str() {
+            "--iterations" => iterations = value.ok_or("--iterations expects a number")?,
+            "--seed" => seed = value.ok_or("--seed expects a number")?,
+            other => return Err(format!("fuzz: unknown option {}", other)),
+        }
+    }
+    match fuzz::run(target, iterations, seed) {
+        None => {
+            println!("{} inputs, no crashes", iterations);
+            Ok(())
+        }
+        Some(crash) => {
+            println!("crash: {}", crash.message);
+            println!("input: {:?}", String::from_utf8_lossy(&crash.input));
+            process::exit(1);
+        }
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, cli::USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let command = args.get(1).map(|s| s.as_str()).unwrap_or("");
+    let rest = if args.len() > 2 { &args[2..] } else { &[] };
+
+    let result = match command {
+        "check" | "emit-ast" | "emit-asm" | "build" => compile_command(command, rest),
+        "run" => run_file(&cli::parse_options(rest).unwrap_or_else(|e| usage_error(&e))),
+        // Run the `name|source|expected,` corpora, by default samples/*.in
+        "test" => test(rest),
+        // Read expressions from stdin, keeping definitions between inputs
+        "repl" => repl::run(),
+        // Compare the compiled code against the interpreter
+        "difftest" => difftest(rest),
+        // Throw random inputs at the parser or the compiler until one panics
+        "fuzz" => fuzz(rest),
+        // Pretty-print sources in the canonical layout
+        "fmt" => fmt(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        "" => usage_error("no command given"),
+        other => usage_error(&format!("unknown command {}", other)),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::asm::{
+    Directive::{self, *},
+    Operand::{self, *},
+    Register,
+};
+
+/// How many directives a rule sees at once
+const WINDOW: usize = 4;
+
+/// A rule looks at the directives from the current one on and, if it applies, says how many of
+/// them to replace and with what
+type Rule = fn(&[Directive]) -> Option<(usize, Vec<Directive>)>;
+
+const RULES: &[Rule] = &[
+    self_move,
+    move_back,
+    jump_to_next,
+    overwritten,
+    dead_store,
+    store_load,
+];
+
+/// Rewrites short runs of directives into cheaper equivalents, rule by rule, until none
+/// applies. Rules only look past instructions that fall through to the next, so code that
+/// can be reached by a jump in the middle of a window is never changed.
+pub fn optimize(code: &[Directive]) -> Vec<Directive> {
+    let mut code = code.to_vec();
+    loop {
+        let mut optimized = Vec::with_capacity(code.len());
+        let mut changed = false;
+        let mut i = 0;
+        while i < code.len() {
+            let window = &code[i..code.len().min(i + WINDOW)];
+            match RULES.iter().find_map(|rule| rule(window)) {
+                Some((replaced, replacement)) => {
+                    optimized.extend(replacement);
+                    i += replaced;
+                    changed = true;
+                }
+                None => {
+                    optimized.push(code[i].clone());
+                    i += 1;
+                }
+            }
+        }
+        if !changed {
+            return optimized;
+        }
+        code = optimized;
+    }
+}
+
+/// An instruction that always goes on to the next one and touches nothing but its operands and
+/// the flags
+fn straight(d: &Directive) -> bool {
+    matches!(
+        d,
+        Mov(..)
+            | Add(..)
+            | Sub(..)
+            | Adds(..)
+            | Subs(..)
+            | Mul(..)
+            | Sdiv(..)
+            | Lsl(..)
+            | Lsr(..)
+            | Asr(..)
+            | Cmp(..)
+            | And(..)
+            | Orr(..)
+            | Cset(..)
+            | Adr(..)
+            | Str(..)
+            | Ldr(..)
+            | Stp(..)
+            | Ldp(..)
+            | Comment(_)
+    )
+}
+
+/// An instruction whose only effect is to write one register, which dropping it leaves alone
+fn defines_only(d: &Directive) -> Option<Register> {
+    let register = match d {
+        Mov(Reg(r), _)
+        | Add(Reg(r), _)
+        | Sub(Reg(r), _)
+        | Mul(Reg(r), _)
+        | Lsl(Reg(r), _)
+        | Lsr(Reg(r), _)
+        | Asr(Reg(r), _)
+        | And(Reg(r), _)
+        | Orr(Reg(r), _)
+        | Cset(Reg(r), _)
+        | Adr(Reg(r), _)
+        | Ldr(Reg(r), _) => r,
+        _ => return None,
+    };
+    match register {
+        Register::Sp | Register::Fp => None,
+        r => Some(r.clone()),
+    }
+}
+
+/// A slot in the frame, which nothing but the function itself and the calls it makes reads
+fn frame_slot(o: &Operand) -> bool {
+    matches!(o, MemOffset(offset, base) if matches!(**offset, Imm(_)) && **base == Reg(Register::Fp))
+}
+
+/// `mov x, x`
+fn self_move(window: &[Directive]) -> Option<(usize, Vec<Directive>)> {
+    match window {
+        [Mov(Reg(a), Reg(b)), ..] if a == b => Some((1, vec![])),
+        _ => None,
+    }
+}
+
+/// `mov a, b` then `mov b, a`, where the second changes nothing
+fn move_back(window: &[Directive]) -> Option<(usize, Vec<Directive>)> {
+    match window {
+        [first @ Mov(Reg(a), Reg(b)), Mov(Reg(c), Reg(d)), ..] if a == d && b == c => {
+            Some((2, vec![first.clone()]))
+        }
+        _ => None,
+    }
+}
+
+/// A branch to the label right after it
+fn jump_to_next(window: &[Directive]) -> Option<(usize, Vec<Directive>)> {
+    match window {
+        [B(l) | Beq(l) | Bne(l) | Blt(l) | Bge(l) | Bgt(l) | Ble(l) | Bls(l) | Bvs(l), label @ Label(m), ..]
+            if l == m =>
+        {
+            Some((2, vec![label.clone()]))
+        }
+        _ => None,
+    }
+}
+
+/// A register written and then written again before anything reads it, as in `mov x, #0`
+/// before `cset x, eq`
+fn overwritten(window: &[Directive]) -> Option<(usize, Vec<Directive>)> {
+    let register = defines_only(window.first()?)?;
+    for d in &window[1..] {
+        if !straight(d) || d.uses().contains(&register) {
+            return None;
+        }
+        if d.defs().contains(&register) {
+            return Some((1, vec![]));
+        }
+    }
+    None
+}
+
+/// A store to a slot that is stored to again before anything could read it. Loads, and calls,
+/// which may read any slot, keep it.
+fn dead_store(window: &[Directive]) -> Option<(usize, Vec<Directive>)> {
+    let slot = match window.first()? {
+        Str(slot, _) if frame_slot(slot) => slot,
+        _ => return None,
+    };
+    for d in &window[1..] {
+        match d {
+            Str(other, _) if other == slot => return Some((1, vec![])),
+            Ldr(..) | Ldp(..) => return None,
+            d if !straight(d) || d.defs().contains(&Register::Fp) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A load of what was just stored, which the register stored from still holds
+fn store_load(window: &[Directive]) -> Option<(usize, Vec<Directive>)> {
+    let (slot, stored) = match window.first()? {
+        Str(slot, Reg(r)) if frame_slot(slot) => (slot, r),
+        _ => return None,
+    };
+    for (i, d) in window.iter().enumerate().skip(1) {
+        match d {
+            Ldr(Reg(r), other) if other == slot => {
+                let mut replacement = window[..i].to_vec();
+                if r != stored {
+                    replacement.push(Mov(Reg(r.clone()), Reg(stored.clone())));
+                }
+                return Some((i + 1, replacement));
+            }
+            d if !straight(d) || matches!(d, Str(..) | Stp(..)) => return None,
+            d if d.defs().contains(stored) || d.defs().contains(&Register::Fp) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Register::*;
+
+    fn slot(offset: i64) -> Operand {
+        MemOffset(Box::new(Imm(offset)), Box::new(Reg(Fp)))
+    }
+
+    fn label(name: &str) -> Directive {
+        Label(name.to_string())
+    }
+
+    #[test]
+    fn self_move() {
+        let code = [Mov(Reg(X9), Reg(X9)), Ret];
+        assert_eq!(super::self_move(&code), Some((1, vec![])));
+        assert_eq!(super::self_move(&[Mov(Reg(X9), Reg(X10))]), None);
+    }
+
+    #[test]
+    fn move_back() {
+        let code = [Mov(Reg(X9), Reg(X10)), Mov(Reg(X10), Reg(X9))];
+        assert_eq!(optimize(&code), [Mov(Reg(X9), Reg(X10))]);
+        // X10 changes in between, and that value is used, so moving it back does something
+        let code = [
+            Mov(Reg(X9), Reg(X10)),
+            Add(Reg(X10), Imm(1)),
+            Str(slot(-8), Reg(X10)),
+            Mov(Reg(X10), Reg(X9)),
+        ];
+        assert_eq!(optimize(&code), code);
+    }
+
+    #[test]
+    fn jump_to_next() {
+        let code = [B("l".to_string()), label("l")];
+        assert_eq!(optimize(&code), [label("l")]);
+        let code = [Bne("m".to_string()), label("l"), label("m")];
+        assert_eq!(optimize(&code).len(), 3);
+    }
+
+    #[test]
+    fn overwritten() {
+        // `mov x, #0` before `cset x` is the common case
+        let code = [Mov(Reg(X9), Imm(0)), Cset(Reg(X9), "eq".to_string())];
+        assert_eq!(optimize(&code), [Cset(Reg(X9), "eq".to_string())]);
+        for code in [
+            // Read before it is written again
+            vec![Mov(Reg(X9), Imm(0)), Add(Reg(X9), Imm(1))],
+            // A jump could land on the label with the first value
+            vec![Mov(Reg(X9), Imm(0)), label("l"), Mov(Reg(X9), Imm(1))],
+            // Calls read their arguments
+            vec![
+                Mov(Reg(X0), Imm(0)),
+                Bl("f".to_string()),
+                Mov(Reg(X0), Imm(1)),
+            ],
+            // Writes to sp do more than set a value
+            vec![Sub(Reg(Sp), Imm(16)), Mov(Reg(Sp), Reg(Fp))],
+        ] {
+            assert_eq!(super::overwritten(&code), None, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn dead_store() {
+        let code = [
+            Str(slot(-8), Reg(X9)),
+            Mov(Reg(X10), Imm(1)),
+            Str(slot(-8), Reg(X10)),
+        ];
+        assert_eq!(super::dead_store(&code), Some((1, vec![])));
+        for code in [
+            // The slot is read before it is stored to again
+            vec![
+                Str(slot(-8), Reg(X9)),
+                Ldr(Reg(X10), slot(-8)),
+                Str(slot(-8), Reg(X10)),
+            ],
+            // Code after the label may read it
+            vec![Str(slot(-8), Reg(X9)), label("l"), Str(slot(-8), Reg(X10))],
+            // So may the function called
+            vec![
+                Str(slot(-8), Reg(X9)),
+                Bl("f".to_string()),
+                Str(slot(-8), Reg(X10)),
+            ],
+            // A different slot
+            vec![Str(slot(-8), Reg(X9)), Str(slot(-16), Reg(X10))],
+        ] {
+            assert_eq!(super::dead_store(&code), None, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn store_load() {
+        let code = [
+            Str(slot(-8), Reg(X9)),
+            Mov(Reg(X11), Imm(1)),
+            Ldr(Reg(X10), slot(-8)),
+        ];
+        assert_eq!(
+            optimize(&code),
+            [
+                Str(slot(-8), Reg(X9)),
+                Mov(Reg(X11), Imm(1)),
+                Mov(Reg(X10), Reg(X9)),
+            ]
+        );
+        for code in [
+            // The register stored from changes before the load
+            vec![
+                Str(slot(-8), Reg(X9)),
+                Add(Reg(X9), Imm(1)),
+                Ldr(Reg(X10), slot(-8)),
+            ],
+            // Something else may store to the slot in between
+            vec![
+                Str(slot(-8), Reg(X9)),
+                Bl("f".to_string()),
+                Ldr(Reg(X10), slot(-8)),
+            ],
+            // A jump may land between them, with something else in the slot
+            vec![Str(slot(-8), Reg(X9)), label("l"), Ldr(Reg(X10), slot(-8))],
+        ] {
+            assert_eq!(super::store_load(&code), None, "{:?}", code);
+        }
+        // Beyond the window
+        let code = [
+            Str(slot(-8), Reg(X9)),
+            Mov(Reg(X11), Imm(1)),
+            Mov(Reg(X12), Imm(2)),
+            Mov(Reg(X13), Imm(3)),
+            Ldr(Reg(X10), slot(-8)),
+        ];
+        assert_eq!(optimize(&code), code);
+    }
+}
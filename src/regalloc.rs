@@ -0,0 +1,416 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::asm::{
+    Directive::{self, *},
+    Operand::*,
+    Register::{self, *},
+    RegisterClass, ALLOCATABLE,
+};
+use crate::compile::stack_address;
+
+/// What the operands of one instruction use while their virtual registers sit in slots
+const SCRATCH: [Register; 2] = [X8, X7];
+
+/// Where the allocator keeps a virtual register for all of its life
+#[derive(Clone, Debug, PartialEq)]
+pub enum Location {
+    Reg(Register),
+    /// A slot at this offset from fp
+    Slot(i64),
+}
+
+/// Code rewritten to machine registers
+pub struct Allocation {
+    pub code: Vec<Directive>,
+    /// The callee-saved registers the code writes, which its function must save and restore
+    pub callee_saved: Vec<Register>,
+}
+
+fn virtuals(registers: Vec<Register>) -> Vec<usize> {
+    registers
+        .into_iter()
+        .filter_map(|r| match r {
+            Virtual(n) => Some(n),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Where control can go after each directive: the next one, a branch target, or both. Branches
+/// to labels outside the code leave it.
+fn successors(code: &[Directive]) -> Vec<Vec<usize>> {
+    let labels: HashMap<&str, usize> = code
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| match d {
+            Label(l) => Some((l.as_str(), i)),
+            _ => None,
+        })
+        .collect();
+    let target = |l: &String| labels.get(l.as_str()).copied();
+    code.iter()
+        .enumerate()
+        .map(|(i, d)| {
+            let next = Some(i + 1).filter(|&n| n < code.len());
+            match d {
+                B(l) => target(l).into_iter().collect(),
+                Beq(l) | Bne(l) | Blt(l) | Bge(l) | Bgt(l) | Ble(l) | Bls(l) | Bvs(l) => {
+                    next.into_iter().chain(target(l)).collect()
+                }
+                Br(_) | Ret => vec![],
+                _ => next.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+/// The virtual registers live after each directive: those some path from it reads before
+/// writing them
+pub fn live_out(code: &[Directive]) -> Vec<BTreeSet<usize>> {
+    let successors = successors(code);
+    let mut live_in = vec![BTreeSet::new(); code.len()];
+    let mut live_out = vec![BTreeSet::new(); code.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in (0..code.len()).rev() {
+            let out: BTreeSet<usize> = successors[i]
+                .iter()
+                .flat_map(|&s| live_in[s].iter().copied())
+                .collect();
+            let defs = virtuals(code[i].defs());
+            let mut inside: BTreeSet<usize> =
+                out.iter().copied().filter(|v| !defs.contains(v)).collect();
+            inside.extend(virtuals(code[i].uses()));
+            if inside != live_in[i] {
+                live_in[i] = inside;
+                changed = true;
+            }
+            live_out[i] = out;
+        }
+    }
+    live_out
+}
+
+/// The first and last directive at which each virtual register is written, read or live
+fn intervals(code: &[Directive], live_out: &[BTreeSet<usize>]) -> Vec<(usize, usize, usize)> {
+    let mut ranges: HashMap<usize, (usize, usize)> = HashMap::new();
+    for (i, d) in code.iter().enumerate() {
+        let mentioned = virtuals([d.uses(), d.defs()].concat());
+        for v in mentioned.into_iter().chain(live_out[i].iter().copied()) {
+            ranges.entry(v).or_insert((i, i)).1 = i;
+        }
+    }
+    let mut intervals: Vec<_> = ranges
+        .into_iter()
+        .map(|(v, (start, end))| (start, end, v))
+        .collect();
+    intervals.sort();
+    intervals
+}
+
+/// Linear scan: walks the intervals in order of their start, handing each a register no
+/// overlapping interval holds. When none is left, whichever of it and the active intervals
+/// ends last goes to a slot, as the `n`th spill.
+fn linear_scan(
+    intervals: &[(usize, usize, usize)],
+) -> (HashMap<usize, Location>, HashMap<usize, usize>) {
+    let mut registers: HashMap<usize, Register> = HashMap::new();
+    let mut spills = HashMap::new();
+    let mut active: Vec<(usize, usize)> = Vec::new();
+    let mut free = ALLOCATABLE.to_vec();
+    for &(start, end, v) in intervals {
+        // An interval that ends where this one starts is last read by the instruction that
+        // writes this one, which reads its operands first
+        active.retain(|&(last, a)| {
+            if last <= start {
+                free.push(registers[&a].clone());
+                false
+            } else {
+                true
+            }
+        });
+        free.sort_by_key(|r| ALLOCATABLE.iter().position(|a| a == r));
+        if !free.is_empty() {
+            registers.insert(v, free.remove(0));
+            active.push((end, v));
+            continue;
+        }
+        let (furthest, &(last, a)) = active
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, (last, _))| *last)
+            .unwrap();
+        if last > end {
+            let register = registers.remove(&a).unwrap();
+            spills.insert(a, spills.len());
+            registers.insert(v, register);
+            active[furthest] = (end, v);
+        } else {
+            spills.insert(v, spills.len());
+        }
+    }
+    let locations = registers
+        .into_iter()
+        .map(|(v, r)| (v, Location::Reg(r)))
+        .collect();
+    (locations, spills)
+}
+
+/// Maps the virtual registers of `code` to the registers in `ALLOCATABLE`, spilling to slots
+/// only when more values are live at once than there are registers.
+///
+/// Each call saves the values live across it in the slots from `first_slot` down, and loads
+/// them back after, since the runtime may collect and move what they point to; `roots` gives
+/// the directives that tell the runtime about the slots above the `stack_index` it is passed.
+/// Everything live across a call must therefore be a tagged value. Spill slots come below the
+/// most any call saves.
+pub fn allocate(
+    code: &[Directive],
+    first_slot: i64,
+    roots: impl Fn(&str, i64) -> Vec<Directive>,
+) -> Allocation {
+    let live_out = live_out(code);
+    let (mut locations, spills) = linear_scan(&intervals(code, &live_out));
+    let saved = code
+        .iter()
+        .zip(&live_out)
+        .filter(|(d, _)| matches!(d, Bl(_)))
+        .map(|(_, live)| live.len() as i64)
+        .max()
+        .unwrap_or(0);
+    for (v, n) in spills {
+        locations.insert(v, Location::Slot(first_slot - 8 * (saved + n as i64)));
+    }
+
+    let mut allocated = Vec::new();
+    for (i, d) in code.iter().enumerate() {
+        if let Bl(name) = d {
+            let slots: Vec<(i64, &Location)> = live_out[i]
+                .iter()
+                .enumerate()
+                .map(|(j, v)| (first_slot - 8 * j as i64, &locations[v]))
+                .collect();
+            for (slot, location) in &slots {
+                allocated.extend(match location {
+                    Location::Reg(r) => vec![Str(stack_address(*slot), Reg(r.clone()))],
+                    Location::Slot(s) => vec![
+                        Ldr(Reg(SCRATCH[0].clone()), stack_address(*s)),
+                        Str(stack_address(*slot), Reg(SCRATCH[0].clone())),
+                    ],
+                });
+            }
+            allocated.extend(roots(name, first_slot - 8 * slots.len() as i64));
+            allocated.push(d.clone());
+            for (slot, location) in &slots {
+                allocated.extend(match location {
+                    Location::Reg(r) => vec![Ldr(Reg(r.clone()), stack_address(*slot))],
+                    Location::Slot(s) => vec![
+                        Ldr(Reg(SCRATCH[0].clone()), stack_address(*slot)),
+                        Str(stack_address(*s), Reg(SCRATCH[0].clone())),
+                    ],
+                });
+            }
+            continue;
+        }
+
+        // Operands in slots go through scratch registers, loaded before and stored after
+        let mut scratch: HashMap<usize, Register> = HashMap::new();
+        for v in virtuals([d.uses(), d.defs()].concat()) {
+            if matches!(locations[&v], Location::Slot(_)) && !scratch.contains_key(&v) {
+                // No instruction names more than two
+                scratch.insert(v, SCRATCH[scratch.len()].clone());
+            }
+        }
+        let slot_of = |v: &usize| match locations[v] {
+            Location::Slot(s) => s,
+            Location::Reg(_) => unreachable!(),
+        };
+        let mut loaded = Vec::new();
+        for v in virtuals(d.uses()) {
+            if scratch.contains_key(&v) && !loaded.contains(&v) {
+                allocated.push(Ldr(Reg(scratch[&v].clone()), stack_address(slot_of(&v))));
+                loaded.push(v);
+            }
+        }
+        allocated.push(d.map_registers(&mut |r| match r {
+            Virtual(v) => match &locations[v] {
+                Location::Reg(physical) => physical.clone(),
+                Location::Slot(_) => scratch[v].clone(),
+            },
+            r => r.clone(),
+        }));
+        for v in virtuals(d.defs()) {
+            if scratch.contains_key(&v) {
+                allocated.push(Str(stack_address(slot_of(&v)), Reg(scratch[&v].clone())));
+            }
+        }
+    }
+
+    let callee_saved = ALLOCATABLE
+        .iter()
+        .filter(|r| r.class() == RegisterClass::CalleeSaved)
+        .filter(|r| {
+            locations
+                .values()
+                .any(|l| *l == Location::Reg((*r).clone()))
+        })
+        .cloned()
+        .collect();
+    Allocation {
+        code: allocated,
+        callee_saved,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{operand_of_num, NUM_SHIFT};
+    use crate::sim::{Machine, FUEL, HEAP_SIZE};
+
+    const FIRST_SLOT: i64 = -8;
+
+    fn v(n: usize) -> crate::asm::Operand {
+        Reg(Virtual(n))
+    }
+
+    /// Runs allocated code in a frame of its own, followed by `clobber`, a function that
+    /// overwrites every allocatable register, and returns the number left in X0
+    fn run(code: &[Directive]) -> i64 {
+        let sp = || MemOffset(Box::new(Imm(0)), Box::new(Reg(Sp)));
+        let program = [
+            vec![Label("lisp_entry".to_string())],
+            vec![
+                Sub(Reg(Sp), Imm(16)),
+                Stp(Reg(Fp), Reg(Lr), sp()),
+                Mov(Reg(Fp), Reg(Sp)),
+                Sub(Reg(Sp), Imm(1024)),
+            ],
+            code.to_vec(),
+            vec![
+                Mov(Reg(Sp), Reg(Fp)),
+                Ldp(Reg(Fp), Reg(Lr), sp()),
+                Add(Reg(Sp), Imm(16)),
+                Ret,
+                Label("clobber".to_string()),
+            ],
+            ALLOCATABLE
+                .iter()
+                .map(|r| Mov(Reg(r.clone()), Imm(-1)))
+                .collect(),
+            vec![Ret],
+        ]
+        .concat();
+        let mut machine = Machine::new(&program, HEAP_SIZE).unwrap();
+        assert_eq!(machine.run(FUEL), Ok(0));
+        machine.reg(&X0) as i64 >> NUM_SHIFT
+    }
+
+    /// Defines `count` values, then adds them all up, so every one is live at once
+    fn sum_of(count: usize) -> Vec<Directive> {
+        let total = count;
+        let mut code: Vec<Directive> = (0..count)
+            .map(|i| Mov(v(i), operand_of_num(i as i64 + 1)))
+            .collect();
+        code.push(Mov(v(total), operand_of_num(0)));
+        code.extend((0..count).map(|i| Add(v(total), v(i))));
+        code.push(Mov(Reg(X0), v(total)));
+        code
+    }
+
+    fn slots(code: &[Directive]) -> usize {
+        code.iter()
+            .filter(|d| matches!(d, Str(MemOffset(..), _) | Ldr(_, MemOffset(..))))
+            .count()
+    }
+
+    #[test]
+    fn spills_when_registers_run_out() {
+        let fits = allocate(&sum_of(ALLOCATABLE.len() - 1), FIRST_SLOT, |_, _| vec![]);
+        assert_eq!(slots(&fits.code), 0);
+
+        let count = ALLOCATABLE.len() + 3;
+        let spilled = allocate(&sum_of(count), FIRST_SLOT, |_, _| vec![]);
+        assert!(slots(&spilled.code) > 0);
+        assert!(spilled.code.iter().all(|d| [d.uses(), d.defs()]
+            .concat()
+            .iter()
+            .all(|r| r.class() != RegisterClass::Virtual)));
+        assert_eq!(run(&spilled.code), (count * (count + 1) / 2) as i64);
+    }
+
+    /// Whichever interval ends last gives up its register
+    #[test]
+    fn spills_the_furthest_end() {
+        let mut intervals = vec![(0, 100, 0)];
+        intervals.extend((1..=ALLOCATABLE.len()).map(|v| (1, 10, v)));
+        let (locations, spills) = linear_scan(&intervals);
+        assert_eq!(spills, HashMap::from([(0, 0)]));
+        assert!(!locations.contains_key(&0));
+        assert_eq!(locations.len(), ALLOCATABLE.len());
+    }
+
+    #[test]
+    fn values_live_across_calls_are_saved_and_restored() {
+        let code = vec![
+            Mov(v(0), operand_of_num(20)),
+            Mov(v(1), operand_of_num(22)),
+            Bl("clobber".to_string()),
+            Add(v(0), v(1)),
+            Mov(Reg(X0), v(0)),
+        ];
+        let roots = std::cell::RefCell::new(Vec::new());
+        let allocation = allocate(&code, FIRST_SLOT, |name, stack_index| {
+            roots.borrow_mut().push((name.to_string(), stack_index));
+            vec![]
+        });
+        // Both values go in the two slots from the first down, and the runtime is told so
+        assert_eq!(
+            roots.into_inner(),
+            [("clobber".to_string(), FIRST_SLOT - 16)]
+        );
+        let call = allocation
+            .code
+            .iter()
+            .position(|d| matches!(d, Bl(_)))
+            .unwrap();
+        let saved = &allocation.code[call - 2..call];
+        let restored = &allocation.code[call + 1..call + 3];
+        for (i, slot) in [FIRST_SLOT, FIRST_SLOT - 8].into_iter().enumerate() {
+            assert!(matches!(&saved[i], Str(a, _) if *a == stack_address(slot)));
+            assert!(matches!(&restored[i], Ldr(_, a) if *a == stack_address(slot)));
+        }
+        assert_eq!(run(&allocation.code), 42);
+    }
+
+    /// An interval that ends where another starts hands its register on
+    #[test]
+    fn registers_are_reused_where_intervals_meet() {
+        let (locations, spills) = linear_scan(&[(0, 2, 0), (2, 4, 1), (3, 5, 2)]);
+        assert!(spills.is_empty());
+        assert_eq!(locations[&0], Location::Reg(ALLOCATABLE[0].clone()));
+        assert_eq!(locations[&1], Location::Reg(ALLOCATABLE[0].clone()));
+        assert_eq!(locations[&2], Location::Reg(ALLOCATABLE[1].clone()));
+
+        // `add v1, v0` reads v0 as it writes v1, which can then share v0's register
+        let code = vec![
+            Mov(v(0), operand_of_num(1)),
+            Mov(v(1), operand_of_num(2)),
+            Add(v(1), v(0)),
+            Mov(v(2), v(1)),
+            Add(v(2), v(1)),
+            Mov(Reg(X0), v(2)),
+        ];
+        let allocation = allocate(&code, FIRST_SLOT, |_, _| vec![]);
+        assert_eq!(run(&allocation.code), 6);
+        let used: BTreeSet<String> = allocation
+            .code
+            .iter()
+            .flat_map(|d| [d.uses(), d.defs()].concat())
+            .filter(|r| ALLOCATABLE.contains(r))
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(used.len(), 2);
+    }
+}
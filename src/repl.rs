@@ -0,0 +1,220 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::asm::string_of_directive;
+use crate::ast::Expr;
+use crate::compile::compile;
+use crate::eval::{self, Interpreter};
+use crate::lexer::{Lexer, LexicalError, Tok};
+use crate::sim;
+
+const HELP: &str = "\
+:ast <expr>      print the parsed expression
+:asm <expr>      print the directives compile produces
+:mode interp     evaluate with the interpreter (default)
+:mode compiled   compile and run in the simulator
+:defs            list the functions defined so far
+:quit            leave the REPL";
+
+pub struct Repl {
+    interpreter: Interpreter,
+    definitions: Vec<Expr>,
+    compiled: bool,
+}
+
+/// How many brackets are still open; input continues onto the next line while this is positive.
+/// An unterminated block comment also keeps the input open, while a closing bracket with nothing
+/// open makes the input an error however it goes on, so it ends the input at once.
+fn open_brackets(input: &str) -> i64 {
+    let mut depth = 0;
+    for token in Lexer::new(input) {
+        match token {
+            Ok((_, Tok::LParen | Tok::LBrace, _)) => depth += 1,
+            Ok((_, Tok::RParen | Tok::RBrace, _)) if depth == 0 => return -1,
+            Ok((_, Tok::RParen | Tok::RBrace, _)) => depth -= 1,
+            Err(LexicalError::UnterminatedComment { .. }) => return depth + 1,
+            _ => (),
+        }
+    }
+    depth
+}
+
+fn parse(input: &str) -> Result<Expr, String> {
+    crate::parse(input).map_err(|d| d.render("<repl>", input, false).trim_end().to_string())
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl {
+            interpreter: Interpreter::new(),
+            definitions: Vec::new(),
+            compiled: false,
+        }
+    }
+
+    /// The program actually run for an input: earlier definitions followed by the input
+    fn with_definitions(&self, expr: Expr) -> Expr {
+        if self.definitions.is_empty() {
+            return expr;
+        }
+        let mut exprs: Vec<Box<Expr>> = self.definitions.iter().cloned().map(Box::new).collect();
+        exprs.push(Box::new(expr));
+        Expr::Do(exprs)
+    }
+
+    fn evaluate(&mut self, expr: Expr) -> String {
+        if let Expr::FuncDef(name, _, _) = &expr {
+            let name = name.clone();
+            self.definitions
+                .retain(|d| !matches!(d, Expr::FuncDef(n, _, _) if *n == name));
+            self.definitions.push(expr.clone());
+        }
+        if self.compiled {
+            match compile(self.with_definitions(expr)) {
+                Ok(directives) => match sim::simulate(&directives) {
+                    Ok(output) => output.stdout.trim_end().to_string(),
+                    Err(e) => format!("Simulator error: {}", e),
+                },
+                Err(e) => format!("Compile error: {}", e),
+            }
+        } else {
+            let interpreter = &mut self.interpreter;
+            eval::on_stack(|| match interpreter.eval(&expr) {
+                Ok(v) => v.to_string(),
+                Err(e) => e.to_string(),
+            })
+        }
+    }
+
+    /// Handles one complete input, returning the text to show, or `None` to quit
+    pub fn handle(&mut self, input: &str) -> Option<String> {
+        let input = input.trim();
+        let (command, rest) = match input.strip_prefix(':') {
+            Some(c) => c.split_once(char::is_whitespace).unwrap_or((c, "")),
+            None => ("", input),
+        };
+        let output = match command {
+            "" if rest.is_empty() => String::new(),
+            "" => match parse(rest) {
+                Ok(expr) => self.evaluate(expr),
+                Err(e) => e,
+            },
+            "ast" => match parse(rest) {
+                Ok(expr) => format!("{:#?}", expr),
+                Err(e) => e,
+            },
+            "asm" => match parse(rest)
+                .and_then(|e| compile(e).map_err(|e| format!("Compile error: {}", e)))
+            {
+                Ok(directives) => directives
+                    .iter()
+                    .map(string_of_directive)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Err(e) => e,
+            },
+            "mode" => match rest.trim() {
+                "interp" => {
+                    self.compiled = false;
+                    "Evaluating with the interpreter".to_string()
+                }
+                "compiled" => {
+                    self.compiled = true;
+                    "Compiling and running in the simulator".to_string()
+                }
+                other => format!("Unknown mode: {}", other),
+            },
+            "defs" => self
+                .definitions
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "help" => HELP.to_string(),
+            "quit" | "q" => return None,
+            other => format!("Unknown command :{} (try :help)", other),
+        };
+        Some(output)
+    }
+}
+
+/// Reads inputs from stdin until EOF or `:quit`, joining lines while brackets are unbalanced
+pub fn run() -> Result<(), String> {
+    let interactive = io::stdin().is_terminal();
+    let prompt = |continuation: bool| {
+        if interactive {
+            print!("{}", if continuation { "... " } else { "> " });
+            let _ = io::stdout().flush();
+        }
+    };
+
+    let mut repl = Repl::new();
+    let mut pending = String::new();
+    prompt(false);
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("Error reading input: {}", e))?;
+        pending.push_str(&line);
+        pending.push('\n');
+        if open_brackets(&pending) > 0 {
+            prompt(true);
+            continue;
+        }
+        match repl.handle(&pending) {
+            Some(output) if output.is_empty() => (),
+            Some(output) => println!("{}", output),
+            None => return Ok(()),
+        }
+        pending.clear();
+        prompt(false);
+    }
+    if !pending.trim().is_empty() {
+        // Input ended inside an unclosed bracket; report the parse error rather than drop it
+        if let Some(output) = repl.handle(&pending) {
+            println!("{}", output);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continues_only_on_unbalanced_brackets() {
+        assert_eq!(open_brackets("add1(1)"), 0);
+        assert_eq!(open_brackets("{ def f(x) ="), 1);
+        assert_eq!(open_brackets("pair(1, { 2"), 2);
+        assert_eq!(open_brackets("1 /* comment"), 1);
+        assert_eq!(open_brackets("1 // (comment"), 0);
+        assert!(open_brackets("add1(1))") <= 0);
+        assert!(open_brackets(") (((") <= 0);
+        assert!(open_brackets("1 } {") <= 0);
+    }
+
+    #[test]
+    fn definitions_persist() {
+        let mut repl = Repl::new();
+        assert_eq!(
+            repl.handle("def double(x) = x + x").unwrap(),
+            "<function double>"
+        );
+        assert_eq!(repl.handle("let y = 2 in double(y)").unwrap(), "4");
+        assert_eq!(repl.handle("y").unwrap(), "Unbound variable: y");
+        assert_eq!(repl.handle(":defs").unwrap(), "def double(x) = x + x");
+        assert_eq!(repl.handle(":quit"), None);
+
+        let mut repl = Repl::new();
+        repl.handle(":mode compiled");
+        assert_eq!(repl.handle("let y = 41 in add1(y)").unwrap(), "42");
+        assert_eq!(
+            repl.handle("y").unwrap(),
+            "Compile error: unbound identifier y"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::ops::Range;
+
+use crate::ast::Expr;
+use crate::lexer::{Lexer, Tok};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Variable,
+    Parameter,
+    Function,
+}
+
+/// A name introduced by `let`, a parameter list or `def`
+#[derive(Clone, Debug)]
+pub struct Definition {
+    pub name: String,
+    pub kind: Kind,
+    pub span: Range<usize>,
+    /// For functions, the parameter names
+    pub params: Vec<String>,
+}
+
+/// A use of a name, and the definition it resolves to if any
+#[derive(Clone, Debug)]
+pub struct Reference {
+    pub name: String,
+    pub span: Range<usize>,
+    pub definition: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct Resolution {
+    pub definitions: Vec<Definition>,
+    pub references: Vec<Reference>,
+}
+
+/// Every identifier in the source with its span; keywords and comments are not identifiers
+pub fn identifiers(source: &str) -> Vec<(Range<usize>, &str)> {
+    Lexer::new(source)
+        .map_while(Result::ok)
+        .filter_map(|(start, tok, end)| match tok {
+            Tok::Id(name) => Some((start..end, name)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Walks the tree in source order, pairing each name in it with the next identifier in the
+/// source. The tree has no positions of its own, but the grammar never reorders names.
+struct Resolver<'a> {
+    identifiers: std::vec::IntoIter<(Range<usize>, &'a str)>,
+    functions: Vec<(String, usize)>,
+    resolution: Resolution,
+}
+
+impl Resolver<'_> {
+    fn next_span(&mut self, name: &str) -> Range<usize> {
+        match self.identifiers.next() {
+            Some((span, word)) if word == name => span,
+            // Only reachable if the source and tree disagree; fall back to an empty span
+            _ => 0..0,
+        }
+    }
+
+    fn define(&mut self, name: &str, kind: Kind, params: &[String]) -> usize {
+        let span = self.next_span(name);
+        self.resolution.definitions.push(Definition {
+            name: name.to_string(),
+            kind,
+            span,
+            params: params.to_vec(),
+        });
+        self.resolution.definitions.len() - 1
+    }
+
+    fn reference(&mut self, scope: &[(String, usize)], name: &str) {
+        let span = self.next_span(name);
+        let definition = scope
+            .iter()
+            .rev()
+            .chain(self.functions.iter().rev())
+            .find(|(n, _)| n == name)
+            .map(|(_, d)| *d);
+        self.resolution.references.push(Reference {
+            name: name.to_string(),
+            span,
+            definition,
+        });
+    }
+
+    fn walk(&mut self, scope: &[(String, usize)], expr: &Expr) {
+        match expr {
+            Expr::Num(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Unit => (),
+            Expr::Id(name) => self.reference(scope, name),
+            Expr::UnOp(_, e) => self.walk(scope, e),
+            Expr::BinOp(_, e1, e2) => {
+                self.walk(scope, e1);
+                self.walk(scope, e2);
+            }
+            Expr::TriOp(_, e1, e2, e3) | Expr::If(e1, e2, e3) => {
+                self.walk(scope, e1);
+                self.walk(scope, e2);
+                self.walk(scope, e3);
+            }
+            Expr::Let(bindings, body) => {
+                let mut scope = scope.to_vec();
+                for (var, e) in bindings {
+                    let definition = self.define(var, Kind::Variable, &[]);
+                    self.walk(&scope, e);
+                    scope.push((var.clone(), definition));
+                }
+                self.walk(&scope, body);
+            }
+            Expr::Do(exps) => exps.iter().for_each(|e| self.walk(scope, e)),
+            Expr::FuncDef(name, params, body) => {
+                let definition = self.define(name, Kind::Function, params);
+                self.functions.push((name.clone(), definition));
+                // A body sees only its parameters and the functions, as in the interpreter
+                let scope: Vec<(String, usize)> = params
+                    .iter()
+                    .map(|p| (p.clone(), self.define(p, Kind::Parameter, &[])))
+                    .collect();
+                self.walk(&scope, body);
+            }
+            Expr::FuncCall(f, args) => {
+                self.walk(scope, f);
+                args.iter().for_each(|e| self.walk(scope, e));
+            }
+        }
+    }
+}
+
+/// Functions are visible everywhere, since the interpreter keeps them in one table
+fn hoist(resolver: &mut Resolver, resolution: &Resolution) {
+    resolver.functions = resolution
+        .definitions
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.kind == Kind::Function)
+        .map(|(i, d)| (d.name.clone(), i))
+        .collect();
+}
+
+/// Finds where every name in `expr`, parsed from `source`, is defined and used
+pub fn resolve(source: &str, expr: &Expr) -> Resolution {
+    let identifiers = identifiers(source);
+    let new = || Resolver {
+        identifiers: identifiers.clone().into_iter(),
+        functions: Vec::new(),
+        resolution: Resolution::default(),
+    };
+
+    // The first pass finds the functions, the second resolves calls that precede them
+    let mut first = new();
+    first.walk(&[], expr);
+    let mut second = new();
+    hoist(&mut second, &first.resolution);
+    second.walk(&[], expr);
+    second.resolution
+}
+
+impl Resolution {
+    /// Uses of names that nothing defines
+    pub fn unbound(&self) -> impl Iterator<Item = &Reference> {
+        self.references.iter().filter(|r| r.definition.is_none())
+    }
+
+    /// Variables and parameters that are never used
+    pub fn unused(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter().enumerate().filter_map(|(i, d)| {
+            let used = self.references.iter().any(|r| r.definition == Some(i));
+            (d.kind != Kind::Function && !used).then_some(d)
+        })
+    }
+
+    /// The name under `offset`, whether a use or a definition, and the definition it refers to
+    pub fn definition_at(&self, offset: usize) -> Option<(Range<usize>, &Definition)> {
+        let contains = |span: &Range<usize>| span.start <= offset && offset <= span.end;
+        if let Some(d) = self.definitions.iter().find(|d| contains(&d.span)) {
+            return Some((d.span.clone(), d));
+        }
+        let reference = self.references.iter().find(|r| contains(&r.span))?;
+        Some((
+            reference.span.clone(),
+            &self.definitions[reference.definition?],
+        ))
+    }
+}
@@ -1,8 +1,10 @@
 #include "stdlib.h"
 #include <inttypes.h>
 #include <stdio.h>
+#include <string.h>
+#include <sys/resource.h>
 
-extern uint64_t lisp_entry(void *heap);
+extern uint64_t lisp_entry(void *heap, void *heap_limit, void *stack_limit);
 
 #define num_mask 0b11
 #define num_tag 0b00
@@ -13,7 +15,9 @@ extern uint64_t lisp_entry(void *heap);
 #define bool_shift 7
 
 #define heap_mask 0b111
+#define float_tag 0b001
 #define pair_tag 0b010
+#define big_tag 0b011
 
 #define nil_mask 0b11111111
 #define nil_tag 0b11111111
@@ -21,6 +25,551 @@ extern uint64_t lisp_entry(void *heap);
 #define vector_tag 0b101
 #define vector_mask 0b111
 
+void lisp_error(char *exp);
+
+__extension__ typedef unsigned __int128 uint128_t;
+
+/* The heap is collected by copying live objects between two semispaces. An
+ * object's tag says how to find its size: a float is one word, a bignum its
+ * header and limbs, a pair two words and a vector its length and elements. */
+static uint64_t heap_words;
+/* Where new objects go, and where the next collection copies them to */
+static uint64_t *space, *spare;
+/* The compiled code keeps the heap pointer and the end of the heap in the
+ * two slots just above its roots, so that it can allocate without calling
+ * into the runtime; these point at them */
+static uint64_t **heap_pointer, **heap_limit;
+/* While collecting, one bit per word of spare saying whether the object
+ * starting there has moved; its first word then holds the new address */
+static uint64_t *forwarded;
+/* Copied pairs and vectors whose fields still point into spare */
+static uint64_t *pending;
+static uint64_t pending_count;
+
+/* The stack slots in which the compiled code keeps values, from the lowest
+ * live one to the top of its frame, as it passes them on each call into the
+ * runtime */
+static uint64_t *roots, *roots_end;
+
+static void heap_init(uint64_t words) {
+  heap_words = words;
+  space = malloc(words * sizeof(uint64_t));
+  spare = malloc(words * sizeof(uint64_t));
+  forwarded = malloc((words / 64 + 1) * sizeof(uint64_t));
+  pending = malloc(words * sizeof(uint64_t));
+}
+
+static void set_roots(uint64_t *bottom, uint64_t *top) {
+  roots = bottom;
+  roots_end = top;
+  heap_limit = (uint64_t **)top;
+  heap_pointer = (uint64_t **)(top + 1);
+}
+
+static uint64_t object_words(uint64_t value) {
+  int64_t *object = (int64_t *)(value & ~(uint64_t)heap_mask);
+  switch (value & heap_mask) {
+  case float_tag:
+    return 1;
+  case big_tag:
+    return 1 + (uint64_t)(object[0] < 0 ? -object[0] : object[0]);
+  case pair_tag:
+    return 2;
+  default:
+    return 1 + (uint64_t)object[0];
+  }
+}
+
+static void out_of_memory(void) {
+  fprintf(stderr,
+          "the heap is full at %" PRIu64 " bytes; set LISP_HEAP_SIZE or pass "
+          "--heap-size for a larger one\n",
+          heap_words * (uint64_t)sizeof(uint64_t));
+  lisp_error("out of memory");
+}
+
+/* Copies the object a value points to out of spare, once, returning the value
+ * that points to the copy. Anything else, including the boxes of float
+ * literals in the code, stays as it is. */
+static uint64_t forward(uint64_t value) {
+  uint64_t tag = value & heap_mask;
+  if (tag != float_tag && tag != pair_tag && tag != big_tag &&
+      tag != vector_tag) {
+    return value;
+  }
+  uint64_t *object = (uint64_t *)(value - tag);
+  if (object < spare || object >= spare + heap_words) {
+    return value;
+  }
+  uint64_t index = object - spare;
+  if ((forwarded[index / 64] >> (index % 64)) & 1) {
+    return object[0] | tag;
+  }
+  uint64_t words = object_words(value);
+  uint64_t *copy = *heap_pointer;
+  *heap_pointer += words;
+  memcpy(copy, object, words * sizeof(uint64_t));
+  object[0] = (uint64_t)copy;
+  forwarded[index / 64] |= (uint64_t)1 << (index % 64);
+  if (tag == pair_tag || tag == vector_tag) {
+    pending[pending_count++] = (uint64_t)copy | tag;
+  }
+  return (uint64_t)copy | tag;
+}
+
+/* Copies everything reachable from the roots, and from the values the runtime
+ * is working on in a and b when they are given, into the other semispace */
+static void collect(uint64_t *a, uint64_t *b) {
+  uint64_t *full = space;
+  space = spare;
+  spare = full;
+  *heap_pointer = space;
+  *heap_limit = space + heap_words;
+  memset(forwarded, 0, (heap_words / 64 + 1) * sizeof(uint64_t));
+  pending_count = 0;
+  for (uint64_t *slot = roots; slot < roots_end; slot++) {
+    *slot = forward(*slot);
+  }
+  if (a) {
+    *a = forward(*a);
+  }
+  if (b) {
+    *b = forward(*b);
+  }
+  while (pending_count > 0) {
+    uint64_t value = pending[--pending_count];
+    uint64_t *object = (uint64_t *)(value & ~(uint64_t)heap_mask);
+    uint64_t first = (value & heap_mask) == pair_tag ? 0 : 1;
+    uint64_t end = (value & heap_mask) == pair_tag ? 2 : 1 + object[0];
+    for (uint64_t i = first; i < end; i++) {
+      object[i] = forward(object[i]);
+    }
+  }
+}
+
+/* Room for an object of the given number of words, collecting first if the
+ * heap is full. A collection moves objects, so a and b, when given, are
+ * updated to where the values in them went. */
+static uint64_t *allocate(uint64_t words, uint64_t *a, uint64_t *b) {
+  if ((uint64_t)(*heap_limit - *heap_pointer) < words) {
+    collect(a, b);
+    if ((uint64_t)(*heap_limit - *heap_pointer) < words) {
+      out_of_memory();
+    }
+  }
+  uint64_t *object = *heap_pointer;
+  *heap_pointer += words;
+  return object;
+}
+
+/* A fixnum or bignum as a sign and a magnitude in 64-bit limbs, least
+ * significant first. On the heap a bignum is a header word holding the limb
+ * count, negated for negative numbers, followed by the limbs. */
+struct number {
+  int negative;
+  int64_t length;
+  uint64_t *limbs;
+  uint64_t small;
+};
+
+/* Reads a fixnum or bignum, returning 0 for any other value */
+static int read_number(uint64_t value, struct number *n) {
+  if ((value & num_mask) == num_tag) {
+    int64_t i = (int64_t)value >> num_shift;
+    n->negative = i < 0;
+    n->small = i < 0 ? -(uint64_t)i : (uint64_t)i;
+    n->limbs = &n->small;
+    n->length = n->small != 0;
+    return 1;
+  }
+  if ((value & heap_mask) == big_tag) {
+    int64_t *big = (int64_t *)(value - big_tag);
+    n->negative = big[0] < 0;
+    n->length = big[0] < 0 ? -big[0] : big[0];
+    n->limbs = (uint64_t *)(big + 1);
+    return 1;
+  }
+  return 0;
+}
+
+static uint64_t limb(struct number *n, int64_t i) {
+  return i < n->length ? n->limbs[i] : 0;
+}
+
+static int compare_magnitudes(struct number *a, struct number *b) {
+  if (a->length != b->length) {
+    return a->length < b->length ? -1 : 1;
+  }
+  for (int64_t i = a->length - 1; i >= 0; i--) {
+    if (a->limbs[i] != b->limbs[i]) {
+      return a->limbs[i] < b->limbs[i] ? -1 : 1;
+    }
+  }
+  return 0;
+}
+
+static int compare(struct number *a, struct number *b) {
+  if (a->negative != b->negative) {
+    return a->negative ? -1 : 1;
+  }
+  int c = compare_magnitudes(a, b);
+  return a->negative ? -c : c;
+}
+
+/* Trims the limbs of a new bignum and tags it, or returns a fixnum when the
+ * value fits in one */
+static uint64_t make_number(uint64_t *big, int64_t length, int negative) {
+  while (length > 0 && big[length] == 0) {
+    length--;
+  }
+  uint64_t limit = negative ? (uint64_t)1 << 61 : ((uint64_t)1 << 61) - 1;
+  if (length == 0 || (length == 1 && big[1] <= limit)) {
+    int64_t i = length == 0 ? 0 : negative ? -(int64_t)big[1] : (int64_t)big[1];
+    return (uint64_t)i << num_shift;
+  }
+  big[0] = (uint64_t)(negative ? -length : length);
+  return (uint64_t)big | big_tag;
+}
+
+/* The sum of two integers or, with negate set, their difference */
+static uint64_t add_numbers(uint64_t a_value, uint64_t b_value, int negate) {
+  struct number first, second;
+  struct number *a = &first, *b = &second;
+  read_number(a_value, a);
+  read_number(b_value, b);
+  int64_t length = (a->length > b->length ? a->length : b->length) + 1;
+  uint64_t *big = allocate(length + 1, &a_value, &b_value);
+  read_number(a_value, a);
+  read_number(b_value, b);
+  int b_negative = negate ? !b->negative : b->negative;
+  if (a->negative == b_negative) {
+    uint128_t carry = 0;
+    for (int64_t i = 0; i < length; i++) {
+      carry += (uint128_t)limb(a, i) + limb(b, i);
+      big[i + 1] = (uint64_t)carry;
+      carry >>= 64;
+    }
+    return make_number(big, length, a->negative);
+  }
+  /* Opposite signs: the larger magnitude decides the sign */
+  int swap = compare_magnitudes(a, b) < 0;
+  struct number *x = swap ? b : a;
+  struct number *y = swap ? a : b;
+  uint64_t borrow = 0;
+  for (int64_t i = 0; i < length; i++) {
+    uint64_t xi = limb(x, i);
+    uint64_t yi = limb(y, i);
+    uint64_t difference = xi - yi;
+    big[i + 1] = difference - borrow;
+    borrow = xi < yi || difference < borrow;
+  }
+  return make_number(big, length, swap ? b_negative : a->negative);
+}
+
+/* The value of an integer as the nearest double, accumulating from the most
+ * significant limb */
+static double to_double(struct number *n) {
+  double d = 0;
+  for (int64_t i = n->length - 1; i >= 0; i--) {
+    d = d * 18446744073709551616.0 + (double)n->limbs[i];
+  }
+  return n->negative ? -d : d;
+}
+
+static uint64_t make_float(double d) {
+  double *box = (double *)allocate(1, NULL, NULL);
+  *box = d;
+  return (uint64_t)box | float_tag;
+}
+
+/* Reads any number as a double, returning 0 for any other value */
+static int read_double(uint64_t value, double *d) {
+  struct number n;
+  if ((value & heap_mask) == float_tag) {
+    *d = *(double *)(value - float_tag);
+    return 1;
+  }
+  if (!read_number(value, &n)) {
+    return 0;
+  }
+  *d = to_double(&n);
+  return 1;
+}
+
+/* Whether an operation on a and b works on floats, as it does when either is
+ * one; then both are read into x and y, stopping with op's name if either is
+ * not a number */
+static int as_floats(uint64_t a, uint64_t b, double *x, double *y, char *op) {
+  if ((a & heap_mask) != float_tag && (b & heap_mask) != float_tag) {
+    return 0;
+  }
+  if (!read_double(a, x) || !read_double(b, y)) {
+    lisp_error(op);
+  }
+  return 1;
+}
+
+/* The integer a whole, finite double holds */
+static uint64_t from_double(double d) {
+  uint64_t bits;
+  memcpy(&bits, &d, sizeof bits);
+  if ((bits << 1) == 0) {
+    return 0;
+  }
+  uint64_t mantissa = (bits & (((uint64_t)1 << 52) - 1)) | ((uint64_t)1 << 52);
+  int64_t exponent = (int64_t)((bits >> 52) & 0x7ff) - 1075;
+  if (exponent < 0) {
+    mantissa >>= -exponent;
+    exponent = 0;
+  }
+  int64_t length = exponent / 64 + 2;
+  uint64_t *big = allocate(length + 1, NULL, NULL);
+  memset(big, 0, (length + 1) * sizeof(uint64_t));
+  big[1 + exponent / 64] = mantissa << (exponent % 64);
+  if (exponent % 64 != 0) {
+    big[2 + exponent / 64] = mantissa >> (64 - exponent % 64);
+  }
+  return make_number(big, length, bits >> 63);
+}
+
+/* Doubles this large have no fractional part */
+#define whole_limit 4503599627370496.0
+
+/* Rounds down or, with nearest set, to the nearest integer with halves away
+ * from zero, stopping with op's name for anything but a number */
+static uint64_t to_integer(uint64_t value, int nearest, char *op) {
+  struct number n;
+  if (read_number(value, &n)) {
+    return value;
+  }
+  if ((value & heap_mask) != float_tag) {
+    lisp_error(op);
+  }
+  double d = *(double *)(value - float_tag);
+  if (d != d || d - d != 0) {
+    lisp_error(op);
+  }
+  if (d > -whole_limit && d < whole_limit) {
+    double truncated = (double)(int64_t)d;
+    if (nearest && (d - truncated >= 0.5 || d - truncated <= -0.5)) {
+      d = truncated + (d < 0 ? -1 : 1);
+    } else if (!nearest && truncated > d) {
+      d = truncated - 1;
+    } else {
+      d = truncated;
+    }
+  }
+  return from_double(d);
+}
+
+/* The runtime functions that allocate take the compiled code's roots after
+ * their arguments */
+
+/* Where the compiled code goes when an object it allocates does not fit */
+uint64_t lisp_collect(uint64_t words, uint64_t *bottom, uint64_t *top) {
+  set_roots(bottom, top);
+  collect(NULL, NULL);
+  if ((uint64_t)(*heap_limit - *heap_pointer) < words) {
+    out_of_memory();
+  }
+  return 0;
+}
+
+uint64_t lisp_floor(uint64_t a, uint64_t *bottom, uint64_t *top) {
+  set_roots(bottom, top);
+  return to_integer(a, 0, "floor");
+}
+
+uint64_t lisp_round(uint64_t a, uint64_t *bottom, uint64_t *top) {
+  set_roots(bottom, top);
+  return to_integer(a, 1, "round");
+}
+
+uint64_t lisp_to_float(uint64_t a, uint64_t *bottom, uint64_t *top) {
+  double d;
+  set_roots(bottom, top);
+  if (!read_double(a, &d)) {
+    lisp_error("toFloat");
+  }
+  return (a & heap_mask) == float_tag ? a : make_float(d);
+}
+
+uint64_t lisp_add(uint64_t a, uint64_t b, uint64_t *bottom, uint64_t *top) {
+  struct number x, y;
+  double dx, dy;
+  set_roots(bottom, top);
+  if (as_floats(a, b, &dx, &dy, "+")) {
+    return make_float(dx + dy);
+  }
+  if (!read_number(a, &x) || !read_number(b, &y)) {
+    lisp_error("+");
+  }
+  return add_numbers(a, b, 0);
+}
+
+uint64_t lisp_sub(uint64_t a, uint64_t b, uint64_t *bottom, uint64_t *top) {
+  struct number x, y;
+  double dx, dy;
+  set_roots(bottom, top);
+  if (as_floats(a, b, &dx, &dy, "-")) {
+    return make_float(dx - dy);
+  }
+  if (!read_number(a, &x) || !read_number(b, &y)) {
+    lisp_error("-");
+  }
+  return add_numbers(a, b, 1);
+}
+
+uint64_t lisp_add1(uint64_t a, uint64_t *bottom, uint64_t *top) {
+  struct number x;
+  double dx, dy;
+  set_roots(bottom, top);
+  if (as_floats(a, 1 << num_shift, &dx, &dy, "add1")) {
+    return make_float(dx + dy);
+  }
+  if (!read_number(a, &x)) {
+    lisp_error("add1");
+  }
+  return add_numbers(a, 1 << num_shift, 0);
+}
+
+uint64_t lisp_sub1(uint64_t a, uint64_t *bottom, uint64_t *top) {
+  struct number x;
+  double dx, dy;
+  set_roots(bottom, top);
+  if (as_floats(a, 1 << num_shift, &dx, &dy, "sub1")) {
+    return make_float(dx - dy);
+  }
+  if (!read_number(a, &x)) {
+    lisp_error("sub1");
+  }
+  return add_numbers(a, 1 << num_shift, 1);
+}
+
+uint64_t lisp_less(uint64_t a, uint64_t b) {
+  struct number x, y;
+  double dx, dy;
+  if (as_floats(a, b, &dx, &dy, "<")) {
+    return ((uint64_t)(dx < dy) << bool_shift) | bool_tag;
+  }
+  if (!read_number(a, &x) || !read_number(b, &y)) {
+    lisp_error("<");
+  }
+  return ((uint64_t)(compare(&x, &y) < 0) << bool_shift) | bool_tag;
+}
+
+/* Identity, except that numbers compare by value */
+uint64_t lisp_equal(uint64_t a, uint64_t b) {
+  struct number x, y;
+  double dx, dy;
+  int equal;
+  if (((a & heap_mask) == float_tag || (b & heap_mask) == float_tag) &&
+      read_double(a, &dx) && read_double(b, &dy)) {
+    equal = dx == dy;
+  } else {
+    equal = a == b || (read_number(a, &x) && read_number(b, &y) &&
+                       compare(&x, &y) == 0);
+  }
+  return ((uint64_t)equal << bool_shift) | bool_tag;
+}
+
+/* Fixnum zero never gets here, and bignums are never zero */
+uint64_t lisp_is_zero(uint64_t a) {
+  struct number x;
+  double d;
+  if (read_number(a, &x)) {
+    return bool_tag;
+  }
+  if (!read_double(a, &d)) {
+    lisp_error("isZero");
+  }
+  return ((uint64_t)(d == 0) << bool_shift) | bool_tag;
+}
+
+/* Prints the fewest digits that read back as the same double, in positional
+ * notation unless the exponent is below -5 or above 15, and always with a `.`
+ * or an exponent so that the output reads back as a float */
+static void print_float(double d) {
+  if (d != d) {
+    printf("nan");
+    return;
+  }
+  if (d - d != 0) {
+    printf(d < 0 ? "-inf" : "inf");
+    return;
+  }
+  char buffer[32];
+  int precision = 0;
+  do {
+    snprintf(buffer, sizeof buffer, "%.*e", precision++, d);
+  } while (strtod(buffer, NULL) != d);
+  char *e = strchr(buffer, 'e');
+  int exponent = atoi(e + 1);
+  *e = 0;
+  char *mantissa = buffer;
+  if (*mantissa == '-') {
+    printf("-");
+    mantissa++;
+  }
+  if (exponent < -5 || exponent > 15) {
+    printf("%se%d", mantissa, exponent);
+    return;
+  }
+  char digits[32];
+  int count = 0;
+  for (char *c = mantissa; *c; c++) {
+    if (*c != '.') {
+      digits[count++] = *c;
+    }
+  }
+  digits[count] = 0;
+  if (exponent < 0) {
+    printf("0.");
+    for (int i = -1; i > exponent; i--) {
+      printf("0");
+    }
+    printf("%s", digits);
+  } else {
+    for (int i = 0; i <= exponent; i++) {
+      printf("%c", i < count ? digits[i] : '0');
+    }
+    printf(".%s", count > exponent + 1 ? digits + exponent + 1 : "0");
+  }
+}
+
+/* Prints a bignum in decimal, dividing by 10^19 to get 19 digits at a time */
+static void print_big(int64_t *big) {
+  const uint64_t chunk = 10000000000000000000u;
+  int64_t length = big[0] < 0 ? -big[0] : big[0];
+  uint64_t *magnitude = malloc(length * sizeof(uint64_t));
+  uint64_t *chunks = malloc(2 * length * sizeof(uint64_t));
+  int64_t count = 0;
+  for (int64_t i = 0; i < length; i++) {
+    magnitude[i] = (uint64_t)big[i + 1];
+  }
+  while (length > 0) {
+    uint128_t remainder = 0;
+    for (int64_t i = length - 1; i >= 0; i--) {
+      uint128_t current = (remainder << 64) | magnitude[i];
+      magnitude[i] = (uint64_t)(current / chunk);
+      remainder = current % chunk;
+    }
+    chunks[count++] = (uint64_t)remainder;
+    while (length > 0 && magnitude[length - 1] == 0) {
+      length--;
+    }
+  }
+  if (big[0] < 0) {
+    printf("-");
+  }
+  printf("%" PRIu64, chunks[count - 1]);
+  for (int64_t i = count - 2; i >= 0; i--) {
+    printf("%019" PRIu64, chunks[i]);
+  }
+  free(magnitude);
+  free(chunks);
+}
+
 uint64_t print_value(uint64_t value) {
   if ((value & num_mask) == num_tag) {
     int64_t ivalue = (int64_t)value;
@@ -39,6 +588,10 @@ uint64_t print_value(uint64_t value) {
     printf(" ");
     print_value(v2);
     printf(")");
+  } else if ((value & heap_mask) == float_tag) {
+    print_float(*(double *)(value - float_tag));
+  } else if ((value & heap_mask) == big_tag) {
+    print_big((int64_t *)(value - big_tag));
   } else if ((value & nil_mask) == nil_tag) {
     printf("()");
   } else if ((value & vector_mask) == vector_tag) {
@@ -64,9 +617,80 @@ void lisp_error(char *exp) {
   exit(1);
 }
 
+/* Each semispace is 1 MiB unless the program is told otherwise */
+#define default_heap_words (1 << 17)
+
+static void usage(char *program) {
+  fprintf(stderr, "usage: %s [--heap-size <bytes>[k|m|g]]\n", program);
+  exit(2);
+}
+
+/* A size in bytes such as 65536, 64k or 16m, as the number of words of each
+ * semispace */
+static uint64_t parse_heap_size(char *program, char *text) {
+  char *end;
+  uint64_t bytes = strtoull(text, &end, 10);
+  switch (*end) {
+  case 'k':
+  case 'K':
+    bytes <<= 10;
+    end++;
+    break;
+  case 'm':
+  case 'M':
+    bytes <<= 20;
+    end++;
+    break;
+  case 'g':
+  case 'G':
+    bytes <<= 30;
+    end++;
+    break;
+  }
+  if (end == text || *end != '\0' || bytes < 64 ||
+      bytes > ((uint64_t)1 << 40)) {
+    fprintf(stderr, "invalid heap size %s (expected 64 bytes to 1 TiB)\n",
+            text);
+    usage(program);
+  }
+  return bytes / sizeof(uint64_t);
+}
+
+/* The compiled code stops with an error rather than grow its frames past this
+ * much of the stack, leaving the rest for the runtime's own calls, such as
+ * printing a deeply nested value, or a quarter of the stack if that is less */
+#define stack_reserve (256 * 1024)
+
+/* The lowest address the compiled code's frames may reach, from the size the
+ * system allows the stack, or 8 MiB when it sets no limit */
+static void *stack_limit(void) {
+  struct rlimit limit;
+  uint64_t size = 8 << 20;
+  uintptr_t here = (uintptr_t)__builtin_frame_address(0);
+  if (getrlimit(RLIMIT_STACK, &limit) == 0 && limit.rlim_cur != RLIM_INFINITY) {
+    size = limit.rlim_cur;
+  }
+  uint64_t reserve = size / 4 < stack_reserve ? size / 4 : stack_reserve;
+  return (void *)(here - size + reserve);
+}
+
 int main(int argc, char **argv) {
-  void *heap = malloc(4096);
-  print_value(lisp_entry(heap));
+  uint64_t words = default_heap_words;
+  char *size = getenv("LISP_HEAP_SIZE");
+  if (size && *size) {
+    words = parse_heap_size(argv[0], size);
+  }
+  for (int i = 1; i < argc; i++) {
+    if (strcmp(argv[i], "--heap-size") == 0 && i + 1 < argc) {
+      words = parse_heap_size(argv[0], argv[++i]);
+    } else if (strncmp(argv[i], "--heap-size=", 12) == 0) {
+      words = parse_heap_size(argv[0], argv[i] + 12);
+    } else {
+      usage(argv[0]);
+    }
+  }
+  heap_init(words);
+  print_value(lisp_entry(space, space + words, stack_limit()));
   printf("\n");
   return 0;
 }
@@ -0,0 +1,834 @@
+use std::collections::HashMap;
+
+use crate::asm::{Directive, Operand, Register, RegisterClass};
+use crate::ast::float_literal;
+use crate::bignum::BigInt;
+use crate::compile::{
+    BIG_TAG, BOOL_MASK, BOOL_SHIFT, BOOL_TAG, FLOAT_TAG, HEAP_MASK, NIL_MASK, NIL_TAG, NUM_MASK,
+    NUM_MAX, NUM_MIN, NUM_SHIFT, NUM_TAG, PAIR_TAG, VEC_MASK, VEC_TAG,
+};
+
+/// Where the directive list is laid out; instructions take four bytes, data its own size
+const IMAGE_BASE: u64 = 0x0040_0000;
+const HEAP_BASE: u64 = 0x1000_0000;
+/// Matches one semispace of the default heap in runtime.c's `main`
+pub const HEAP_SIZE: u64 = 1 << 20;
+const STACK_TOP: u64 = 0x7000_0000;
+const STACK_SIZE: u64 = 1 << 20;
+/// Where the runtime's `malloc` hands out memory, growing as needed
+const MALLOC_BASE: u64 = 0x2000_0000;
+/// `lisp_entry` returns here, which stops the machine
+const HALT: u64 = 0xdead_0000;
+
+const SP: usize = 31;
+
+/// What running a program printed and how it exited, as the compiled binary would
+#[derive(Clone, Debug, PartialEq)]
+pub struct Output {
+    pub stdout: String,
+    pub exit_code: i32,
+}
+
+struct Region {
+    base: u64,
+    bytes: Vec<u8>,
+}
+
+pub struct Machine {
+    regs: [u64; 32],
+    n: bool,
+    z: bool,
+    c: bool,
+    v: bool,
+    pc: u64,
+    instructions: Vec<Directive>,
+    by_address: HashMap<u64, usize>,
+    labels: HashMap<String, u64>,
+    image: Region,
+    /// Both semispaces, the one `lisp_entry` starts in first
+    heap: Region,
+    heap_size: u64,
+    /// The semispace objects are allocated in; a collection copies them to the other one
+    space: u64,
+    stack: Region,
+    malloc: Region,
+    stdout: String,
+    pub steps: u64,
+}
+
+/// Where a collection has got to: the next free word of the semispace it copies to, the
+/// objects it has moved and where to, and the copied pairs and vectors whose fields it has yet
+/// to forward
+struct Collection {
+    next: u64,
+    forwarded: HashMap<u64, u64>,
+    pending: Vec<u64>,
+}
+
+enum Step {
+    Continue,
+    Halt(i32),
+}
+
+fn register_index(r: &Register) -> usize {
+    match r {
+        Register::X0 => 0,
+        Register::X1 => 1,
+        Register::X2 => 2,
+        Register::X3 => 3,
+        Register::X4 => 4,
+        Register::X5 => 5,
+        Register::X6 => 6,
+        Register::X7 => 7,
+        Register::X8 => 8,
+        Register::X9 => 9,
+        Register::X10 => 10,
+        Register::X11 => 11,
+        Register::X12 => 12,
+        Register::X13 => 13,
+        Register::X14 => 14,
+        Register::X15 => 15,
+        Register::X16 => 16,
+        Register::X19 => 19,
+        Register::X20 => 20,
+        Register::X21 => 21,
+        Register::X22 => 22,
+        Register::X23 => 23,
+        Register::X24 => 24,
+        Register::X25 => 25,
+        Register::X26 => 26,
+        Register::X27 => 27,
+        Register::X28 => 28,
+        Register::Fp => 29,
+        Register::Lr => 30,
+        Register::Sp => SP,
+        Register::Virtual(_) => unreachable!("Machine::new rejects virtual registers"),
+    }
+}
+
+fn is_instruction(d: &Directive) -> bool {
+    !matches!(
+        d,
+        Directive::Global(_)
+            | Directive::Extern(_)
+            | Directive::Section(_)
+            | Directive::Label(_)
+            | Directive::DqLabel(_)
+            | Directive::DqString(_)
+            | Directive::DqInt(_)
+            | Directive::Align(_)
+            | Directive::Comment(_)
+    )
+}
+
+impl Machine {
+    /// Lays the directives out in memory and resolves labels; a semispace of `heap_size` bytes
+    /// is handed to `lisp_entry`, along with where it ends and how far the stack may grow
+    pub fn new(directives: &[Directive], heap_size: u64) -> Result<Self, String> {
+        let mut labels = HashMap::new();
+        let mut cursor = IMAGE_BASE;
+        for d in directives {
+            match d {
+                Directive::Label(l) => {
+                    if labels.contains_key(l) {
+                        return Err(format!("label defined twice: {}", l));
+                    }
+                    labels.insert(l.clone(), cursor);
+                }
+                Directive::DqLabel(_) | Directive::DqInt(_) => cursor += 8,
+                Directive::DqString(s) => cursor += s.len() as u64 + 1,
+                Directive::Align(n) => cursor = cursor.next_multiple_of(1 << n),
+                d if is_instruction(d) => cursor += 4,
+                _ => (),
+            }
+        }
+
+        let mut image = vec![0; (cursor - IMAGE_BASE) as usize];
+        let mut instructions = Vec::new();
+        let mut by_address = HashMap::new();
+        let mut cursor = IMAGE_BASE;
+        for d in directives {
+            let at = (cursor - IMAGE_BASE) as usize;
+            match d {
+                Directive::DqLabel(l) => {
+                    let address = labels
+                        .get(l)
+                        .ok_or_else(|| format!("undefined label: {}", l))?;
+                    image[at..at + 8].copy_from_slice(&address.to_le_bytes());
+                    cursor += 8;
+                }
+                Directive::DqInt(i) => {
+                    image[at..at + 8].copy_from_slice(&i.to_le_bytes());
+                    cursor += 8;
+                }
+                Directive::DqString(s) => {
+                    image[at..at + s.len()].copy_from_slice(s.as_bytes());
+                    cursor += s.len() as u64 + 1;
+                }
+                Directive::Align(n) => cursor = cursor.next_multiple_of(1 << n),
+                d if is_instruction(d) => {
+                    if let Some(r) = [d.uses(), d.defs()]
+                        .concat()
+                        .into_iter()
+                        .find(|r| r.class() == RegisterClass::Virtual)
+                    {
+                        return Err(format!("unallocated register {}", r));
+                    }
+                    by_address.insert(cursor, instructions.len());
+                    instructions.push(d.clone());
+                    cursor += 4;
+                }
+                _ => (),
+            }
+        }
+
+        let entry = *labels
+            .get("lisp_entry")
+            .ok_or_else(|| "no lisp_entry label".to_string())?;
+        let mut regs = [0; 32];
+        regs[0] = HEAP_BASE;
+        regs[1] = HEAP_BASE + heap_size;
+        regs[2] = STACK_TOP - STACK_SIZE;
+        regs[30] = HALT;
+        regs[SP] = STACK_TOP;
+
+        Ok(Machine {
+            regs,
+            n: false,
+            z: false,
+            c: false,
+            v: false,
+            pc: entry,
+            instructions,
+            by_address,
+            labels,
+            image: Region {
+                base: IMAGE_BASE,
+                bytes: image,
+            },
+            heap: Region {
+                base: HEAP_BASE,
+                bytes: vec![0; 2 * heap_size as usize],
+            },
+            heap_size,
+            space: HEAP_BASE,
+            stack: Region {
+                base: STACK_TOP - STACK_SIZE,
+                bytes: vec![0; STACK_SIZE as usize],
+            },
+            malloc: Region {
+                base: MALLOC_BASE,
+                bytes: Vec::new(),
+            },
+            stdout: String::new(),
+            steps: 0,
+        })
+    }
+
+    fn region(&mut self, address: u64, len: u64) -> Result<(&mut Vec<u8>, usize), String> {
+        for region in [
+            &mut self.image,
+            &mut self.heap,
+            &mut self.stack,
+            &mut self.malloc,
+        ] {
+            let end = region.base + region.bytes.len() as u64;
+            if address >= region.base && address + len <= end {
+                return Ok((&mut region.bytes, (address - region.base) as usize));
+            }
+        }
+        Err(format!("memory access out of bounds: {:#x}", address))
+    }
+
+    pub fn load(&mut self, address: u64) -> Result<u64, String> {
+        let (bytes, at) = self.region(address, 8)?;
+        Ok(u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap()))
+    }
+
+    fn store(&mut self, address: u64, value: u64) -> Result<(), String> {
+        let (bytes, at) = self.region(address, 8)?;
+        bytes[at..at + 8].copy_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    fn c_string(&mut self, address: u64) -> Result<String, String> {
+        let mut s = Vec::new();
+        for a in address.. {
+            let (bytes, at) = self.region(a, 1)?;
+            match bytes[at] {
+                0 => break,
+                b => s.push(b),
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).into_owned())
+    }
+
+    pub fn reg(&self, r: &Register) -> u64 {
+        self.regs[register_index(r)]
+    }
+
+    fn set(&mut self, dest: &Operand, value: u64) -> Result<(), String> {
+        match dest {
+            Operand::Reg(r) => {
+                self.regs[register_index(r)] = value;
+                Ok(())
+            }
+            _ => Err(format!("expected a register, found {}", dest)),
+        }
+    }
+
+    fn value(&self, o: &Operand) -> Result<u64, String> {
+        match o {
+            Operand::Reg(r) => Ok(self.reg(r)),
+            Operand::Imm(i) => Ok(*i as u64),
+            _ => Err(format!("expected a register or immediate, found {}", o)),
+        }
+    }
+
+    fn address(&self, o: &Operand) -> Result<u64, String> {
+        match o {
+            Operand::MemOffset(offset, base) => {
+                Ok(self.value(base)?.wrapping_add(self.value(offset)?))
+            }
+            Operand::RegOffset(base, offset) => Ok(self.reg(base).wrapping_add(*offset as u64)),
+            _ => Err(format!("expected a memory operand, found {}", o)),
+        }
+    }
+
+    fn label(&self, l: &str) -> Result<u64, String> {
+        self.labels
+            .get(l)
+            .copied()
+            .ok_or_else(|| format!("undefined label: {}", l))
+    }
+
+    fn condition(&self, cond: &str) -> Result<bool, String> {
+        Ok(match cond {
+            "eq" => self.z,
+            "ne" => !self.z,
+            "cs" | "hs" => self.c,
+            "cc" | "lo" => !self.c,
+            "mi" => self.n,
+            "pl" => !self.n,
+            "vs" => self.v,
+            "vc" => !self.v,
+            "hi" => self.c && !self.z,
+            "ls" => !self.c || self.z,
+            "ge" => self.n == self.v,
+            "lt" => self.n != self.v,
+            "gt" => !self.z && self.n == self.v,
+            "le" => self.z || self.n != self.v,
+            "al" => true,
+            _ => return Err(format!("unknown condition code: {}", cond)),
+        })
+    }
+
+    fn compare(&mut self, a: u64, b: u64) {
+        let result = a.wrapping_sub(b);
+        self.n = (result as i64) < 0;
+        self.z = result == 0;
+        self.c = a >= b;
+        self.v = (((a ^ b) & (a ^ result)) >> 63) == 1;
+    }
+
+    /// The flags `adds` sets; `compare` gives those of `subs`
+    fn add_flags(&mut self, a: u64, b: u64) {
+        let result = a.wrapping_add(b);
+        self.n = (result as i64) < 0;
+        self.z = result == 0;
+        self.c = result < a;
+        self.v = (((a ^ result) & (b ^ result)) >> 63) == 1;
+    }
+
+    fn branch(&mut self, taken: bool, label: &str) -> Result<(), String> {
+        if taken {
+            self.pc = self.label(label)?;
+        }
+        Ok(())
+    }
+
+    /// Allocates like `malloc`, 16-byte aligned
+    fn malloc(&mut self, size: u64) -> u64 {
+        let address = self.malloc.base + self.malloc.bytes.len() as u64;
+        let size = size.next_multiple_of(16);
+        self.malloc
+            .bytes
+            .resize(self.malloc.bytes.len() + size as usize, 0);
+        address
+    }
+
+    /// The value of a fixnum or bignum, or `None` for anything else
+    fn number(&mut self, value: u64) -> Result<Option<BigInt>, String> {
+        let value_i = value as i64;
+        if value_i & NUM_MASK == NUM_TAG {
+            return Ok(Some(BigInt::from_i64(value_i >> NUM_SHIFT)));
+        }
+        if value_i & HEAP_MASK != BIG_TAG {
+            return Ok(None);
+        }
+        let base = value - BIG_TAG as u64;
+        let header = self.load(base)? as i64;
+        let mut limbs = Vec::new();
+        for i in 0..header.unsigned_abs() {
+            limbs.push(self.load(base + 8 * (i + 1))?);
+        }
+        Ok(Some(BigInt::from_parts(header < 0, limbs)))
+    }
+
+    /// A fixnum if `n` fits in one, otherwise a new bignum, as runtime.c's `make_number` does
+    fn make_number(&mut self, n: BigInt) -> Result<u64, String> {
+        match n.to_i64() {
+            Some(i) if (NUM_MIN..=NUM_MAX).contains(&i) => Ok(((i << NUM_SHIFT) | NUM_TAG) as u64),
+            _ => {
+                let base = self.malloc(8 * (n.limbs().len() as u64 + 1));
+                let length = n.limbs().len() as i64;
+                let header = if n.is_negative() { -length } else { length };
+                self.store(base, header as u64)?;
+                for (i, limb) in n.limbs().iter().enumerate() {
+                    self.store(base + 8 * (i as u64 + 1), *limb)?;
+                }
+                Ok(base | BIG_TAG as u64)
+            }
+        }
+    }
+
+    fn stuck(&mut self, message: &str) -> Step {
+        self.stdout.push_str(&format!("Stuck[{}]", message));
+        Step::Halt(1)
+    }
+
+    /// Any number as a double, or `None` for anything else
+    fn float(&mut self, value: u64) -> Result<Option<f64>, String> {
+        if is_float(value) {
+            return Ok(Some(f64::from_bits(self.load(value - FLOAT_TAG as u64)?)));
+        }
+        Ok(self.number(value)?.map(|n| n.to_f64()))
+    }
+
+    fn make_float(&mut self, x: f64) -> Result<u64, String> {
+        let base = self.malloc(8);
+        self.store(base, x.to_bits())?;
+        Ok(base | FLOAT_TAG as u64)
+    }
+
+    /// The slow paths of arithmetic and comparisons, and the conversions between integers and
+    /// floats, on the argument in X0 and for binary operations the one in X1
+    fn generic(&mut self, name: &str) -> Result<Step, String> {
+        let (op, binary) = match name {
+            "lisp_add" => ("+", true),
+            "lisp_sub" => ("-", true),
+            "lisp_less" => ("<", true),
+            "lisp_equal" => ("==", true),
+            "lisp_add1" => ("add1", false),
+            "lisp_sub1" => ("sub1", false),
+            "lisp_is_zero" => ("isZero", false),
+            "lisp_floor" => ("floor", false),
+            "lisp_round" => ("round", false),
+            "lisp_to_float" => ("toFloat", false),
+            _ => return Err(format!("call to unknown function: {}", name)),
+        };
+        let a = self.regs[0];
+        // The fixnum 1 that add1 and sub1 add and subtract
+        let b = if binary {
+            self.regs[1]
+        } else {
+            (1 << NUM_SHIFT) as u64
+        };
+        let floats = is_float(a) || is_float(b);
+        let (x, y) = (self.float(a)?, self.float(b)?);
+        let (m, n) = (self.number(a)?, self.number(b)?);
+        let result = match (name, x, y) {
+            ("lisp_equal", Some(x), Some(y)) if floats => bool_value(x == y),
+            ("lisp_equal", ..) => bool_value(a == b || (m.is_some() && m == n)),
+            (_, None, _) | (_, _, None) => return Ok(self.stuck(op)),
+            ("lisp_add" | "lisp_add1", Some(x), Some(y)) if floats => self.make_float(x + y)?,
+            ("lisp_sub" | "lisp_sub1", Some(x), Some(y)) if floats => self.make_float(x - y)?,
+            ("lisp_less", Some(x), Some(y)) if floats => bool_value(x < y),
+            ("lisp_is_zero", Some(x), _) => bool_value(x == 0.0),
+            ("lisp_to_float", _, _) if is_float(a) => a,
+            ("lisp_to_float", Some(x), _) => self.make_float(x)?,
+            ("lisp_floor" | "lisp_round", _, _) if !is_float(a) => a,
+            ("lisp_floor" | "lisp_round", Some(x), _) if !x.is_finite() => {
+                return Ok(self.stuck(op))
+            }
+            ("lisp_floor", Some(x), _) => self.make_number(BigInt::from_f64(x.floor()))?,
+            ("lisp_round", Some(x), _) => self.make_number(BigInt::from_f64(x.round()))?,
+            _ => {
+                let (m, n) = (m.unwrap(), n.unwrap());
+                match name {
+                    "lisp_add" | "lisp_add1" => self.make_number(m.add(&n))?,
+                    "lisp_sub" | "lisp_sub1" => self.make_number(m.sub(&n))?,
+                    _ => bool_value(m < n),
+                }
+            }
+        };
+        self.regs[0] = result;
+        Ok(Step::Continue)
+    }
+
+    /// The number of words of the heap object `value` points to, as `object_words` in runtime.c
+    fn object_words(&mut self, value: u64) -> Result<u64, String> {
+        let header = self.load(value & !(HEAP_MASK as u64))? as i64;
+        Ok(match value as i64 & HEAP_MASK {
+            FLOAT_TAG => 1,
+            BIG_TAG => 1 + header.unsigned_abs(),
+            PAIR_TAG => 2,
+            _ => 1 + header as u64,
+        })
+    }
+
+    /// Copies the object `value` points to out of the semispace at `from`, once, returning the
+    /// value that points to the copy; anything else stays as it is. Mirrors `forward` in
+    /// runtime.c, with a map of the objects already moved in place of its bitmap.
+    fn forward(&mut self, value: u64, from: u64, copy: &mut Collection) -> Result<u64, String> {
+        let tag = value as i64 & HEAP_MASK;
+        if ![FLOAT_TAG, PAIR_TAG, BIG_TAG, VEC_TAG].contains(&tag) {
+            return Ok(value);
+        }
+        let object = value - tag as u64;
+        if object < from || object >= from + self.heap_size {
+            return Ok(value);
+        }
+        if let Some(moved) = copy.forwarded.get(&object) {
+            return Ok(moved | tag as u64);
+        }
+        let words = self.object_words(value)?;
+        let moved = copy.next;
+        copy.next += 8 * words;
+        for i in 0..words {
+            let word = self.load(object + 8 * i)?;
+            self.store(moved + 8 * i, word)?;
+        }
+        copy.forwarded.insert(object, moved);
+        if tag == PAIR_TAG || tag == VEC_TAG {
+            copy.pending.push(moved | tag as u64);
+        }
+        Ok(moved | tag as u64)
+    }
+
+    /// Copies everything reachable from the slots from `bottom` up to `top` into the other
+    /// semispace and points the heap slots at `top` there, as `collect` in runtime.c does
+    fn collect(&mut self, bottom: u64, top: u64) -> Result<(), String> {
+        let from = self.space;
+        self.space = HEAP_BASE + (from - HEAP_BASE + self.heap_size) % (2 * self.heap_size);
+        let mut copy = Collection {
+            next: self.space,
+            forwarded: HashMap::new(),
+            pending: Vec::new(),
+        };
+        for slot in (bottom..top).step_by(8) {
+            let value = self.load(slot)?;
+            let value = self.forward(value, from, &mut copy)?;
+            self.store(slot, value)?;
+        }
+        while let Some(value) = copy.pending.pop() {
+            let object = value & !(HEAP_MASK as u64);
+            let fields = match value as i64 & HEAP_MASK {
+                PAIR_TAG => 0..2,
+                _ => 1..1 + self.load(object)?,
+            };
+            for i in fields {
+                let field = self.load(object + 8 * i)?;
+                let field = self.forward(field, from, &mut copy)?;
+                self.store(object + 8 * i, field)?;
+            }
+        }
+        self.store(top, self.space + self.heap_size)?;
+        self.store(top + 8, copy.next)
+    }
+
+    /// Calls to symbols outside the program stand in for the C runtime
+    fn call_runtime(&mut self, name: &str) -> Result<Step, String> {
+        match name {
+            "lisp_error" => {
+                let message = self.c_string(self.regs[0])?;
+                Ok(self.stuck(&message))
+            }
+            "print_value" => {
+                let printed = self.print_value(self.regs[0])?;
+                self.stdout.push_str(&printed);
+                Ok(Step::Continue)
+            }
+            "lisp_collect" => {
+                let (words, bottom, top) = (self.regs[0], self.regs[1], self.regs[2]);
+                self.collect(bottom, top)?;
+                let free = self.load(top)? - self.load(top + 8)?;
+                if free < 8 * words {
+                    return Ok(self.stuck("out of memory"));
+                }
+                Ok(Step::Continue)
+            }
+            _ => self.generic(name),
+        }
+    }
+
+    fn step(&mut self) -> Result<Step, String> {
+        if self.pc == HALT {
+            return Ok(Step::Halt(0));
+        }
+        let index = *self
+            .by_address
+            .get(&self.pc)
+            .ok_or_else(|| format!("jump to non-instruction address {:#x}", self.pc))?;
+        let instruction = self.instructions[index].clone();
+        self.pc += 4;
+        self.steps += 1;
+
+        match &instruction {
+            Directive::Mov(dest, src) => self.set(dest, self.value(src)?)?,
+            Directive::Add(dest, src) => {
+                self.set(dest, self.value(dest)?.wrapping_add(self.value(src)?))?
+            }
+            Directive::Sub(dest, src) => {
+                self.set(dest, self.value(dest)?.wrapping_sub(self.value(src)?))?
+            }
+            Directive::Adds(dest, src) => {
+                let (a, b) = (self.value(dest)?, self.value(src)?);
+                self.add_flags(a, b);
+                self.set(dest, a.wrapping_add(b))?
+            }
+            Directive::Subs(dest, src) => {
+                let (a, b) = (self.value(dest)?, self.value(src)?);
+                self.compare(a, b);
+                self.set(dest, a.wrapping_sub(b))?
+            }
+            Directive::Mul(dest, src) => {
+                self.set(dest, self.value(dest)?.wrapping_mul(self.value(src)?))?
+            }
+            Directive::Sdiv(dest, src) => {
+                let (a, b) = (self.value(dest)? as i64, self.value(src)? as i64);
+                // AArch64 division by zero yields zero rather than trapping
+                let q = if b == 0 { 0 } else { a.wrapping_div(b) };
+                self.set(dest, q as u64)?
+            }
+            Directive::Lsl(dest, src) => {
+                self.set(dest, self.value(dest)? << (self.value(src)? % 64))?
+            }
+            Directive::Lsr(dest, src) => {
+                self.set(dest, self.value(dest)? >> (self.value(src)? % 64))?
+            }
+            Directive::Asr(dest, src) => {
+                let shifted = (self.value(dest)? as i64) >> (self.value(src)? % 64);
+                self.set(dest, shifted as u64)?
+            }
+            Directive::And(dest, src) => self.set(dest, self.value(dest)? & self.value(src)?)?,
+            Directive::Orr(dest, src) => self.set(dest, self.value(dest)? | self.value(src)?)?,
+            Directive::Cmp(a, b) => self.compare(self.value(a)?, self.value(b)?),
+            Directive::Cset(dest, cond) => self.set(dest, self.condition(cond)? as u64)?,
+            Directive::Adr(dest, label) => self.set(dest, self.label(label)?)?,
+            Directive::B(l) => self.branch(true, l)?,
+            Directive::Beq(l) => self.branch(self.condition("eq")?, l)?,
+            Directive::Bne(l) => self.branch(self.condition("ne")?, l)?,
+            Directive::Blt(l) => self.branch(self.condition("lt")?, l)?,
+            Directive::Bge(l) => self.branch(self.condition("ge")?, l)?,
+            Directive::Bgt(l) => self.branch(self.condition("gt")?, l)?,
+            Directive::Ble(l) => self.branch(self.condition("le")?, l)?,
+            Directive::Bls(l) => self.branch(self.condition("ls")?, l)?,
+            Directive::Bvs(l) => self.branch(self.condition("vs")?, l)?,
+            Directive::Br(target) => self.pc = self.value(target)?,
+            Directive::Bl(l) => match self.labels.get(l) {
+                Some(&target) => {
+                    self.regs[30] = self.pc;
+                    self.pc = target;
+                }
+                None => return self.call_runtime(l),
+            },
+            Directive::Ret => self.pc = self.regs[30],
+            Directive::Str(dest, src) => {
+                let address = self.address(dest)?;
+                self.store(address, self.value(src)?)?
+            }
+            Directive::Ldr(dest, src) => {
+                let address = self.address(src)?;
+                let value = self.load(address)?;
+                self.set(dest, value)?
+            }
+            Directive::Stp(r1, r2, dest) => {
+                let address = self.address(dest)?;
+                self.store(address, self.value(r1)?)?;
+                self.store(address + 8, self.value(r2)?)?
+            }
+            Directive::Ldp(r1, r2, src) => {
+                let address = self.address(src)?;
+                let (v1, v2) = (self.load(address)?, self.load(address + 8)?);
+                self.set(r1, v1)?;
+                self.set(r2, v2)?
+            }
+            other => return Err(format!("cannot execute {:?}", other)),
+        }
+        Ok(Step::Continue)
+    }
+
+    /// Runs until `lisp_entry` returns or `lisp_error` is called, giving up after `fuel` instructions
+    pub fn run(&mut self, fuel: u64) -> Result<i32, String> {
+        loop {
+            if self.steps >= fuel {
+                return Err(format!("no result after {} instructions", fuel));
+            }
+            if let Step::Halt(code) = self.step()? {
+                return Ok(code);
+            }
+        }
+    }
+
+    /// Mirrors `print_value` in runtime.c
+    pub fn print_value(&mut self, value: u64) -> Result<String, String> {
+        let value_i = value as i64;
+        if value_i & NUM_MASK == NUM_TAG {
+            Ok(format!("{}", value_i >> NUM_SHIFT))
+        } else if value_i & BOOL_MASK == BOOL_TAG {
+            Ok(if value >> BOOL_SHIFT != 0 {
+                "true"
+            } else {
+                "false"
+            }
+            .to_string())
+        } else if value_i & HEAP_MASK == PAIR_TAG {
+            let base = value - PAIR_TAG as u64;
+            let (v1, v2) = (self.load(base)?, self.load(base + 8)?);
+            Ok(format!(
+                "(pair {} {})",
+                self.print_value(v1)?,
+                self.print_value(v2)?
+            ))
+        } else if is_float(value) {
+            let bits = self.load(value - FLOAT_TAG as u64)?;
+            Ok(float_literal(f64::from_bits(bits)))
+        } else if value_i & HEAP_MASK == BIG_TAG {
+            Ok(self.number(value)?.unwrap().to_string())
+        } else if value_i & NIL_MASK == NIL_TAG {
+            Ok("()".to_string())
+        } else if value_i & VEC_MASK == VEC_TAG {
+            let base = value - VEC_TAG as u64;
+            let length = self.load(base)?;
+            let mut elements = Vec::new();
+            for i in 0..length {
+                let element = self.load(base + 8 * (i + 1))?;
+                elements.push(self.print_value(element)?);
+            }
+            Ok(format!("[{}]", elements.join(" ")))
+        } else {
+            Ok(format!("BAD VALUE: {}", value))
+        }
+    }
+
+    /// What the runtime's `main` writes: the printed result and a newline, or the error text
+    pub fn output(&mut self, exit_code: i32) -> Result<Output, String> {
+        let mut stdout = self.stdout.clone();
+        if exit_code == 0 {
+            stdout.push_str(&self.print_value(self.regs[0])?);
+            stdout.push('\n');
+        }
+        Ok(Output { stdout, exit_code })
+    }
+}
+
+fn is_float(value: u64) -> bool {
+    value as i64 & HEAP_MASK == FLOAT_TAG
+}
+
+fn bool_value(b: bool) -> u64 {
+    ((b as u64) << BOOL_SHIFT) | BOOL_TAG as u64
+}
+
+/// Default instruction budget for `simulate`
+pub const FUEL: u64 = 10_000_000;
+
+/// Runs a compiled program the way the C runtime's `main` would
+pub fn simulate(directives: &[Directive]) -> Result<Output, String> {
+    let mut machine = Machine::new(directives, HEAP_SIZE)?;
+    let exit_code = machine.run(FUEL)?;
+    machine.output(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Expr;
+    use crate::compile::compile;
+    use crate::eval::{Interpreter, Value};
+
+    /// Whether `source` returns a bignum, compiled and simulated, and interpreted
+    fn returns_big(source: &str) -> (bool, bool) {
+        let expr = crate::parse(source).unwrap();
+        let mut machine = Machine::new(&compile(expr.clone()).unwrap(), HEAP_SIZE).unwrap();
+        assert_eq!(machine.run(FUEL), Ok(0));
+        let result = machine.reg(&Register::X0) as i64;
+        let compiled = match result {
+            r if r & NUM_MASK == NUM_TAG => false,
+            r if r & HEAP_MASK == BIG_TAG => true,
+            r => panic!("{} returned {:#x}, which is no number", source, r),
+        };
+        let interpreted = match Interpreter::new().eval(&expr).unwrap() {
+            Value::Num(_) => false,
+            Value::Big(_) => true,
+            v => panic!("{} returned {}, which is no number", source, v),
+        };
+        (compiled, interpreted)
+    }
+
+    #[test]
+    fn promotion_and_demotion_at_the_fixnum_boundary() {
+        let cases = [
+            ("2305843009213693951", false),
+            ("2305843009213693951 + 1", true),
+            ("add1(2305843009213693951)", true),
+            ("-2305843009213693952 - 1", true),
+            ("sub1(-2305843009213693952)", true),
+            ("let big = 2305843009213693951 + 1 in big - 1", false),
+            ("let big = sub1(-2305843009213693952) in add1(big)", false),
+            (
+                "let big = 2305843009213693951 + 2305843009213693951 in big - big",
+                false,
+            ),
+        ];
+        for (source, big) in cases {
+            assert_eq!(returns_big(source), (big, big), "{}", source);
+        }
+    }
+
+    /// A list of `length` pairs built with `garbage` discarded pairs around each one
+    fn churn(length: usize, garbage: usize) -> Expr {
+        let mut source = "let l0 = pair(0, {}) in\n".to_string();
+        for i in 1..=length {
+            let waste = "pair(1, 2); ".repeat(garbage);
+            source.push_str(&format!(
+                "let l{i} = {{ {waste}pair({i}, {{ {waste}l{} }}) }} in\n",
+                i - 1
+            ));
+        }
+        source.push_str(&format!("l{}", length));
+        crate::parse(&source).unwrap()
+    }
+
+    fn run_with_heap(expr: &Expr, opt_level: u8, heap_size: u64) -> Output {
+        let options = crate::Options {
+            opt_level,
+            ..crate::Options::default()
+        };
+        let directives = crate::compile::compile_with(expr.clone(), &options).unwrap();
+        let mut machine = Machine::new(&directives, heap_size).unwrap();
+        let exit_code = machine.run(FUEL).unwrap();
+        machine.output(exit_code).unwrap()
+    }
+
+    /// Allocating several times the heap while a list stays live collects again and again and
+    /// keeps the list intact
+    #[test]
+    fn collection_keeps_live_data() {
+        let expr = churn(100, 4);
+        let expected = crate::eval::run(&expr).unwrap();
+        assert!(expected.stdout.starts_with("(pair 100 (pair 99 "));
+        // 100 live pairs of 16 bytes fit in 2 KiB, the 900 allocated in all at -O0 do not
+        for opt_level in [0, 1] {
+            assert_eq!(run_with_heap(&expr, opt_level, 2048), expected);
+        }
+    }
+
+    #[test]
+    fn out_of_memory_when_live_data_does_not_fit() {
+        let output = run_with_heap(&churn(100, 0), 0, 1024);
+        assert_eq!(output.stdout, "Stuck[out of memory]");
+        assert_eq!(output.exit_code, 1);
+    }
+}
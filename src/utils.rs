@@ -1,8 +1,15 @@
 use std::sync::atomic::AtomicUsize;
 
+use crate::asm::Register;
+
 static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub fn gensym(prefix: &str) -> String {
     let count = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     format!("{}_{}", prefix, count)
 }
+
+/// A virtual register no other code uses
+pub fn fresh_register() -> Register {
+    Register::Virtual(COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+}
@@ -0,0 +1,106 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn asm(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_asm"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn sample(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("samples")
+        .join(name);
+    path.display().to_string()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// Each command reaches its own handler
+#[test]
+fn subcommands_dispatch() {
+    let example = sample("example1.lisp");
+
+    let help = asm(&["help"]);
+    assert_eq!(help.status.code(), Some(0));
+    assert!(stdout(&help).starts_with("Usage: asm <command>"));
+
+    let check = asm(&["check", &example]);
+    assert_eq!(check.status.code(), Some(0), "{}", stderr(&check));
+    assert_eq!(stdout(&check), "");
+
+    let emitted = asm(&["emit-asm", &example]);
+    assert_eq!(emitted.status.code(), Some(0), "{}", stderr(&emitted));
+    assert!(stdout(&emitted).contains("ret"));
+
+    let compiled = asm(&["run", "-O2", &example]);
+    let interpreted = asm(&["run", "--interp", &example]);
+    assert_eq!(compiled.status.code(), Some(0), "{}", stderr(&compiled));
+    assert_eq!(stdout(&compiled), stdout(&interpreted));
+
+    let tested = asm(&["test", &sample("basics.in")]);
+    assert_eq!(tested.status.code(), Some(0), "{}", stdout(&tested));
+}
+
+/// Unknown commands and options are usage errors, which exit with 2 and print the usage
+#[test]
+fn usage_errors() {
+    let example = sample("example1.lisp");
+    for args in [
+        vec![],
+        vec!["frobnicate"],
+        vec!["run", "--frobnicate", &example],
+        vec!["run", "-O4", &example],
+        vec!["build", &example, &example],
+        vec!["test", "--frobnicate"],
+        vec!["difftest", "--frobnicate"],
+        vec!["fmt", "--frobnicate", &example],
+        vec!["fmt", "-w", &example],
+    ] {
+        let output = asm(&args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(
+            stderr(&output).contains("Usage: asm <command>"),
+            "{:?}",
+            args
+        );
+    }
+}
+
+/// The language server speaks framed JSON-RPC over stdio and exits cleanly after `shutdown`
+#[test]
+fn language_server_over_stdio() {
+    let input: String = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ]
+    .iter()
+    .map(|body| format!("Content-Length: {}\r\n\r\n{}", body.len(), body))
+    .collect();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_asm-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let replies = stdout(&output);
+    assert!(replies.starts_with("Content-Length: "), "{}", replies);
+    assert!(replies.contains(r#""serverInfo":{"name":"asm-lsp"}"#));
+    assert!(replies.ends_with(r#"{"jsonrpc":"2.0","id":2,"result":null}"#));
+}
@@ -0,0 +1,48 @@
+use asm::asm::Target;
+use asm::{analyze, compile, emit_for, parse, sim, Options};
+
+/// Source to assembly through the public functions alone, for every target
+#[test]
+fn source_to_assembly_for_each_target() {
+    let source = "let x = 5 in pair(add1(x), x)";
+    let expr = parse(source).unwrap();
+    let (analyzed, diagnostics) = analyze(source);
+    assert!(diagnostics.0.is_empty());
+    assert_eq!(analyzed.unwrap().to_string(), expr.to_string());
+
+    for (target, prefix) in [(Target::Linux, ""), (Target::MacOs, "_")] {
+        let options = Options {
+            target,
+            ..Options::default()
+        };
+        let directives = compile(&expr, &options).unwrap();
+        assert_eq!(sim::simulate(&directives).unwrap().stdout, "(pair 6 5)\n");
+
+        let assembly = emit_for(&directives, target);
+        let lines: Vec<&str> = assembly.lines().collect();
+        assert_eq!(lines[0], format!(".global {}lisp_entry", prefix));
+        assert!(
+            lines.contains(&format!("{}lisp_entry:", prefix).as_str()),
+            "{}",
+            assembly
+        );
+        assert!(lines.contains(&format!(".extern {}lisp_error", prefix).as_str()));
+        assert!(assembly.ends_with('\n'));
+    }
+}
+
+/// Problems come back as diagnostics instead of a tree
+#[test]
+fn errors_are_diagnostics() {
+    assert!(parse("let x = in x").is_err());
+    let (expr, diagnostics) = analyze("let x = 1 in y");
+    assert!(expr.is_some());
+    let messages: Vec<String> = diagnostics.0.iter().map(|d| d.to_string()).collect();
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.starts_with("error") && m.contains('y')),
+        "{:?}",
+        messages
+    );
+}
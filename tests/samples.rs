@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use asm::corpus::{self, Outcome};
+use asm::{eval, sim};
+
+fn samples() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("samples");
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "lisp"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Every sample prints the same when compiled, at each optimization setting, and simulated as
+/// when interpreted
+#[test]
+fn compiled_samples_print_what_the_interpreter_does() {
+    let files = samples();
+    assert!(!files.is_empty());
+    for path in files {
+        let source = fs::read_to_string(&path).unwrap();
+        let expr = asm::parse(&source).unwrap();
+        let expected = eval::run(&expr).unwrap();
+        for (opt_level, peephole) in [(0, false), (0, true), (1, true)] {
+            let options = asm::Options {
+                opt_level,
+                peephole,
+                ..asm::Options::default()
+            };
+            let directives = asm::compile(&expr, &options).unwrap();
+            let output = sim::simulate(&directives).unwrap();
+            assert_eq!(
+                output,
+                expected,
+                "{} at -O{}{}",
+                path.display(),
+                opt_level,
+                if peephole { "" } else { " --no-peephole" }
+            );
+        }
+    }
+}
+
+#[test]
+fn example1_prints_the_last_value() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("samples/example1.lisp");
+    let expr = asm::parse(&fs::read_to_string(path).unwrap()).unwrap();
+    let directives = asm::compile(&expr, &asm::Options::default()).unwrap();
+    let output = sim::simulate(&directives).unwrap();
+    assert_eq!((output.stdout.as_str(), output.exit_code), ("2\n", 0));
+}
+
+/// Every case of the corpora in samples that is written in this syntax passes, compiled and
+/// interpreted
+#[test]
+fn corpus_cases_pass() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("samples");
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "in"))
+        .collect();
+    files.sort();
+    let mut passed = 0;
+    for path in files {
+        let cases = corpus::parse_corpus(&fs::read_to_string(&path).unwrap()).unwrap();
+        for case in &cases {
+            for interp in [false, true] {
+                match corpus::run_case(case, Duration::from_secs(10), interp) {
+                    Outcome::Pass => passed += 1,
+                    Outcome::Unparseable => {}
+                    outcome => panic!(
+                        "{} {}{}: {:?}",
+                        path.display(),
+                        case.name,
+                        if interp { " (interpreted)" } else { "" },
+                        outcome
+                    ),
+                }
+            }
+        }
+    }
+    assert!(passed > 0);
+}